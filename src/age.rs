@@ -0,0 +1,115 @@
+//! Serialization of ages as the timestamps they started at.
+//!
+//! Some formats record when something happened, but code only cares how long
+//! ago that was, as for cache entries or heartbeats. This module deserializes
+//! an RFC 3339 timestamp into the `Duration` elapsed since it, and serializes
+//! a `Duration` as the timestamp that long before the current time. Its
+//! `serialize` and `deserialize` functions use the system clock and can be
+//! used with `serde_derive`'s `with` annotations. Other clocks can be used
+//! through `serialize_with_clock` and `deserialize_with_clock`.
+//!
+//! Timestamps after the current time, as from a skewed clock, have an age of
+//! zero.
+//!
+//! # Examples
+//!
+//! ```
+//! extern crate serde_humantime;
+//! extern crate serde;
+//! #[macro_use]
+//! extern crate serde_derive;
+//!
+//! use std::time::Duration;
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Heartbeat {
+//!     #[serde(with = "serde_humantime::age")]
+//!     last_seen: Duration,
+//! }
+//!
+//! # fn main() {}
+//! ```
+#[cfg(feature = "de")]
+use serde::de::Deserializer;
+#[cfg(feature = "ser")]
+use serde::ser::{self, Serializer};
+use std::time::Duration;
+#[cfg(feature = "de")]
+use std::time::SystemTime;
+
+use clock::{Clock, SystemClock};
+
+/// Serializes an age as the timestamp that long before the current time of
+/// the system clock.
+#[cfg(feature = "ser")]
+pub fn serialize<S>(d: &Duration, s: S) -> Result<S::Ok, S::Error>
+    where S: Serializer
+{
+    serialize_with_clock(d, s, &SystemClock)
+}
+
+/// Serializes an age as the timestamp that long before the current time of a
+/// clock.
+#[cfg(feature = "ser")]
+pub fn serialize_with_clock<S, C>(d: &Duration, s: S, clock: &C) -> Result<S::Ok, S::Error>
+    where S: Serializer,
+          C: Clock + ?Sized
+{
+    let t = clock.now()
+        .checked_sub(*d)
+        .ok_or_else(|| ser::Error::custom("age out of range"))?;
+    ::serialize(&t, s)
+}
+
+/// Deserializes an RFC 3339 timestamp into the time elapsed since it
+/// according to the system clock.
+#[cfg(feature = "de")]
+pub fn deserialize<'de, D>(d: D) -> Result<Duration, D::Error>
+    where D: Deserializer<'de>
+{
+    deserialize_with_clock(d, &SystemClock)
+}
+
+/// Deserializes an RFC 3339 timestamp into the time elapsed since it
+/// according to a clock.
+#[cfg(feature = "de")]
+pub fn deserialize_with_clock<'de, D, C>(d: D, clock: &C) -> Result<Duration, D::Error>
+    where D: Deserializer<'de>,
+          C: Clock + ?Sized
+{
+    let t = ::deserialize::<SystemTime, D>(d)?;
+    Ok(clock.now().duration_since(t).unwrap_or(Duration::from_secs(0)))
+}
+
+#[cfg(all(test, feature = "ser", feature = "de"))]
+mod test {
+    use serde::de::IntoDeserializer;
+    use serde::de::value::{Error, StrDeserializer};
+    use serde_json;
+    use std::time::UNIX_EPOCH;
+
+    use clock::FixedClock;
+    use super::*;
+
+    fn de(s: &str) -> StrDeserializer<'_, Error> {
+        s.into_deserializer()
+    }
+
+    #[test]
+    fn round_trip() {
+        let clock = FixedClock::new(UNIX_EPOCH + Duration::from_secs(1518563312));
+
+        let age = deserialize_with_clock(de("2018-02-13T22:08:32Z"), &clock).unwrap();
+        assert_eq!(age, Duration::from_secs(3600));
+        let age = deserialize_with_clock(de("2018-02-14T00:00:00Z"), &clock).unwrap();
+        assert_eq!(age, Duration::from_secs(0));
+        assert!(deserialize_with_clock(de("an hour ago"), &clock).is_err());
+
+        let mut buf = vec![];
+        serialize_with_clock(&Duration::from_secs(3600), &mut serde_json::Serializer::new(&mut buf), &clock)
+            .unwrap();
+        assert_eq!(buf, br#""2018-02-13T22:08:32Z""#);
+        let err = serialize_with_clock(&Duration::from_secs(u64::MAX), &mut serde_json::Serializer::new(vec![]), &clock);
+        assert!(err.is_err());
+    }
+}
@@ -0,0 +1,120 @@
+//! Parsing of batches of durations and timestamps.
+//!
+//! Every value of a batch is parsed even if some fail, and the failures are
+//! reported along with their indices so that all of the offending records
+//! can be tracked down in one pass.
+//!
+//! # Examples
+//!
+//! ```
+//! use serde_humantime::batch::parse_all_durations;
+//! use std::time::Duration;
+//!
+//! let durations = parse_all_durations(vec!["1h", "30m"]).unwrap();
+//! assert_eq!(durations, [Duration::from_secs(3600), Duration::from_secs(1800)]);
+//!
+//! let err = parse_all_durations(vec!["1h", "bogus", "30m", "1 fortnight"]).unwrap_err();
+//! let indices = err.errors().iter().map(|&(i, _)| i).collect::<Vec<_>>();
+//! assert_eq!(indices, [1, 3]);
+//! ```
+use std::error;
+use std::fmt;
+use std::time::Duration;
+#[cfg(feature = "systemtime")]
+use std::time::SystemTime;
+
+use parse;
+use Error;
+
+/// An error parsing a batch of values.
+#[derive(Debug, Clone)]
+pub struct BatchError {
+    errors: Vec<(usize, Error)>,
+}
+
+impl BatchError {
+    pub(crate) fn new(errors: Vec<(usize, Error)>) -> BatchError {
+        BatchError { errors }
+    }
+
+    /// Returns the indices of the values which failed to parse and their
+    /// errors, in order of index.
+    pub fn errors(&self) -> &[(usize, Error)] {
+        &self.errors
+    }
+
+    /// Consumes the `BatchError`, returning the indexed errors.
+    pub fn into_errors(self) -> Vec<(usize, Error)> {
+        self.errors
+    }
+}
+
+impl fmt::Display for BatchError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        let (index, ref error) = self.errors[0];
+        write!(fmt, "{} values failed to parse, the first at index {}: {}", self.errors.len(), index, error)
+    }
+}
+
+impl error::Error for BatchError {}
+
+/// Parses a batch of durations.
+///
+/// Returns the durations in the order of their inputs, or the errors of
+/// every input which failed to parse.
+pub fn parse_all_durations<I>(iter: I) -> Result<Vec<Duration>, BatchError>
+    where I: IntoIterator,
+          I::Item: AsRef<str>
+{
+    parse_all(iter, parse::parse_duration)
+}
+
+/// Parses a batch of RFC 3339-like timestamps.
+///
+/// Returns the timestamps in the order of their inputs, or the errors of
+/// every input which failed to parse.
+#[cfg(feature = "systemtime")]
+pub fn parse_all_timestamps<I>(iter: I) -> Result<Vec<SystemTime>, BatchError>
+    where I: IntoIterator,
+          I::Item: AsRef<str>
+{
+    parse_all(iter, parse::parse_rfc3339_weak)
+}
+
+fn parse_all<I, T, P>(iter: I, parse: P) -> Result<Vec<T>, BatchError>
+    where I: IntoIterator,
+          I::Item: AsRef<str>,
+          P: Fn(&str) -> Result<T, Error>
+{
+    let mut values = vec![];
+    let mut errors = vec![];
+    for (i, s) in iter.into_iter().enumerate() {
+        match parse(s.as_ref()) {
+            Ok(value) => values.push(value),
+            Err(e) => errors.push((i, e)),
+        }
+    }
+
+    if !errors.is_empty() {
+        return Err(BatchError::new(errors));
+    }
+    Ok(values)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn durations() {
+        let lines = "1h\n90s\n\n2 days\n1 eon";
+        let err = parse_all_durations(lines.lines()).unwrap_err();
+        assert_eq!(err.errors().iter().map(|&(i, _)| i).collect::<Vec<_>>(), [2, 4]);
+        assert!(err.to_string().starts_with("2 values failed to parse, the first at index 2: "));
+
+        let durations = parse_all_durations(lines.lines().filter(|l| !l.is_empty()).take(3)).unwrap();
+        assert_eq!(durations,
+                   [Duration::from_secs(3600), Duration::from_secs(90), Duration::from_secs(172800)]);
+        assert!(parse_all_durations(Vec::<String>::new()).unwrap().is_empty());
+    }
+}
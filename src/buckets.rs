@@ -0,0 +1,279 @@
+//! Histogram bucket specifications.
+#[cfg(feature = "de")]
+use serde::de::{Deserialize, Deserializer, Visitor, Error, Unexpected};
+#[cfg(feature = "ser")]
+use serde::ser::{Serialize, Serializer};
+#[cfg(feature = "de")]
+use std::error;
+#[cfg(any(feature = "ser", feature = "de"))]
+use std::fmt;
+#[cfg(feature = "de")]
+use std::str::FromStr;
+use std::time::Duration;
+
+#[cfg(feature = "ser")]
+use format;
+#[cfg(feature = "de")]
+use parse;
+
+/// The largest number of bucket boundaries a specification may have.
+pub const MAX_COUNT: usize = 10_000;
+
+/// The spacing of bucket boundaries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scale {
+    /// Boundaries are evenly spaced.
+    Linear,
+    /// Boundaries are spaced by a constant ratio.
+    Log,
+}
+
+/// A compact specification of histogram bucket boundaries.
+///
+/// Specifications have the form `<start>..<end> x<count> [linear|log]`, where
+/// `start` and `end` are humantime durations and the scale defaults to
+/// `linear`. For example, `1ms..1s x4 log` describes the boundaries `1ms`,
+/// `10ms`, `100ms` and `1s`.
+///
+/// It is serialized and deserialized as a specification string.
+///
+/// # Examples
+///
+/// ```
+/// extern crate serde_humantime;
+///
+/// use serde_humantime::buckets::Buckets;
+/// use std::time::Duration;
+///
+/// # fn main() {
+/// let buckets = "1ms..1s x4 log".parse::<Buckets>().unwrap();
+/// assert_eq!(buckets.durations(),
+///            [Duration::from_millis(1),
+///             Duration::from_millis(10),
+///             Duration::from_millis(100),
+///             Duration::from_secs(1)]);
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Buckets {
+    start: Duration,
+    end: Duration,
+    count: usize,
+    scale: Scale,
+}
+
+impl Buckets {
+    /// Creates a new bucket specification.
+    ///
+    /// Returns `None` if `start` is greater than `end`, if fewer than two
+    /// buckets are requested for a nonempty range, if more than `MAX_COUNT`
+    /// are requested, or if a logarithmic scale starts at zero.
+    pub fn new(start: Duration, end: Duration, count: usize, scale: Scale) -> Option<Buckets> {
+        if start > end || count == 0 || count > MAX_COUNT || (count < 2 && start != end) ||
+           (scale == Scale::Log && start == Duration::from_secs(0)) {
+            return None;
+        }
+        Some(Buckets {
+            start,
+            end,
+            count,
+            scale,
+        })
+    }
+
+    /// Returns the first bucket boundary.
+    pub fn start(&self) -> Duration {
+        self.start
+    }
+
+    /// Returns the last bucket boundary.
+    pub fn end(&self) -> Duration {
+        self.end
+    }
+
+    /// Returns the number of bucket boundaries.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Returns the spacing of the bucket boundaries.
+    pub fn scale(&self) -> Scale {
+        self.scale
+    }
+
+    /// Returns the bucket boundaries in ascending order.
+    ///
+    /// Boundaries are rounded to the nearest nanosecond, but the first and
+    /// last are always exactly `start` and `end`.
+    pub fn durations(&self) -> Vec<Duration> {
+        if self.count == 1 {
+            return vec![self.start];
+        }
+
+        let start = self.start.as_nanos();
+        let end = self.end.as_nanos();
+        let steps = (self.count - 1) as u128;
+        let mut durations = (0..self.count as u128)
+            .map(|i| {
+                let nanos = match self.scale {
+                    Scale::Linear => start + (end - start) * i / steps,
+                    Scale::Log => {
+                        let ratio = (end as f64 / start as f64).powf(i as f64 / steps as f64);
+                        (start as f64 * ratio).round() as u128
+                    }
+                };
+                from_nanos(nanos)
+            })
+            .collect::<Vec<_>>();
+        durations[self.count - 1] = self.end;
+        durations
+    }
+}
+
+fn from_nanos(nanos: u128) -> Duration {
+    Duration::new((nanos / 1_000_000_000) as u64, (nanos % 1_000_000_000) as u32)
+}
+
+#[cfg(feature = "ser")]
+impl fmt::Display for Buckets {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt,
+               "{}..{} x{}",
+               format::format_duration(self.start),
+               format::format_duration(self.end),
+               self.count)?;
+        if self.scale == Scale::Log {
+            fmt.write_str(" log")?;
+        }
+        Ok(())
+    }
+}
+
+/// An error parsing a bucket specification.
+#[derive(Debug)]
+#[cfg(feature = "de")]
+pub struct ParseBucketsError(());
+
+#[cfg(feature = "de")]
+impl fmt::Display for ParseBucketsError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str("invalid bucket specification")
+    }
+}
+
+#[cfg(feature = "de")]
+impl error::Error for ParseBucketsError {}
+
+#[cfg(feature = "de")]
+impl FromStr for Buckets {
+    type Err = ParseBucketsError;
+
+    fn from_str(s: &str) -> Result<Buckets, ParseBucketsError> {
+        parse(s).ok_or(ParseBucketsError(()))
+    }
+}
+
+#[cfg(feature = "de")]
+fn parse(s: &str) -> Option<Buckets> {
+    let idx = s.find("..")?;
+    let start = parse::parse_duration(&s[..idx]).ok()?;
+    let rest = &s[idx + 2..];
+
+    let idx = rest.rfind(" x")?;
+    let end = parse::parse_duration(&rest[..idx]).ok()?;
+    let mut parts = rest[idx + 2..].split_whitespace();
+
+    let count = parts.next()?.parse().ok()?;
+    let scale = match parts.next() {
+        None | Some("linear") => Scale::Linear,
+        Some("log") => Scale::Log,
+        Some(_) => return None,
+    };
+    if parts.next().is_some() {
+        return None;
+    }
+
+    Buckets::new(start, end, count, scale)
+}
+
+#[cfg(feature = "ser")]
+impl Serialize for Buckets {
+    fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        s.collect_str(self)
+    }
+}
+
+#[cfg(feature = "de")]
+impl<'de> Deserialize<'de> for Buckets {
+    fn deserialize<D>(d: D) -> Result<Buckets, D::Error>
+        where D: Deserializer<'de>
+    {
+        struct V;
+
+        impl<'de2> Visitor<'de2> for V {
+            type Value = Buckets;
+
+            fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+                fmt.write_str("a bucket specification")
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Buckets, E>
+                where E: Error
+            {
+                v.parse().map_err(|_| E::invalid_value(Unexpected::Str(v), &self))
+            }
+        }
+
+        d.deserialize_str(V)
+    }
+}
+
+#[cfg(all(test, feature = "ser", feature = "de"))]
+mod test {
+    use serde_json;
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn linear() {
+        let buckets = "0s..1s x5".parse::<Buckets>().unwrap();
+        assert_eq!(buckets.durations(),
+                   [Duration::from_millis(0),
+                    Duration::from_millis(250),
+                    Duration::from_millis(500),
+                    Duration::from_millis(750),
+                    Duration::from_millis(1000)]);
+        assert_eq!("0s..1s x5 linear".parse::<Buckets>().unwrap(), buckets);
+    }
+
+    #[test]
+    fn log() {
+        let buckets = "1ms..1s x10 log".parse::<Buckets>().unwrap();
+        let durations = buckets.durations();
+        assert_eq!(durations.len(), 10);
+        assert_eq!(durations[0], Duration::from_millis(1));
+        assert_eq!(durations[3], Duration::from_millis(10));
+        assert_eq!(durations[9], Duration::from_secs(1));
+        assert!(durations.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[test]
+    fn invalid() {
+        for s in &["1s..1ms x3", "1ms..1s", "1ms..1s x1", "0s..1s x3 log", "1ms..1s x3 cubic",
+                   "1ms 1s x3", "1ms..1s x3 log extra", "1ms..1s xten",
+                   "1ms..1s x10001", "1ms..1s x9999999999999999"] {
+            assert!(s.parse::<Buckets>().is_err(), "{}", s);
+        }
+        assert_eq!("1ms..1s x10000".parse::<Buckets>().unwrap().count(), MAX_COUNT);
+    }
+
+    #[test]
+    fn serde() {
+        let buckets = serde_json::from_str::<Buckets>(r#""1ms..1s x10 log""#).unwrap();
+        assert_eq!(serde_json::to_string(&buckets).unwrap(), r#""1ms..1s x10 log""#);
+        assert!(serde_json::from_str::<Buckets>(r#""1ms..1s""#).is_err());
+    }
+}
@@ -0,0 +1,293 @@
+//! Calendar-aware durations.
+#[cfg(feature = "de")]
+use serde::de::{Deserialize, Deserializer, Visitor, Error, Unexpected};
+#[cfg(feature = "ser")]
+use serde::ser::{Serialize, Serializer};
+#[cfg(feature = "de")]
+use std::error;
+#[cfg(any(feature = "ser", feature = "de"))]
+use std::fmt;
+#[cfg(feature = "de")]
+use std::str::FromStr;
+use std::time::Duration;
+#[cfg(feature = "systemtime")]
+use std::time::SystemTime;
+
+#[cfg(feature = "systemtime")]
+use civil;
+#[cfg(feature = "ser")]
+use format;
+#[cfg(feature = "de")]
+use parse;
+
+/// A duration which keeps months and years symbolic.
+///
+/// `humantime` treats a month as 30.44 days and a year as 365.25 days, which
+/// is wrong for things like billing periods. A `CalendarDuration` instead
+/// tracks a number of calendar months alongside an exact `Duration`, and is
+/// only converted to an exact `Duration` given an anchor time. Months are
+/// added in UTC, and the day of the month is clamped to the length of the
+/// resulting month, so one month after January 31st is the last day of
+/// February.
+///
+/// It is parsed from and formatted to strings using `humantime`'s units, and
+/// is serialized and deserialized as such a string.
+///
+/// # Examples
+///
+/// ```
+/// extern crate serde_humantime;
+///
+/// use serde_humantime::calendar::CalendarDuration;
+/// use std::time::{Duration, UNIX_EPOCH};
+///
+/// # fn main() {
+/// let duration = "1month 1day".parse::<CalendarDuration>().unwrap();
+/// // 1970-02-01 + 1 month + 1 day
+/// let anchor = UNIX_EPOCH + Duration::from_secs(31 * 86400);
+/// assert_eq!(duration.resolve(anchor), Some(Duration::from_secs(29 * 86400)));
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct CalendarDuration {
+    months: u32,
+    exact: Duration,
+}
+
+impl CalendarDuration {
+    /// Creates a new `CalendarDuration` from a number of calendar months and
+    /// an exact duration.
+    pub fn new(months: u32, exact: Duration) -> CalendarDuration {
+        CalendarDuration { months, exact }
+    }
+
+    /// Returns the number of calendar months, including those of whole years.
+    pub fn months(&self) -> u32 {
+        self.months
+    }
+
+    /// Returns the exact part of the duration.
+    pub fn exact(&self) -> Duration {
+        self.exact
+    }
+
+    /// Returns the time this duration after `anchor`.
+    ///
+    /// Returns `None` if the result can't be represented.
+    #[cfg(feature = "systemtime")]
+    pub fn add_to(&self, anchor: SystemTime) -> Option<SystemTime> {
+        let (secs, nanos) = civil::to_unix(anchor);
+        let days = secs.div_euclid(civil::SECS_PER_DAY);
+        let secs_of_day = secs.rem_euclid(civil::SECS_PER_DAY);
+
+        let (year, month, day) = civil::civil_from_days(days);
+        let month0 = (month - 1) as i64 + self.months as i64;
+        let year = year + month0.div_euclid(12);
+        let month = month0.rem_euclid(12) as u32 + 1;
+        let day = day.min(civil::days_in_month(year, month));
+
+        let secs = civil::days_from_civil(year, month, day) * civil::SECS_PER_DAY + secs_of_day;
+        civil::from_unix(secs, nanos)?.checked_add(self.exact)
+    }
+
+    /// Returns the exact length of this duration when starting at `anchor`.
+    ///
+    /// Returns `None` if the result can't be represented.
+    #[cfg(feature = "systemtime")]
+    pub fn resolve(&self, anchor: SystemTime) -> Option<Duration> {
+        self.add_to(anchor)?.duration_since(anchor).ok()
+    }
+}
+
+impl From<Duration> for CalendarDuration {
+    fn from(exact: Duration) -> CalendarDuration {
+        CalendarDuration::new(0, exact)
+    }
+}
+
+#[cfg(feature = "ser")]
+impl fmt::Display for CalendarDuration {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        let years = self.months / 12;
+        let months = self.months % 12;
+        let mut started = false;
+        for &(value, name) in &[(years, "year"), (months, "month")] {
+            if value == 0 {
+                continue;
+            }
+            if started {
+                fmt.write_str(" ")?;
+            }
+            write!(fmt, "{}{}", value, name)?;
+            if value > 1 {
+                fmt.write_str("s")?;
+            }
+            started = true;
+        }
+
+        if self.exact != Duration::from_secs(0) || !started {
+            if started {
+                fmt.write_str(" ")?;
+            }
+            write!(fmt, "{}", format::format_duration(self.exact))?;
+        }
+        Ok(())
+    }
+}
+
+/// An error parsing a `CalendarDuration`.
+#[derive(Debug)]
+#[cfg(feature = "de")]
+pub struct ParseCalendarDurationError(());
+
+#[cfg(feature = "de")]
+impl fmt::Display for ParseCalendarDurationError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str("invalid calendar duration")
+    }
+}
+
+#[cfg(feature = "de")]
+impl error::Error for ParseCalendarDurationError {}
+
+#[cfg(feature = "de")]
+impl FromStr for CalendarDuration {
+    type Err = ParseCalendarDurationError;
+
+    fn from_str(s: &str) -> Result<CalendarDuration, ParseCalendarDurationError> {
+        parse(s).ok_or(ParseCalendarDurationError(()))
+    }
+}
+
+#[cfg(feature = "de")]
+fn parse(s: &str) -> Option<CalendarDuration> {
+    let mut months = 0u32;
+    let mut exact = String::new();
+
+    let mut rest = s.trim_start();
+    if rest.is_empty() {
+        return None;
+    }
+    while !rest.is_empty() {
+        let end = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+        let number = &rest[..end];
+        rest = rest[end..].trim_start();
+        let end = rest.find(|c: char| !c.is_ascii_alphabetic()).unwrap_or(rest.len());
+        let unit = &rest[..end];
+        rest = rest[end..].trim_start();
+        if number.is_empty() || unit.is_empty() {
+            return None;
+        }
+
+        let per = match unit {
+            "years" | "year" | "y" => 12,
+            "months" | "month" | "M" => 1,
+            _ => {
+                exact.push_str(number);
+                exact.push_str(unit);
+                exact.push(' ');
+                continue;
+            }
+        };
+        let value = number.parse::<u32>().ok()?;
+        months = months.checked_add(value.checked_mul(per)?)?;
+    }
+
+    let exact = if exact.is_empty() {
+        Duration::from_secs(0)
+    } else {
+        parse::parse_duration(&exact).ok()?
+    };
+    Some(CalendarDuration::new(months, exact))
+}
+
+#[cfg(feature = "ser")]
+impl Serialize for CalendarDuration {
+    fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        s.collect_str(self)
+    }
+}
+
+#[cfg(feature = "de")]
+impl<'de> Deserialize<'de> for CalendarDuration {
+    fn deserialize<D>(d: D) -> Result<CalendarDuration, D::Error>
+        where D: Deserializer<'de>
+    {
+        #[cfg(feature = "de")]
+        struct V;
+
+        impl<'de2> Visitor<'de2> for V {
+            type Value = CalendarDuration;
+
+            fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+                fmt.write_str("a calendar duration")
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<CalendarDuration, E>
+                where E: Error
+            {
+                v.parse().map_err(|_| E::invalid_value(Unexpected::Str(v), &self))
+            }
+        }
+
+        d.deserialize_str(V)
+    }
+}
+
+#[cfg(all(test, feature = "ser", feature = "de"))]
+mod test {
+    use serde_json;
+    use std::time::Duration;
+    #[cfg(feature = "systemtime")]
+    use std::time::SystemTime;
+
+    #[cfg(feature = "systemtime")]
+    use civil;
+    use super::*;
+
+    #[cfg(feature = "systemtime")]
+    fn date(year: i64, month: u32, day: u32) -> SystemTime {
+        civil::from_unix(civil::days_from_civil(year, month, day) * 86400, 0).unwrap()
+    }
+
+    #[test]
+    fn parse() {
+        let d = "1y 2months 3d 4h".parse::<CalendarDuration>().unwrap();
+        assert_eq!(d.months(), 14);
+        assert_eq!(d.exact(), Duration::from_secs(3 * 86400 + 4 * 3600));
+        assert_eq!("2 M".parse::<CalendarDuration>().unwrap(),
+                   CalendarDuration::new(2, Duration::from_secs(0)));
+        assert_eq!("15s".parse::<CalendarDuration>().unwrap(),
+                   CalendarDuration::from(Duration::from_secs(15)));
+
+        for s in &["", "month", "1 fortnight", "-1y"] {
+            assert!(s.parse::<CalendarDuration>().is_err(), "{}", s);
+        }
+    }
+
+    #[cfg(feature = "systemtime")]
+    #[test]
+    fn resolve() {
+        let month = CalendarDuration::new(1, Duration::from_secs(0));
+        assert_eq!(month.resolve(date(2024, 1, 15)), Some(Duration::from_secs(31 * 86400)));
+        assert_eq!(month.resolve(date(2024, 2, 15)), Some(Duration::from_secs(29 * 86400)));
+        assert_eq!(month.add_to(date(2023, 1, 31)), Some(date(2023, 2, 28)));
+
+        let year = CalendarDuration::new(12, Duration::from_secs(0));
+        assert_eq!(year.add_to(date(2024, 2, 29)), Some(date(2025, 2, 28)));
+        assert_eq!(year.resolve(date(2023, 6, 1)), Some(Duration::from_secs(366 * 86400)));
+
+        let mixed = CalendarDuration::new(1, Duration::from_secs(86400));
+        assert_eq!(mixed.add_to(date(2023, 12, 31)), Some(date(2024, 2, 1)));
+    }
+
+    #[test]
+    fn serde() {
+        let d = serde_json::from_str::<CalendarDuration>(r#""13 months 1h""#).unwrap();
+        assert_eq!(serde_json::to_string(&d).unwrap(), r#""1year 1month 1h""#);
+        let d = CalendarDuration::default();
+        assert_eq!(serde_json::to_string(&d).unwrap(), r#""0s""#);
+    }
+}
@@ -0,0 +1,244 @@
+//! Serialization of `chrono` types in the format of the crate root.
+//!
+//! This module is only available with the `chrono` feature. Its `datetime`
+//! and `naive` submodules serialize `DateTime<Utc>`s and `NaiveDateTime`s,
+//! taken to be in UTC, like `SystemTime`s, and its `duration` submodule
+//! serializes `chrono::Duration`s like `Duration`s, with a leading `-` for
+//! negative durations. `De<SystemTime>` converts to and from
+//! `DateTime<Utc>`.
+//!
+//! # Examples
+//!
+//! ```
+//! extern crate chrono;
+//! extern crate serde_humantime;
+//! extern crate serde;
+//! #[macro_use]
+//! extern crate serde_derive;
+//!
+//! use chrono::{DateTime, NaiveDateTime, Utc};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Job {
+//!     #[serde(with = "serde_humantime::chrono_compat::datetime")]
+//!     started: DateTime<Utc>,
+//!     #[serde(with = "serde_humantime::chrono_compat::naive")]
+//!     scheduled: NaiveDateTime,
+//!     #[serde(with = "serde_humantime::chrono_compat::duration")]
+//!     skew: chrono::Duration,
+//! }
+//!
+//! # fn main() {}
+//! ```
+#[cfg(feature = "systemtime")]
+use chrono::{DateTime, Utc};
+#[cfg(feature = "systemtime")]
+use std::time::SystemTime;
+
+#[cfg(feature = "systemtime")]
+use De;
+
+/// Serialization of `DateTime<Utc>`s as RFC 3339 timestamps.
+#[cfg(all(feature = "systemtime", any(feature = "ser", feature = "de")))]
+pub mod datetime {
+    use chrono::{DateTime, Utc};
+    #[cfg(feature = "de")]
+    use serde::de::{Deserializer, Error};
+    #[cfg(feature = "ser")]
+    use serde::ser::Serializer;
+    use std::time::SystemTime;
+
+    #[cfg(feature = "de")]
+    use civil;
+
+    /// Serializes a `DateTime<Utc>` as an RFC 3339 timestamp.
+    #[cfg(feature = "ser")]
+    pub fn serialize<S>(t: &DateTime<Utc>, s: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        ::serialize(&SystemTime::from(*t), s)
+    }
+
+    /// Deserializes a `DateTime<Utc>` from an RFC 3339 timestamp.
+    #[cfg(feature = "de")]
+    pub fn deserialize<'de, D>(d: D) -> Result<DateTime<Utc>, D::Error>
+        where D: Deserializer<'de>
+    {
+        // formats which aren't human readable can hold timestamps outside of
+        // chrono's range
+        let (secs, nanos) = civil::to_unix(::deserialize::<SystemTime, D>(d)?);
+        DateTime::from_timestamp(secs, nanos).ok_or_else(|| Error::custom("timestamp is out of range"))
+    }
+}
+
+/// Serialization of `NaiveDateTime`s in UTC as RFC 3339 timestamps.
+#[cfg(all(feature = "systemtime", any(feature = "ser", feature = "de")))]
+pub mod naive {
+    use chrono::NaiveDateTime;
+    #[cfg(feature = "de")]
+    use chrono::{DateTime, Utc};
+    #[cfg(feature = "de")]
+    use serde::de::Deserializer;
+    #[cfg(feature = "ser")]
+    use serde::ser::Serializer;
+
+    /// Serializes a `NaiveDateTime` in UTC as an RFC 3339 timestamp.
+    #[cfg(feature = "ser")]
+    pub fn serialize<S>(t: &NaiveDateTime, s: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        super::datetime::serialize(&t.and_utc(), s)
+    }
+
+    /// Deserializes a `NaiveDateTime` in UTC from an RFC 3339 timestamp.
+    #[cfg(feature = "de")]
+    pub fn deserialize<'de, D>(d: D) -> Result<NaiveDateTime, D::Error>
+        where D: Deserializer<'de>
+    {
+        super::datetime::deserialize(d).map(|t: DateTime<Utc>| t.naive_utc())
+    }
+}
+
+/// Serialization of `chrono::Duration`s as duration strings.
+#[cfg(any(feature = "ser", feature = "de"))]
+pub mod duration {
+    use chrono;
+    #[cfg(feature = "de")]
+    use serde::de::{Deserializer, Visitor, Error, Unexpected};
+    #[cfg(feature = "ser")]
+    use serde::ser::Serializer;
+    #[cfg(feature = "de")]
+    use std::fmt;
+
+    #[cfg(feature = "ser")]
+    use format;
+    #[cfg(feature = "de")]
+    use parse;
+
+    /// Serializes a `chrono::Duration` as a duration string, prefixed with
+    /// `-` if it is negative.
+    #[cfg(feature = "ser")]
+    pub fn serialize<S>(d: &chrono::Duration, s: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        let sign = if *d < chrono::Duration::zero() { "-" } else { "" };
+        // the magnitude of every chrono::Duration fits in a Duration
+        let d = d.abs().to_std().unwrap();
+        s.collect_str(&format_args!("{}{}", sign, format::global_format().format_duration(d)))
+    }
+
+    /// Deserializes a `chrono::Duration` from a duration string, optionally
+    /// prefixed with `-`.
+    #[cfg(feature = "de")]
+    pub fn deserialize<'de, D>(d: D) -> Result<chrono::Duration, D::Error>
+        where D: Deserializer<'de>
+    {
+        d.deserialize_str(V)
+    }
+
+    #[cfg(feature = "de")]
+    struct V;
+
+    #[cfg(feature = "de")]
+    impl<'de> Visitor<'de> for V {
+        type Value = chrono::Duration;
+
+        fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+            fmt.write_str("a duration")
+        }
+
+        fn visit_str<E>(self, v: &str) -> Result<chrono::Duration, E>
+            where E: Error
+        {
+            let (negative, s) = match v.strip_prefix('-') {
+                Some(s) => (true, s),
+                None => (false, v),
+            };
+            let d = parse::parse_duration(s)
+                .ok()
+                .and_then(|d| chrono::Duration::from_std(d).ok())
+                .ok_or_else(|| E::invalid_value(Unexpected::Str(v), &self))?;
+            Ok(if negative { -d } else { d })
+        }
+    }
+}
+
+#[cfg(feature = "systemtime")]
+impl<F> From<De<SystemTime, F>> for DateTime<Utc> {
+    fn from(t: De<SystemTime, F>) -> DateTime<Utc> {
+        DateTime::from(t.into_inner())
+    }
+}
+
+#[cfg(feature = "systemtime")]
+impl From<DateTime<Utc>> for De<SystemTime> {
+    fn from(t: DateTime<Utc>) -> De<SystemTime> {
+        De::new(SystemTime::from(t))
+    }
+}
+
+#[cfg(all(test, feature = "ser", feature = "de"))]
+mod test {
+    use chrono;
+    #[cfg(feature = "systemtime")]
+    use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+    #[cfg(feature = "systemtime")]
+    use bincode;
+    use serde_json;
+    #[cfg(feature = "systemtime")]
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    #[cfg(feature = "systemtime")]
+    use De;
+
+    #[cfg(feature = "systemtime")]
+    #[test]
+    fn datetimes() {
+        #[derive(Serialize, Deserialize)]
+        struct Foo {
+            #[serde(with = "super::datetime")]
+            utc: DateTime<Utc>,
+            #[serde(with = "super::naive")]
+            naive: NaiveDateTime,
+        }
+
+        let naive = NaiveDate::from_ymd_opt(2018, 2, 13).unwrap().and_hms_milli_opt(23, 8, 32, 500).unwrap();
+        let json = r#"{"utc":"2018-02-13T23:08:32.500000000Z","naive":"2018-02-13T23:08:32.500000000Z"}"#;
+        let foo = serde_json::from_str::<Foo>(json).unwrap();
+        assert_eq!((foo.utc, foo.naive), (naive.and_utc(), naive));
+        assert_eq!(serde_json::to_string(&foo).unwrap(), json);
+
+        let de = De::<SystemTime>::from(naive.and_utc());
+        assert_eq!(serde_json::to_string(&de).unwrap(), r#""2018-02-13T23:08:32.500000000Z""#);
+        assert_eq!(DateTime::<Utc>::from(de), naive.and_utc());
+
+        let early = NaiveDate::from_ymd_opt(1969, 12, 31).unwrap().and_hms_opt(0, 0, 0).unwrap();
+        assert!(serde_json::to_string(&Foo { utc: early.and_utc(), naive: early }).is_err());
+
+        let late = UNIX_EPOCH + Duration::from_secs(1 << 62);
+        let bytes = bincode::serialize(&(late, late)).unwrap();
+        assert!(bincode::deserialize::<Foo>(&bytes).is_err());
+    }
+
+    #[test]
+    fn durations() {
+        #[derive(Serialize, Deserialize)]
+        struct Foo {
+            #[serde(with = "super::duration")]
+            d: chrono::Duration,
+        }
+
+        for &(json, secs) in &[(r#"{"d":"1h 30m"}"#, 5400), (r#"{"d":"-15s"}"#, -15), (r#"{"d":"0s"}"#, 0)] {
+            let foo = serde_json::from_str::<Foo>(json).unwrap();
+            assert_eq!(foo.d, chrono::Duration::seconds(secs), "{}", json);
+            assert_eq!(serde_json::to_string(&foo).unwrap(), json);
+        }
+        let foo = Foo { d: chrono::Duration::MIN };
+        let json = serde_json::to_string(&foo).unwrap();
+        assert_eq!(serde_json::from_str::<Foo>(&json).unwrap().d, chrono::Duration::MIN);
+
+        for json in &[r#"{"d":"--1s"}"#, r#"{"d":"1000000000000years"}"#, r#"{"d":15}"#] {
+            assert!(serde_json::from_str::<Foo>(json).is_err(), "{}", json);
+        }
+    }
+}
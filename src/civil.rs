@@ -0,0 +1,224 @@
+//! Conversions between `SystemTime`s and proleptic Gregorian calendar dates.
+#![cfg_attr(not(feature = "systemtime"), allow(dead_code))]
+
+#[cfg(feature = "systemtime")]
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+pub const SECS_PER_DAY: i64 = 86400;
+
+/// Splits a `SystemTime` into whole seconds relative to the Unix epoch
+/// (rounded towards negative infinity) and a nanosecond remainder.
+#[cfg(feature = "systemtime")]
+pub fn to_unix(t: SystemTime) -> (i64, u32) {
+    match t.duration_since(UNIX_EPOCH) {
+        Ok(d) => (d.as_secs() as i64, d.subsec_nanos()),
+        Err(e) => {
+            let d = e.duration();
+            let secs = -(d.as_secs() as i64);
+            match d.subsec_nanos() {
+                0 => (secs, 0),
+                nanos => (secs - 1, 1_000_000_000 - nanos),
+            }
+        }
+    }
+}
+
+/// The inverse of `to_unix`, returning `None` if the value can't be
+/// represented on this platform.
+#[cfg(feature = "systemtime")]
+pub fn from_unix(secs: i64, nanos: u32) -> Option<SystemTime> {
+    if secs >= 0 {
+        UNIX_EPOCH.checked_add(Duration::new(secs as u64, nanos))
+    } else {
+        UNIX_EPOCH
+            .checked_sub(Duration::from_secs(secs.unsigned_abs()))?
+            .checked_add(Duration::new(0, nanos))
+    }
+}
+
+/// Returns the number of days since 1970-01-01 of the given date.
+pub fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year.rem_euclid(400);
+    let mp = (month as i64 + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+/// Returns the `(year, month, day)` of the given number of days since
+/// 1970-01-01.
+pub fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+pub fn is_leap_year(year: i64) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+pub fn days_in_month(year: i64, month: u32) -> u32 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// A date and time without a timezone.
+#[cfg(feature = "de")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DateTime {
+    pub year: i64,
+    pub month: u32,
+    pub day: u32,
+    pub hour: u32,
+    pub minute: u32,
+    pub second: u32,
+    pub nanos: u32,
+}
+
+#[cfg(feature = "de")]
+fn digits(s: &str) -> Option<u32> {
+    if !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit()) {
+        s.parse().ok()
+    } else {
+        None
+    }
+}
+
+/// Parses a date and time of the form `YYYY-MM-DD[T ]HH:MM[:SS[.fff]]`.
+#[cfg(feature = "de")]
+pub fn parse_datetime(s: &str) -> Option<DateTime> {
+    if s.len() < 16 || !s.is_char_boundary(10) || !s.is_char_boundary(11) {
+        return None;
+    }
+    let (date, time) = (&s[..10], &s[11..]);
+    let b = s.as_bytes();
+    if b[4] != b'-' || b[7] != b'-' || (b[10] != b'T' && b[10] != b' ') {
+        return None;
+    }
+    let year = digits(&date[..4])? as i64;
+    let month = digits(&date[5..7])?;
+    let day = digits(&date[8..])?;
+    if month == 0 || month > 12 || day == 0 || day > days_in_month(year, month) {
+        return None;
+    }
+
+    let (time, fraction) = match time.find('.') {
+        Some(idx) => (&time[..idx], Some(&time[idx + 1..])),
+        None => (time, None),
+    };
+    let mut parts = time.split(':');
+    let hour = parts.next().filter(|p| p.len() == 2).and_then(digits)?;
+    let minute = parts.next().filter(|p| p.len() == 2).and_then(digits)?;
+    let second = match parts.next() {
+        Some(p) if p.len() == 2 => digits(p)?,
+        Some(_) => return None,
+        None if fraction.is_none() => 0,
+        None => return None,
+    };
+    if parts.next().is_some() || hour > 23 || minute > 59 || second > 59 {
+        return None;
+    }
+    let nanos = match fraction {
+        Some(f) if f.len() <= 9 => digits(f)? * 10u32.pow(9 - f.len() as u32),
+        Some(_) => return None,
+        None => 0,
+    };
+
+    Some(DateTime {
+        year,
+        month,
+        day,
+        hour,
+        minute,
+        second,
+        nanos,
+    })
+}
+
+/// Splits a trailing `Z` or `+HH:MM` UTC offset off of a timestamp,
+/// returning the remainder and the offset in seconds.
+#[cfg(feature = "de")]
+pub fn split_offset(s: &str) -> Option<(&str, i32)> {
+    if let Some(rest) = s.strip_suffix('Z') {
+        return Some((rest, 0));
+    }
+    if s.len() < 6 || !s.is_char_boundary(s.len() - 6) {
+        return None;
+    }
+    let (rest, offset) = s.split_at(s.len() - 6);
+    let b = offset.as_bytes();
+    let sign = match b[0] {
+        b'+' => 1,
+        b'-' => -1,
+        _ => return None,
+    };
+    if b[3] != b':' {
+        return None;
+    }
+    let hours = digits(&offset[1..3])?;
+    let minutes = digits(&offset[4..])?;
+    if hours > 23 || minutes > 59 {
+        return None;
+    }
+    Some((rest, sign * (hours * 3600 + minutes * 60) as i32))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn civil_round_trip() {
+        assert_eq!(days_from_civil(1970, 1, 1), 0);
+        assert_eq!(days_from_civil(2000, 3, 1), 11017);
+        assert_eq!(civil_from_days(-1), (1969, 12, 31));
+        for days in -800_000..800_000 {
+            let (y, m, d) = civil_from_days(days);
+            assert_eq!(days_from_civil(y, m, d), days);
+        }
+    }
+
+    #[cfg(feature = "de")]
+    #[test]
+    fn datetime() {
+        let dt = parse_datetime("2024-03-10 09:05").unwrap();
+        assert_eq!((dt.year, dt.month, dt.day, dt.hour, dt.minute, dt.second), (2024, 3, 10, 9, 5, 0));
+        let dt = parse_datetime("2024-03-10T09:05:07.25").unwrap();
+        assert_eq!((dt.second, dt.nanos), (7, 250_000_000));
+        for s in &["2024-03-10", "2024-02-30 09:05", "2024-03-10 9:05", "2024-03-10 09:05.5",
+                   "2024-03-10 24:00", "2024-03-10 09:05:07Z", "2024-03-10x09:05"] {
+            assert_eq!(parse_datetime(s), None, "{}", s);
+        }
+    }
+
+    #[cfg(feature = "de")]
+    #[test]
+    fn offset() {
+        assert_eq!(split_offset("2024-03-10T09:05Z"), Some(("2024-03-10T09:05", 0)));
+        assert_eq!(split_offset("2024-03-10T09:05+05:30"), Some(("2024-03-10T09:05", 19800)));
+        assert_eq!(split_offset("2024-03-10T09:05-08:00"), Some(("2024-03-10T09:05", -28800)));
+        assert_eq!(split_offset("2024-03-10T09:05"), None);
+        assert_eq!(split_offset("2024-03-10T09:05+5:30"), None);
+    }
+
+    #[cfg(feature = "systemtime")]
+    #[test]
+    fn unix_round_trip() {
+        for &(secs, nanos) in &[(0, 0), (1, 5), (-1, 0), (-2, 999_999_999)] {
+            assert_eq!(to_unix(from_unix(secs, nanos).unwrap()), (secs, nanos));
+        }
+    }
+}
@@ -0,0 +1,57 @@
+//! Sources of the current time.
+//!
+//! Functionality which depends on the current time takes a `Clock`, so tests
+//! can substitute a `FixedClock` for the default `SystemClock` and get
+//! deterministic results.
+use std::time::SystemTime;
+
+/// A source of the current time.
+pub trait Clock {
+    /// Returns the current time.
+    fn now(&self) -> SystemTime;
+}
+
+impl<C> Clock for &C
+    where C: Clock + ?Sized
+{
+    fn now(&self) -> SystemTime {
+        (**self).now()
+    }
+}
+
+/// The system's clock, via `SystemTime::now`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+}
+
+/// A clock which always returns the same time.
+///
+/// # Examples
+///
+/// ```
+/// use serde_humantime::clock::{Clock, FixedClock};
+/// use std::time::{Duration, UNIX_EPOCH};
+///
+/// let time = UNIX_EPOCH + Duration::from_secs(1518563312);
+/// assert_eq!(FixedClock::new(time).now(), time);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FixedClock(SystemTime);
+
+impl FixedClock {
+    /// Creates a clock which always returns `time`.
+    pub fn new(time: SystemTime) -> FixedClock {
+        FixedClock(time)
+    }
+}
+
+impl Clock for FixedClock {
+    fn now(&self) -> SystemTime {
+        self.0
+    }
+}
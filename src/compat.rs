@@ -0,0 +1,82 @@
+//! Interoperability with the `humantime-serde` crate.
+//!
+//! This module is only available with the `humantime-serde` feature. It
+//! provides conversions between `De` and `humantime_serde::Serde`, and
+//! re-exports `humantime_serde`'s `with` modules so code can refer to both
+//! crates' annotations through this crate while migrating.
+//!
+//! `humantime-serde` is built on `humantime` 2.x, while this crate and its
+//! `humantime` re-export use 1.x. Values convert through `Duration` and
+//! `SystemTime`, so the conversions are unaffected, but `humantime` types
+//! from the two crates aren't interchangeable.
+//!
+//! # Examples
+//!
+//! ```
+//! extern crate serde_humantime;
+//! extern crate serde;
+//! #[macro_use]
+//! extern crate serde_derive;
+//!
+//! use std::time::Duration;
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Foo {
+//!     #[serde(with = "serde_humantime::compat::humantime_serde")]
+//!     old: Duration,
+//!     #[serde(with = "serde_humantime")]
+//!     new: Duration,
+//! }
+//!
+//! # fn main() {}
+//! ```
+use humantime_serde::Serde;
+
+use De;
+
+/// Aliases of the `humantime-serde` crate's `with` functions and modules.
+pub mod humantime_serde {
+    pub use humantime_serde::{deserialize, option, serialize, Serde};
+}
+
+impl<T> From<Serde<T>> for De<T> {
+    fn from(v: Serde<T>) -> De<T> {
+        De::new(v.into_inner())
+    }
+}
+
+impl<T> From<De<T>> for Serde<T> {
+    fn from(v: De<T>) -> Serde<T> {
+        Serde::from(v.into_inner())
+    }
+}
+
+#[cfg(all(test, feature = "ser", feature = "de"))]
+mod test {
+    use humantime_serde::Serde;
+    use serde_json;
+    use std::time::Duration;
+
+    use De;
+
+    #[test]
+    fn conversions() {
+        let de = serde_json::from_str::<De<Duration>>(r#""15s""#).unwrap();
+        let serde = Serde::from(de);
+        assert_eq!(*serde, Duration::from_secs(15));
+        let de = De::from(serde);
+        assert!(de == Duration::from_secs(15));
+    }
+
+    #[test]
+    fn alias() {
+        #[derive(Serialize, Deserialize)]
+        struct Foo {
+            #[serde(with = "::compat::humantime_serde")]
+            time: Duration,
+        }
+
+        let foo = serde_json::from_str::<Foo>(r#"{"time":"15s"}"#).unwrap();
+        assert_eq!(foo.time, Duration::from_secs(15));
+    }
+}
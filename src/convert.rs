@@ -0,0 +1,161 @@
+//! Conversion of durations between units.
+//!
+//! Units have the lengths used by the parser and formatter, so a year is
+//! 365.25 days and a month 30.44 days.
+//!
+//! # Examples
+//!
+//! ```
+//! use serde_humantime::convert::{convert, Unit};
+//!
+//! assert_eq!(convert("90 minutes", Unit::Hours).unwrap(), 1.5);
+//! assert_eq!(convert("1day 12h", Unit::Days).unwrap(), 1.5);
+//! assert!(convert("90 parsecs", Unit::Hours).is_err());
+//! ```
+use std::time::Duration;
+
+#[cfg(feature = "de")]
+use parse;
+#[cfg(feature = "de")]
+use Error;
+
+/// The length of each unit in nanoseconds, in the order of `Unit`.
+pub(crate) const UNIT_NANOS: [u128; 9] = [31557600 * 1_000_000_000,
+                                          2630016 * 1_000_000_000,
+                                          86400 * 1_000_000_000,
+                                          3_600_000_000_000,
+                                          60_000_000_000,
+                                          1_000_000_000,
+                                          1_000_000,
+                                          1000,
+                                          1];
+
+/// The units of time, from largest to smallest.
+const UNITS: [Unit; 9] = [Unit::Years,
+                          Unit::Months,
+                          Unit::Days,
+                          Unit::Hours,
+                          Unit::Minutes,
+                          Unit::Seconds,
+                          Unit::Milliseconds,
+                          Unit::Microseconds,
+                          Unit::Nanoseconds];
+
+/// A unit of time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Unit {
+    /// Years of 365.25 days.
+    Years,
+    /// Months of 30.44 days.
+    Months,
+    /// Days.
+    Days,
+    /// Hours.
+    Hours,
+    /// Minutes.
+    Minutes,
+    /// Seconds.
+    Seconds,
+    /// Milliseconds.
+    Milliseconds,
+    /// Microseconds.
+    Microseconds,
+    /// Nanoseconds.
+    Nanoseconds,
+}
+
+impl Unit {
+    /// Returns the length of one of this unit.
+    pub fn duration(self) -> Duration {
+        let nanos = UNIT_NANOS[self as usize];
+        Duration::new((nanos / 1_000_000_000) as u64, (nanos % 1_000_000_000) as u32)
+    }
+}
+
+/// An alias of `Unit`.
+pub type TimeUnit = Unit;
+
+/// Splits a duration into years, months, days, hours, minutes, seconds,
+/// milliseconds, microseconds and nanoseconds, with units larger than the
+/// one at index `largest` folded into it.
+pub(crate) fn split(d: Duration, largest: usize) -> [u128; 9] {
+    let mut rest = d.as_nanos();
+    let mut components = [0; 9];
+    for i in largest..components.len() {
+        components[i] = rest / UNIT_NANOS[i];
+        rest %= UNIT_NANOS[i];
+    }
+    components
+}
+
+/// Returns the nonzero components of a duration from the largest unit to the
+/// smallest, as they are formatted by the crate root.
+///
+/// A zero duration has no components.
+///
+/// # Examples
+///
+/// ```
+/// use serde_humantime::convert::{decompose, Unit};
+/// use std::time::Duration;
+///
+/// let components = decompose(Duration::new(90061, 500_000_000)).collect::<Vec<_>>();
+/// assert_eq!(components,
+///            [(1, Unit::Days), (1, Unit::Hours), (1, Unit::Minutes), (1, Unit::Seconds),
+///             (500, Unit::Milliseconds)]);
+/// ```
+pub fn decompose(d: Duration) -> impl Iterator<Item = (u64, Unit)> {
+    // a Duration holds fewer than 2^64 years
+    IntoIterator::into_iter(split(d, 0))
+        .zip(UNITS)
+        .filter(|&(value, _)| value != 0)
+        .map(|(value, unit)| (value as u64, unit))
+}
+
+/// Returns a duration as a number of the given unit.
+pub fn duration_in(d: Duration, unit: Unit) -> f64 {
+    let unit = UNIT_NANOS[unit as usize];
+    let nanos = d.as_nanos();
+    (nanos / unit) as f64 + (nanos % unit) as f64 / unit as f64
+}
+
+/// Parses a duration such as `90 minutes` and returns it as a number of the
+/// given unit.
+#[cfg(feature = "de")]
+pub fn convert(s: &str, unit: Unit) -> Result<f64, Error> {
+    parse::parse_duration(s).map(|d| duration_in(d, unit))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn duration_in_units() {
+        let d = Duration::new(5400, 500);
+        assert_eq!(duration_in(d, Unit::Hours), 1.5 + 500. / 3_600_000_000_000.);
+        assert_eq!(duration_in(d, Unit::Nanoseconds), 5_400_000_000_500.);
+        assert_eq!(duration_in(Duration::from_secs(0), Unit::Years), 0.);
+        assert_eq!(duration_in(Unit::Years.duration() * 2, Unit::Days), 730.5);
+        assert_eq!(Unit::Months.duration(), Duration::from_secs(2630016));
+    }
+
+    #[test]
+    fn decompose_durations() {
+        assert_eq!(decompose(Duration::from_secs(0)).count(), 0);
+        assert_eq!(decompose(Duration::new(31557600 + 60, 5)).collect::<Vec<_>>(),
+                   [(1, Unit::Years), (1, Unit::Minutes), (5, Unit::Nanoseconds)]);
+        let (value, unit) = decompose(Duration::new(u64::MAX, 999_999_999)).next().unwrap();
+        assert_eq!((value, unit), (584542046090, Unit::Years));
+    }
+
+    #[cfg(feature = "de")]
+    #[test]
+    fn convert_strs() {
+        assert_eq!(convert("1h 30m", Unit::Minutes).unwrap(), 90.);
+        assert_eq!(convert("36h", Unit::Days).unwrap(), 1.5);
+        assert_eq!(convert("1500ms", Unit::Seconds).unwrap(), 1.5);
+        assert_eq!(convert("1 year", Unit::Days).unwrap(), 365.25);
+        assert!(convert("", Unit::Seconds).is_err());
+    }
+}
@@ -0,0 +1,494 @@
+//! Serialization of `SystemTime`s as calendar dates.
+//!
+//! Timestamps are serialized in the form `YYYY-MM-DD`, truncating to midnight
+//! UTC. Deserialized values are always at midnight UTC of the given date.
+//!
+//! # Examples
+//!
+//! ```
+//! extern crate serde_humantime;
+//! extern crate serde;
+//! #[macro_use]
+//! extern crate serde_derive;
+//!
+//! use std::time::SystemTime;
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Foo {
+//!     #[serde(with = "serde_humantime::date")]
+//!     birthday: SystemTime,
+//! }
+//!
+//! # fn main() {}
+//! ```
+//!
+//! The `extended` submodule additionally accepts ISO 8601 ordinal and week
+//! dates when deserializing.
+//!
+//! With the `chrono` feature, the `chrono` submodule provides the same
+//! format for `chrono::NaiveDate` fields. Likewise, with the `time` feature,
+//! the `time` submodule supports `time::Date` fields.
+#[cfg(all(feature = "de", feature = "systemtime"))]
+use serde::de::{Deserializer, Visitor, Error, Unexpected};
+#[cfg(all(feature = "ser", feature = "systemtime"))]
+use serde::ser::{self, Serializer};
+#[cfg(all(feature = "de", feature = "systemtime"))]
+use std::fmt;
+#[cfg(feature = "systemtime")]
+use std::time::SystemTime;
+
+#[cfg(any(feature = "de", feature = "systemtime"))]
+use civil;
+
+/// Serializes a `SystemTime` as a `YYYY-MM-DD` date.
+#[cfg(all(feature = "ser", feature = "systemtime"))]
+pub fn serialize<S>(t: &SystemTime, s: S) -> Result<S::Ok, S::Error>
+    where S: Serializer
+{
+    let (secs, _) = civil::to_unix(*t);
+    let (year, month, day) = civil::civil_from_days(secs.div_euclid(civil::SECS_PER_DAY));
+    if !(0..=9999).contains(&year) {
+        return Err(ser::Error::custom("year out of range"));
+    }
+    s.collect_str(&format_args!("{:04}-{:02}-{:02}", year, month, day))
+}
+
+/// Deserializes a `SystemTime` from a `YYYY-MM-DD` date.
+#[cfg(all(feature = "de", feature = "systemtime"))]
+pub fn deserialize<'de, D>(d: D) -> Result<SystemTime, D::Error>
+    where D: Deserializer<'de>
+{
+    d.deserialize_str(V)
+}
+
+#[cfg(all(feature = "de", feature = "systemtime"))]
+struct V;
+
+#[cfg(all(feature = "de", feature = "systemtime"))]
+impl<'de> Visitor<'de> for V {
+    type Value = SystemTime;
+
+    fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str("a date")
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<SystemTime, E>
+        where E: Error
+    {
+        parse(v).ok_or_else(|| E::invalid_value(Unexpected::Str(v), &self))
+    }
+}
+
+#[cfg(all(feature = "de", feature = "systemtime"))]
+pub(crate) fn parse(s: &str) -> Option<SystemTime> {
+    let (year, month, day) = parse_ymd(s)?;
+    civil::from_unix(civil::days_from_civil(year, month, day) * civil::SECS_PER_DAY, 0)
+}
+
+#[cfg(feature = "de")]
+fn parse_ymd(s: &str) -> Option<(i64, u32, u32)> {
+    let b = s.as_bytes();
+    if b.len() != 10 || b[4] != b'-' || b[7] != b'-' {
+        return None;
+    }
+    let year = digits(&s[..4])? as i64;
+    let month = digits(&s[5..7])?;
+    let day = digits(&s[8..])?;
+    if month == 0 || month > 12 || day == 0 || day > civil::days_in_month(year, month) {
+        return None;
+    }
+    Some((year, month, day))
+}
+
+#[cfg(feature = "de")]
+fn digits(s: &str) -> Option<u32> {
+    if s.bytes().all(|b| b.is_ascii_digit()) {
+        s.parse::<u32>().ok()
+    } else {
+        None
+    }
+}
+
+/// Serialization of `SystemTime`s as calendar dates, accepting ISO 8601
+/// ordinal and week dates.
+///
+/// Timestamps are serialized as `YYYY-MM-DD` dates, as in the parent module.
+/// Along with those, ordinal dates like `2018-131` (the 131st day of 2018)
+/// and week dates like `2018-W19-5` (the Friday of the 19th ISO week of 2018)
+/// are accepted when deserializing.
+///
+/// # Examples
+///
+/// ```
+/// extern crate serde_humantime;
+/// extern crate serde;
+/// #[macro_use]
+/// extern crate serde_derive;
+///
+/// use std::time::SystemTime;
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Broadcast {
+///     #[serde(with = "serde_humantime::date::extended")]
+///     air_date: SystemTime,
+/// }
+///
+/// # fn main() {}
+/// ```
+#[cfg(feature = "systemtime")]
+pub mod extended {
+    #[cfg(feature = "de")]
+    use serde::de::{Deserializer, Visitor, Error, Unexpected};
+    #[cfg(feature = "ser")]
+    use serde::ser::Serializer;
+    #[cfg(feature = "de")]
+    use std::fmt;
+    use std::time::SystemTime;
+
+    #[cfg(feature = "de")]
+    use civil;
+
+    /// Serializes a `SystemTime` as a `YYYY-MM-DD` date.
+    #[cfg(feature = "ser")]
+    pub fn serialize<S>(t: &SystemTime, s: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        super::serialize(t, s)
+    }
+
+    /// Deserializes a `SystemTime` from a calendar, ordinal or week date.
+    #[cfg(feature = "de")]
+    pub fn deserialize<'de, D>(d: D) -> Result<SystemTime, D::Error>
+        where D: Deserializer<'de>
+    {
+        d.deserialize_str(V)
+    }
+
+    #[cfg(feature = "de")]
+    struct V;
+
+    #[cfg(feature = "de")]
+    impl<'de> Visitor<'de> for V {
+        type Value = SystemTime;
+
+        fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+            fmt.write_str("an ISO 8601 date")
+        }
+
+        fn visit_str<E>(self, v: &str) -> Result<SystemTime, E>
+            where E: Error
+        {
+            parse(v)
+                .and_then(|days| civil::from_unix(days * civil::SECS_PER_DAY, 0))
+                .ok_or_else(|| E::invalid_value(Unexpected::Str(v), &self))
+        }
+    }
+
+    /// Parses a calendar, ordinal or week date into a number of days since
+    /// the Unix epoch.
+    #[cfg(feature = "de")]
+    fn parse(s: &str) -> Option<i64> {
+        if let Some((year, month, day)) = super::parse_ymd(s) {
+            return Some(civil::days_from_civil(year, month, day));
+        }
+
+        let b = s.as_bytes();
+        if b.len() < 8 || b[4] != b'-' {
+            return None;
+        }
+        let year = super::digits(&s[..4])? as i64;
+        if b[5] == b'W' {
+            if b.len() != 10 || b[8] != b'-' {
+                return None;
+            }
+            let week = super::digits(&s[6..8])? as i64;
+            let day = super::digits(&s[9..])? as i64;
+            if week == 0 || day == 0 || day > 7 {
+                return None;
+            }
+            let days = week_one(year) + (week - 1) * 7 + day - 1;
+            // years have either 52 or 53 weeks
+            if days >= week_one(year + 1) {
+                return None;
+            }
+            Some(days)
+        } else {
+            if b.len() != 8 {
+                return None;
+            }
+            let ordinal = super::digits(&s[5..])? as i64;
+            let len = if civil::is_leap_year(year) { 366 } else { 365 };
+            if ordinal == 0 || ordinal > len {
+                return None;
+            }
+            Some(civil::days_from_civil(year, 1, 1) + ordinal - 1)
+        }
+    }
+
+    /// Returns the Monday starting the first ISO week of a year, the week
+    /// containing January 4th.
+    #[cfg(feature = "de")]
+    fn week_one(year: i64) -> i64 {
+        let jan4 = civil::days_from_civil(year, 1, 4);
+        // the epoch was a Thursday
+        jan4 - (jan4 + 3).rem_euclid(7)
+    }
+}
+
+/// Serialization of `chrono::NaiveDate`s as `YYYY-MM-DD` dates.
+///
+/// # Examples
+///
+/// ```
+/// extern crate chrono;
+/// extern crate serde_humantime;
+/// extern crate serde;
+/// #[macro_use]
+/// extern crate serde_derive;
+///
+/// use chrono::NaiveDate;
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Foo {
+///     #[serde(with = "serde_humantime::date::chrono")]
+///     birthday: NaiveDate,
+/// }
+///
+/// # fn main() {}
+/// ```
+#[cfg(feature = "chrono")]
+pub mod chrono {
+    #[cfg(feature = "ser")]
+    use chrono::Datelike;
+    use chrono::NaiveDate;
+    #[cfg(feature = "de")]
+    use serde::de::{Deserializer, Visitor, Error, Unexpected};
+    #[cfg(feature = "ser")]
+    use serde::ser::{self, Serializer};
+    #[cfg(feature = "de")]
+    use std::fmt;
+
+    /// Serializes a `NaiveDate` as a `YYYY-MM-DD` date.
+    #[cfg(feature = "ser")]
+    pub fn serialize<S>(date: &NaiveDate, s: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        if !(0..=9999).contains(&date.year()) {
+            return Err(ser::Error::custom("year out of range"));
+        }
+        s.collect_str(&format_args!("{:04}-{:02}-{:02}", date.year(), date.month(), date.day()))
+    }
+
+    /// Deserializes a `NaiveDate` from a `YYYY-MM-DD` date.
+    #[cfg(feature = "de")]
+    pub fn deserialize<'de, D>(d: D) -> Result<NaiveDate, D::Error>
+        where D: Deserializer<'de>
+    {
+        d.deserialize_str(V)
+    }
+
+    #[cfg(feature = "de")]
+    struct V;
+
+    #[cfg(feature = "de")]
+    impl<'de> Visitor<'de> for V {
+        type Value = NaiveDate;
+
+        fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+            fmt.write_str("a date")
+        }
+
+        fn visit_str<E>(self, v: &str) -> Result<NaiveDate, E>
+            where E: Error
+        {
+            super::parse_ymd(v)
+                .and_then(|(year, month, day)| NaiveDate::from_ymd_opt(year as i32, month, day))
+                .ok_or_else(|| E::invalid_value(Unexpected::Str(v), &self))
+        }
+    }
+}
+
+/// Serialization of `time::Date`s as `YYYY-MM-DD` dates.
+///
+/// # Examples
+///
+/// ```
+/// extern crate serde_humantime;
+/// extern crate serde;
+/// #[macro_use]
+/// extern crate serde_derive;
+/// extern crate time;
+///
+/// use time::Date;
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Foo {
+///     #[serde(with = "serde_humantime::date::time")]
+///     birthday: Date,
+/// }
+///
+/// # fn main() {}
+/// ```
+#[cfg(feature = "time")]
+pub mod time {
+    #[cfg(feature = "de")]
+    use serde::de::{Deserializer, Visitor, Error, Unexpected};
+    #[cfg(feature = "ser")]
+    use serde::ser::{self, Serializer};
+    #[cfg(feature = "de")]
+    use std::convert::TryFrom;
+    #[cfg(feature = "de")]
+    use std::fmt;
+    use time::Date;
+    #[cfg(feature = "de")]
+    use time::Month;
+
+    /// Serializes a `Date` as a `YYYY-MM-DD` date.
+    #[cfg(feature = "ser")]
+    pub fn serialize<S>(date: &Date, s: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        if !(0..=9999).contains(&date.year()) {
+            return Err(ser::Error::custom("year out of range"));
+        }
+        s.collect_str(&format_args!("{:04}-{:02}-{:02}", date.year(), date.month() as u8, date.day()))
+    }
+
+    /// Deserializes a `Date` from a `YYYY-MM-DD` date.
+    #[cfg(feature = "de")]
+    pub fn deserialize<'de, D>(d: D) -> Result<Date, D::Error>
+        where D: Deserializer<'de>
+    {
+        d.deserialize_str(V)
+    }
+
+    #[cfg(feature = "de")]
+    struct V;
+
+    #[cfg(feature = "de")]
+    impl<'de> Visitor<'de> for V {
+        type Value = Date;
+
+        fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+            fmt.write_str("a date")
+        }
+
+        fn visit_str<E>(self, v: &str) -> Result<Date, E>
+            where E: Error
+        {
+            super::parse_ymd(v)
+                .and_then(|(year, month, day)| {
+                    let month = Month::try_from(month as u8).ok()?;
+                    Date::from_calendar_date(year as i32, month, day as u8).ok()
+                })
+                .ok_or_else(|| E::invalid_value(Unexpected::Str(v), &self))
+        }
+    }
+}
+
+#[cfg(all(test, feature = "ser", feature = "de"))]
+mod test {
+    #[cfg(feature = "systemtime")]
+    use serde_json;
+    #[cfg(feature = "systemtime")]
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    #[cfg(feature = "systemtime")]
+    #[derive(Serialize, Deserialize)]
+    struct Foo {
+        #[serde(with = "super")]
+        date: SystemTime,
+    }
+
+    #[cfg(feature = "systemtime")]
+    #[test]
+    fn round_trip() {
+        let foo = Foo { date: UNIX_EPOCH + Duration::new(1518563312, 5) };
+        let json = serde_json::to_string(&foo).unwrap();
+        assert_eq!(json, r#"{"date":"2018-02-13"}"#);
+        let foo = serde_json::from_str::<Foo>(&json).unwrap();
+        assert_eq!(foo.date, UNIX_EPOCH + Duration::from_secs(1518480000));
+
+        let foo = Foo { date: UNIX_EPOCH - Duration::new(0, 1) };
+        let json = serde_json::to_string(&foo).unwrap();
+        assert_eq!(json, r#"{"date":"1969-12-31"}"#);
+    }
+
+    #[cfg(feature = "systemtime")]
+    #[test]
+    fn extended() {
+        #[derive(Serialize, Deserialize)]
+        struct Bar {
+            #[serde(with = "super::extended")]
+            date: SystemTime,
+        }
+
+        let may_11 = UNIX_EPOCH + Duration::from_secs(1525996800);
+        for s in &["2018-05-11", "2018-131", "2018-W19-5"] {
+            let bar = serde_json::from_str::<Bar>(&format!(r#"{{"date":"{}"}}"#, s)).unwrap();
+            assert_eq!(bar.date, may_11, "{}", s);
+            assert_eq!(serde_json::to_string(&bar).unwrap(), r#"{"date":"2018-05-11"}"#);
+        }
+
+        let cases = [("2016-366", 1483142400),
+                     ("2009-W53-7", 1262476800),
+                     ("2008-W01-1", 1199059200),
+                     ("1970-W01-4", 0)];
+        for &(s, secs) in &cases {
+            let bar = serde_json::from_str::<Bar>(&format!(r#"{{"date":"{}"}}"#, s)).unwrap();
+            assert_eq!(bar.date, UNIX_EPOCH + Duration::from_secs(secs), "{}", s);
+        }
+        for s in &["2017-366", "2018-000", "2018-W53-1", "2018-W00-1", "2018-W19-8", "2018-W195",
+                   "2018-13"] {
+            assert!(serde_json::from_str::<Bar>(&format!(r#"{{"date":"{}"}}"#, s)).is_err(), "{}", s);
+        }
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn chrono() {
+        use chrono::NaiveDate;
+        use serde_json;
+
+        #[derive(Serialize, Deserialize)]
+        struct Bar {
+            #[serde(with = "super::chrono")]
+            date: NaiveDate,
+        }
+
+        let bar = Bar { date: NaiveDate::from_ymd_opt(2024, 6, 1).unwrap() };
+        let json = serde_json::to_string(&bar).unwrap();
+        assert_eq!(json, r#"{"date":"2024-06-01"}"#);
+        let bar = serde_json::from_str::<Bar>(&json).unwrap();
+        assert_eq!(bar.date, NaiveDate::from_ymd_opt(2024, 6, 1).unwrap());
+        assert!(serde_json::from_str::<Bar>(r#"{"date":"2023-02-29"}"#).is_err());
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn time() {
+        use serde_json;
+        use time::{Date, Month};
+
+        #[derive(Serialize, Deserialize)]
+        struct Bar {
+            #[serde(with = "super::time")]
+            date: Date,
+        }
+
+        let date = Date::from_calendar_date(2024, Month::June, 1).unwrap();
+        let json = serde_json::to_string(&Bar { date }).unwrap();
+        assert_eq!(json, r#"{"date":"2024-06-01"}"#);
+        assert_eq!(serde_json::from_str::<Bar>(&json).unwrap().date, date);
+        assert!(serde_json::from_str::<Bar>(r#"{"date":"2023-02-29"}"#).is_err());
+    }
+
+    #[cfg(feature = "systemtime")]
+    #[test]
+    fn invalid() {
+        for s in &["2018-02-30", "2018-13-01", "2018-2-13", "2018-02-13T00:00:00Z", "+018-02-13"] {
+            let json = format!(r#"{{"date":"{}"}}"#, s);
+            assert!(serde_json::from_str::<Foo>(&json).is_err(), "{}", s);
+        }
+    }
+}
@@ -0,0 +1,108 @@
+//! `defmt::Format` implementations for logging with `defmt`.
+//!
+//! This module is only available with the `defmt` feature. `De<Duration>`,
+//! `De<SystemTime>`, their `Option` forms and `NonZeroDuration` are logged as
+//! the strings they serialize to in human readable formats, such as `1h 30m`
+//! and `2018-02-14T00:28:07Z`, and `None` for missing values. A `De` is
+//! formatted with its format marker, so `De<Duration, Iso8601>` is logged as
+//! `PT1H30M`. Values are formatted on the stack, without allocating.
+//!
+//! The crate requires `std`, so these implementations are only available on
+//! targets which have it.
+use defmt::{self, Formatter};
+use std::fmt::{self, Write};
+use std::marker::PhantomData;
+use std::time::Duration;
+#[cfg(feature = "systemtime")]
+use std::time::SystemTime;
+
+use format;
+#[cfg(feature = "systemtime")]
+use marker::{FormattedTimestamp, TimestampFormat};
+use marker::{DurationFormat, FormattedDuration};
+use noalloc::Buffer;
+use non_zero::NonZeroDuration;
+use De;
+
+fn write_display(f: Formatter, value: &dyn fmt::Display) {
+    let mut buf = Buffer::new();
+    match write!(buf, "{}", value) {
+        Ok(()) => defmt::write!(f, "{=str}", buf.as_str()),
+        Err(_) => defmt::write!(f, "<unrepresentable>"),
+    }
+}
+
+fn write_option(f: Formatter, value: Option<&dyn fmt::Display>) {
+    match value {
+        Some(value) => write_display(f, value),
+        None => defmt::write!(f, "None"),
+    }
+}
+
+impl<F> defmt::Format for De<Duration, F>
+    where F: DurationFormat
+{
+    fn format(&self, f: Formatter) {
+        write_display(f, &FormattedDuration::<F>(&self.0, PhantomData))
+    }
+}
+
+impl<F> defmt::Format for De<Option<Duration>, F>
+    where F: DurationFormat
+{
+    fn format(&self, f: Formatter) {
+        let d = self.0.as_ref().map(|d| FormattedDuration::<F>(d, PhantomData));
+        write_option(f, d.as_ref().map(|d| d as _))
+    }
+}
+
+#[cfg(feature = "systemtime")]
+impl<F> defmt::Format for De<SystemTime, F>
+    where F: TimestampFormat
+{
+    fn format(&self, f: Formatter) {
+        write_display(f, &FormattedTimestamp::<F>(&self.0, PhantomData))
+    }
+}
+
+#[cfg(feature = "systemtime")]
+impl<F> defmt::Format for De<Option<SystemTime>, F>
+    where F: TimestampFormat
+{
+    fn format(&self, f: Formatter) {
+        let t = self.0.as_ref().map(|t| FormattedTimestamp::<F>(t, PhantomData));
+        write_option(f, t.as_ref().map(|t| t as _))
+    }
+}
+
+impl defmt::Format for NonZeroDuration {
+    fn format(&self, f: Formatter) {
+        write_display(f, &format::format_duration(self.get()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use defmt;
+    use std::time::Duration;
+    #[cfg(feature = "systemtime")]
+    use std::time::SystemTime;
+
+    use marker::Iso8601;
+    use non_zero::NonZeroDuration;
+    use De;
+
+    fn assert_format<T: defmt::Format>() {}
+
+    #[test]
+    fn implemented() {
+        assert_format::<De<Duration>>();
+        assert_format::<De<Option<Duration>, Iso8601>>();
+        assert_format::<NonZeroDuration>();
+        #[cfg(feature = "systemtime")]
+        {
+            assert_format::<De<SystemTime>>();
+            assert_format::<De<Option<SystemTime>>>();
+        }
+    }
+}
@@ -0,0 +1,114 @@
+//! Serialization of `Duration`s as a whole number of days.
+//!
+//! Durations are serialized as a `u64` count of 86400 second days. Only
+//! durations which are an exact number of days can be serialized; anything
+//! else is an error rather than being silently truncated.
+//!
+//! # Examples
+//!
+//! ```
+//! extern crate serde_humantime;
+//! extern crate serde;
+//! #[macro_use]
+//! extern crate serde_derive;
+//!
+//! use std::time::Duration;
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Retention {
+//!     #[serde(with = "serde_humantime::duration_days")]
+//!     keep: Duration,
+//! }
+//!
+//! # fn main() {}
+//! ```
+#[cfg(feature = "de")]
+use serde::de::{Deserializer, Visitor, Error, Unexpected};
+#[cfg(feature = "ser")]
+use serde::ser::{self, Serializer};
+#[cfg(feature = "de")]
+use std::fmt;
+use std::time::Duration;
+
+const DAY: u64 = 86400;
+
+/// Serializes a `Duration` as a `u64` number of days.
+///
+/// Returns an error if the duration isn't an exact number of days.
+#[cfg(feature = "ser")]
+pub fn serialize<S>(d: &Duration, s: S) -> Result<S::Ok, S::Error>
+    where S: Serializer
+{
+    if !d.as_secs().is_multiple_of(DAY) || d.subsec_nanos() != 0 {
+        return Err(ser::Error::custom("duration is not a whole number of days"));
+    }
+    s.serialize_u64(d.as_secs() / DAY)
+}
+
+/// Deserializes a `Duration` from a `u64` number of days.
+#[cfg(feature = "de")]
+pub fn deserialize<'de, D>(d: D) -> Result<Duration, D::Error>
+    where D: Deserializer<'de>
+{
+    d.deserialize_u64(V)
+}
+
+#[cfg(feature = "de")]
+struct V;
+
+#[cfg(feature = "de")]
+impl<'de> Visitor<'de> for V {
+    type Value = Duration;
+
+    fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str("a number of days")
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Duration, E>
+        where E: Error
+    {
+        v.checked_mul(DAY)
+            .map(Duration::from_secs)
+            .ok_or_else(|| E::invalid_value(Unexpected::Unsigned(v), &self))
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<Duration, E>
+        where E: Error
+    {
+        if v < 0 {
+            return Err(E::invalid_value(Unexpected::Signed(v), &self));
+        }
+        self.visit_u64(v as u64)
+    }
+}
+
+#[cfg(all(test, feature = "ser", feature = "de"))]
+mod test {
+    use serde_json;
+    use std::time::Duration;
+
+    #[derive(Serialize, Deserialize)]
+    struct Foo {
+        #[serde(with = "super")]
+        time: Duration,
+    }
+
+    #[test]
+    fn round_trip() {
+        let foo = Foo { time: Duration::from_secs(30 * 86400) };
+        let json = serde_json::to_string(&foo).unwrap();
+        assert_eq!(json, r#"{"time":30}"#);
+        let foo = serde_json::from_str::<Foo>(&json).unwrap();
+        assert_eq!(foo.time, Duration::from_secs(30 * 86400));
+    }
+
+    #[test]
+    fn invalid() {
+        assert!(serde_json::to_string(&Foo { time: Duration::from_secs(86401) }).is_err());
+        assert!(serde_json::to_string(&Foo { time: Duration::new(86400, 1) }).is_err());
+        for json in &[r#"{"time":-1}"#, r#"{"time":1.5}"#, r#"{"time":"1"}"#,
+                      r#"{"time":213503982334602}"#] {
+            assert!(serde_json::from_str::<Foo>(json).is_err(), "{}", json);
+        }
+    }
+}
@@ -0,0 +1,191 @@
+//! Serialization of `Duration`s as a total number of nanoseconds.
+//!
+//! Durations are serialized as a `u128` count of nanoseconds. The `string`
+//! submodule serializes the same count as a decimal string instead, for
+//! consumers which can't represent 128-bit integers natively.
+//!
+//! # Examples
+//!
+//! ```
+//! extern crate serde_humantime;
+//! extern crate serde;
+//! #[macro_use]
+//! extern crate serde_derive;
+//!
+//! use std::time::Duration;
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Span {
+//!     #[serde(with = "serde_humantime::duration_nanos_u128")]
+//!     elapsed: Duration,
+//! }
+//!
+//! # fn main() {}
+//! ```
+#[cfg(feature = "de")]
+use serde::de::{Deserializer, Visitor, Error, Unexpected};
+#[cfg(feature = "ser")]
+use serde::ser::Serializer;
+#[cfg(feature = "de")]
+use std::fmt;
+use std::time::Duration;
+
+#[cfg(feature = "de")]
+const NANOS_PER_SEC: u128 = 1_000_000_000;
+
+/// Serializes a `Duration` as a `u128` number of nanoseconds.
+#[cfg(feature = "ser")]
+pub fn serialize<S>(d: &Duration, s: S) -> Result<S::Ok, S::Error>
+    where S: Serializer
+{
+    s.serialize_u128(d.as_nanos())
+}
+
+/// Deserializes a `Duration` from a `u128` number of nanoseconds.
+#[cfg(feature = "de")]
+pub fn deserialize<'de, D>(d: D) -> Result<Duration, D::Error>
+    where D: Deserializer<'de>
+{
+    d.deserialize_u128(V)
+}
+
+/// Serialization of `Duration`s as a decimal string of nanoseconds.
+pub mod string {
+    #[cfg(feature = "de")]
+    use serde::de::Deserializer;
+    #[cfg(feature = "ser")]
+    use serde::ser::Serializer;
+    use std::time::Duration;
+
+    #[cfg(feature = "de")]
+    use super::V;
+
+    /// Serializes a `Duration` as a decimal string of nanoseconds.
+    #[cfg(feature = "ser")]
+    pub fn serialize<S>(d: &Duration, s: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        s.collect_str(&d.as_nanos())
+    }
+
+    /// Deserializes a `Duration` from a number of nanoseconds.
+    ///
+    /// The value may either be a string containing a decimal integer or an
+    /// integer.
+    #[cfg(feature = "de")]
+    pub fn deserialize<'de, D>(d: D) -> Result<Duration, D::Error>
+        where D: Deserializer<'de>
+    {
+        d.deserialize_any(V)
+    }
+}
+
+#[cfg(feature = "de")]
+struct V;
+
+#[cfg(feature = "de")]
+impl V {
+    fn duration<E>(&self, nanos: u128, unexp: Unexpected) -> Result<Duration, E>
+        where E: Error
+    {
+        let secs = nanos / NANOS_PER_SEC;
+        if secs > u64::MAX as u128 {
+            return Err(E::invalid_value(unexp, self));
+        }
+        Ok(Duration::new(secs as u64, (nanos % NANOS_PER_SEC) as u32))
+    }
+}
+
+#[cfg(feature = "de")]
+impl<'de2> Visitor<'de2> for V {
+    type Value = Duration;
+
+    fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str("a number of nanoseconds")
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Duration, E>
+        where E: Error
+    {
+        self.duration(v as u128, Unexpected::Unsigned(v))
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<Duration, E>
+        where E: Error
+    {
+        if v < 0 {
+            return Err(E::invalid_value(Unexpected::Signed(v), &self));
+        }
+        self.duration(v as u128, Unexpected::Signed(v))
+    }
+
+    fn visit_u128<E>(self, v: u128) -> Result<Duration, E>
+        where E: Error
+    {
+        self.duration(v, Unexpected::Other("128-bit integer"))
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Duration, E>
+        where E: Error
+    {
+        match v.parse() {
+            Ok(nanos) => self.duration(nanos, Unexpected::Str(v)),
+            Err(_) => Err(E::invalid_value(Unexpected::Str(v), &self)),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "ser", feature = "de"))]
+mod test {
+    use serde_json;
+    use std::time::Duration;
+
+    #[derive(Serialize, Deserialize)]
+    struct Foo {
+        #[serde(with = "super")]
+        time: Duration,
+    }
+
+    #[test]
+    fn round_trip() {
+        let foo = Foo { time: Duration::new(15, 3) };
+        let json = serde_json::to_string(&foo).unwrap();
+        assert_eq!(json, r#"{"time":15000000003}"#);
+        let foo = serde_json::from_str::<Foo>(&json).unwrap();
+        assert_eq!(foo.time, Duration::new(15, 3));
+    }
+
+    #[test]
+    fn large() {
+        let foo = Foo { time: Duration::new(u64::MAX, 999_999_999) };
+        let json = serde_json::to_string(&foo).unwrap();
+        assert_eq!(json, r#"{"time":18446744073709551615999999999}"#);
+        let foo = serde_json::from_str::<Foo>(&json).unwrap();
+        assert_eq!(foo.time, Duration::new(u64::MAX, 999_999_999));
+
+        let json = r#"{"time":18446744073709551616000000000}"#;
+        assert!(serde_json::from_str::<Foo>(json).is_err());
+    }
+
+    #[test]
+    fn string() {
+        #[derive(Serialize, Deserialize)]
+        struct Bar {
+            #[serde(with = "super::string")]
+            time: Duration,
+        }
+
+        let bar = Bar { time: Duration::new(u64::MAX, 999_999_999) };
+        let json = serde_json::to_string(&bar).unwrap();
+        assert_eq!(json, r#"{"time":"18446744073709551615999999999"}"#);
+        let bar = serde_json::from_str::<Bar>(&json).unwrap();
+        assert_eq!(bar.time, Duration::new(u64::MAX, 999_999_999));
+
+        let json = r#"{"time":1500}"#;
+        let bar = serde_json::from_str::<Bar>(json).unwrap();
+        assert_eq!(bar.time, Duration::new(0, 1500));
+
+        let json = r#"{"time":-1}"#;
+        assert!(serde_json::from_str::<Bar>(json).is_err());
+    }
+}
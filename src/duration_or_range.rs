@@ -0,0 +1,199 @@
+//! Values which are either a single duration or a range of durations.
+#[cfg(feature = "de")]
+use serde::de::{Deserialize, Deserializer, Visitor, Error, Unexpected};
+#[cfg(feature = "ser")]
+use serde::ser::{Serialize, Serializer};
+#[cfg(feature = "de")]
+use std::error;
+#[cfg(any(feature = "ser", feature = "de"))]
+use std::fmt;
+#[cfg(feature = "de")]
+use std::str::FromStr;
+use std::time::Duration;
+
+#[cfg(feature = "ser")]
+use format;
+#[cfg(feature = "de")]
+use parse;
+
+/// Either a single duration, like `30s`, or an inclusive range of durations,
+/// like `10s..60s`.
+///
+/// This allows a setting which started out as a single value to grow into a
+/// range without changing its type, and without invalidating existing
+/// values. A single duration behaves as a range containing only itself.
+///
+/// It is serialized and deserialized as a string in either form. Ranges whose
+/// minimum exceeds their maximum are rejected when parsing.
+///
+/// # Examples
+///
+/// ```
+/// use serde_humantime::duration_or_range::DurationOrRange;
+/// use std::time::Duration;
+///
+/// let range = "10s..1m".parse::<DurationOrRange>().unwrap();
+/// assert_eq!(range.min(), Duration::from_secs(10));
+/// assert_eq!(range.max(), Duration::from_secs(60));
+/// assert!(range.contains(Duration::from_secs(30)));
+///
+/// let single = "30s".parse::<DurationOrRange>().unwrap();
+/// assert_eq!(single, DurationOrRange::Single(Duration::from_secs(30)));
+/// assert_eq!(single.min(), single.max());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DurationOrRange {
+    /// A single duration.
+    Single(Duration),
+    /// An inclusive range of durations, from a minimum to a maximum.
+    Range(Duration, Duration),
+}
+
+impl DurationOrRange {
+    /// Returns the smallest duration of the value.
+    pub fn min(&self) -> Duration {
+        match *self {
+            DurationOrRange::Single(d) => d,
+            DurationOrRange::Range(min, _) => min,
+        }
+    }
+
+    /// Returns the largest duration of the value.
+    pub fn max(&self) -> Duration {
+        match *self {
+            DurationOrRange::Single(d) => d,
+            DurationOrRange::Range(_, max) => max,
+        }
+    }
+
+    /// Determines if a duration is between the minimum and maximum,
+    /// inclusive.
+    pub fn contains(&self, d: Duration) -> bool {
+        self.min() <= d && d <= self.max()
+    }
+}
+
+impl From<Duration> for DurationOrRange {
+    fn from(d: Duration) -> DurationOrRange {
+        DurationOrRange::Single(d)
+    }
+}
+
+#[cfg(feature = "ser")]
+impl fmt::Display for DurationOrRange {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            DurationOrRange::Single(d) => fmt::Display::fmt(&format::format_duration(d), fmt),
+            DurationOrRange::Range(min, max) => {
+                write!(fmt, "{}..{}", format::format_duration(min), format::format_duration(max))
+            }
+        }
+    }
+}
+
+/// An error parsing a `DurationOrRange`.
+#[derive(Debug)]
+#[cfg(feature = "de")]
+pub struct ParseDurationOrRangeError(());
+
+#[cfg(feature = "de")]
+impl fmt::Display for ParseDurationOrRangeError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str("invalid duration or range")
+    }
+}
+
+#[cfg(feature = "de")]
+impl error::Error for ParseDurationOrRangeError {}
+
+#[cfg(feature = "de")]
+impl FromStr for DurationOrRange {
+    type Err = ParseDurationOrRangeError;
+
+    fn from_str(s: &str) -> Result<DurationOrRange, ParseDurationOrRangeError> {
+        parse(s).ok_or(ParseDurationOrRangeError(()))
+    }
+}
+
+#[cfg(feature = "de")]
+fn parse(s: &str) -> Option<DurationOrRange> {
+    let idx = match s.find("..") {
+        Some(idx) => idx,
+        None => return parse::parse_duration(s).ok().map(DurationOrRange::Single),
+    };
+    let min = parse::parse_duration(&s[..idx]).ok()?;
+    let max = parse::parse_duration(&s[idx + 2..]).ok()?;
+    if min > max {
+        return None;
+    }
+    Some(DurationOrRange::Range(min, max))
+}
+
+#[cfg(feature = "ser")]
+impl Serialize for DurationOrRange {
+    fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        s.collect_str(self)
+    }
+}
+
+#[cfg(feature = "de")]
+impl<'de> Deserialize<'de> for DurationOrRange {
+    fn deserialize<D>(d: D) -> Result<DurationOrRange, D::Error>
+        where D: Deserializer<'de>
+    {
+        struct V;
+
+        impl<'de2> Visitor<'de2> for V {
+            type Value = DurationOrRange;
+
+            fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+                fmt.write_str("a duration or range of durations")
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<DurationOrRange, E>
+                where E: Error
+            {
+                v.parse().map_err(|_| E::invalid_value(Unexpected::Str(v), &self))
+            }
+        }
+
+        d.deserialize_str(V)
+    }
+}
+
+#[cfg(all(test, feature = "ser", feature = "de"))]
+mod test {
+    use serde_json;
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let range = "10s .. 1m 30s".parse::<DurationOrRange>().unwrap();
+        assert_eq!(range, DurationOrRange::Range(Duration::from_secs(10), Duration::from_secs(90)));
+        assert!(range.contains(Duration::from_secs(10)));
+        assert!(range.contains(Duration::from_secs(90)));
+        assert!(!range.contains(Duration::from_secs(91)));
+
+        let single = "30s".parse::<DurationOrRange>().unwrap();
+        assert!(single.contains(Duration::from_secs(30)));
+        assert!(!single.contains(Duration::from_secs(31)));
+        assert_eq!("5s..5s".parse::<DurationOrRange>().unwrap().max(), Duration::from_secs(5));
+
+        for s in &["", "..", "10s..", "..10s", "1m..10s", "10s..20s..30s", "bogus"] {
+            assert!(s.parse::<DurationOrRange>().is_err(), "{}", s);
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        for s in &["\"30s\"", "\"10s..1m\"", "\"0s..0s\""] {
+            let value = serde_json::from_str::<DurationOrRange>(s).unwrap();
+            assert_eq!(serde_json::to_string(&value).unwrap(), *s);
+        }
+        assert!(serde_json::from_str::<DurationOrRange>("30").is_err());
+    }
+}
@@ -0,0 +1,154 @@
+//! Deserialization of `{ "start": ..., "end": ... }` objects into the
+//! `Duration` between them.
+//!
+//! Records such as job runs often store when something started and ended,
+//! where only the time it took matters. Both timestamps are parsed like
+//! `SystemTime`s at the crate root, and fields other than `start` and `end`
+//! are ignored. An end before the start is an error.
+//!
+//! The span can't be turned back into timestamps, so this module can only
+//! deserialize. Its `deserialize` function can be used with
+//! `deserialize_with`.
+//!
+//! # Examples
+//!
+//! ```
+//! extern crate serde_humantime;
+//! extern crate serde;
+//! #[macro_use]
+//! extern crate serde_derive;
+//! extern crate serde_json;
+//!
+//! use std::time::Duration;
+//!
+//! #[derive(Deserialize)]
+//! struct JobRun {
+//!     #[serde(deserialize_with = "serde_humantime::elapsed::deserialize")]
+//!     run: Duration,
+//! }
+//!
+//! # fn main() {
+//! let json = r#"{"run": {"start": "2018-02-13T23:08:32Z", "end": "2018-02-13T23:10:02Z"}}"#;
+//! let job = serde_json::from_str::<JobRun>(json).unwrap();
+//! assert_eq!(job.run, Duration::from_secs(90));
+//! # }
+//! ```
+use serde::de::{self, Deserialize, Deserializer, IgnoredAny, MapAccess, Visitor};
+use std::fmt;
+use std::time::{Duration, SystemTime};
+
+use De;
+
+const FIELDS: &[&str] = &["start", "end"];
+
+/// Deserializes the `Duration` between the `start` and `end` timestamps of
+/// an object.
+pub fn deserialize<'de, D>(d: D) -> Result<Duration, D::Error>
+    where D: Deserializer<'de>
+{
+    d.deserialize_struct("Elapsed", FIELDS, V)
+}
+
+struct V;
+
+impl<'de> Visitor<'de> for V {
+    type Value = Duration;
+
+    fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str("a start and end timestamp")
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Duration, A::Error>
+        where A: MapAccess<'de>
+    {
+        let mut start = None;
+        let mut end = None;
+        while let Some(key) = map.next_key::<Field>()? {
+            match key {
+                Field::Start => {
+                    if start.is_some() {
+                        return Err(de::Error::duplicate_field("start"));
+                    }
+                    start = Some(map.next_value::<De<SystemTime>>()?.into_inner());
+                }
+                Field::End => {
+                    if end.is_some() {
+                        return Err(de::Error::duplicate_field("end"));
+                    }
+                    end = Some(map.next_value::<De<SystemTime>>()?.into_inner());
+                }
+                Field::Other => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+        let start = start.ok_or_else(|| de::Error::missing_field("start"))?;
+        let end = end.ok_or_else(|| de::Error::missing_field("end"))?;
+
+        end.duration_since(start).map_err(|_| de::Error::custom("end timestamp precedes start timestamp"))
+    }
+}
+
+enum Field {
+    Start,
+    End,
+    Other,
+}
+
+impl<'de> Deserialize<'de> for Field {
+    fn deserialize<D>(d: D) -> Result<Field, D::Error>
+        where D: Deserializer<'de>
+    {
+        struct FieldV;
+
+        impl<'de2> Visitor<'de2> for FieldV {
+            type Value = Field;
+
+            fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+                fmt.write_str("a field name")
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Field, E>
+                where E: de::Error
+            {
+                match v {
+                    "start" => Ok(Field::Start),
+                    "end" => Ok(Field::End),
+                    _ => Ok(Field::Other),
+                }
+            }
+        }
+
+        d.deserialize_identifier(FieldV)
+    }
+}
+
+#[cfg(all(test, feature = "ser"))]
+mod test {
+    use serde_json;
+    use std::time::Duration;
+
+    #[derive(Deserialize)]
+    struct Foo {
+        #[serde(deserialize_with = "super::deserialize")]
+        run: Duration,
+    }
+
+    #[test]
+    fn deserialize() {
+        let json = r#"{"run": {"id": 7, "end": "2018-02-13T23:10:02.5Z", "start": "2018-02-13T23:08:32Z"}}"#;
+        assert_eq!(serde_json::from_str::<Foo>(json).unwrap().run, Duration::from_millis(90500));
+        let json = r#"{"run": {"start": "2018-02-13T23:08:32Z", "end": "2018-02-13T23:08:32Z"}}"#;
+        assert_eq!(serde_json::from_str::<Foo>(json).unwrap().run, Duration::from_secs(0));
+
+        let json = r#"{"run": {"start": "2018-02-13T23:10:02Z", "end": "2018-02-13T23:08:32Z"}}"#;
+        let err = serde_json::from_str::<Foo>(json).err().unwrap();
+        assert!(err.to_string().starts_with("end timestamp precedes start timestamp"), "{}", err);
+
+        for json in &[r#"{"run": {"start": "2018-02-13T23:08:32Z"}}"#,
+                      r#"{"run": {"start": "2018-02-13T23:08:32Z", "end": "soon"}}"#,
+                      r#"{"run": "90s"}"#] {
+            assert!(serde_json::from_str::<Foo>(json).is_err(), "{}", json);
+        }
+    }
+}
@@ -0,0 +1,227 @@
+//! Serialization of `SystemTime`s as whole seconds since an epoch.
+//!
+//! Timestamps are serialized as a `u64` count of seconds since an epoch, with
+//! any fraction of a second discarded. The `unix`, `gps` and `y2k`
+//! submodules count from the Unix epoch, the GPS epoch (1980-01-06) and
+//! 2000-01-01 respectively, all at midnight UTC, and can be used with
+//! `serde_derive`'s `with` annotations. Other epochs can be used through
+//! `serialize_since` and `deserialize_since`. Timestamps before the epoch
+//! fail to serialize.
+//!
+//! Like `SystemTime`s, counts exclude leap seconds. GPS receivers report GPS
+//! time, which includes the leap seconds since 1980 and so runs ahead of the
+//! timestamps of this module by the current GPS-UTC offset.
+//!
+//! # Examples
+//!
+//! ```
+//! extern crate serde_humantime;
+//! extern crate serde;
+//! #[macro_use]
+//! extern crate serde_derive;
+//!
+//! use std::time::SystemTime;
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Fix {
+//!     #[serde(with = "serde_humantime::epoch::gps")]
+//!     time: SystemTime,
+//! }
+//!
+//! # fn main() {}
+//! ```
+#[cfg(feature = "de")]
+use serde::de::{Deserializer, Visitor, Error, Unexpected};
+#[cfg(feature = "ser")]
+use serde::ser::{self, Serializer};
+#[cfg(feature = "de")]
+use std::fmt;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+// seconds from the Unix epoch to each epoch
+const GPS_SECS: u64 = 315964800;
+const Y2K_SECS: u64 = 946684800;
+
+/// Returns the GPS epoch, 1980-01-06T00:00:00Z.
+pub fn gps_epoch() -> SystemTime {
+    UNIX_EPOCH + Duration::from_secs(GPS_SECS)
+}
+
+/// Returns the epoch 2000-01-01T00:00:00Z.
+pub fn y2k_epoch() -> SystemTime {
+    UNIX_EPOCH + Duration::from_secs(Y2K_SECS)
+}
+
+/// Serializes a `SystemTime` as a `u64` number of seconds since an epoch.
+///
+/// Returns an error if the timestamp is before the epoch.
+#[cfg(feature = "ser")]
+pub fn serialize_since<S>(t: &SystemTime, epoch: SystemTime, s: S) -> Result<S::Ok, S::Error>
+    where S: Serializer
+{
+    let d = t.duration_since(epoch).map_err(|_| ser::Error::custom("timestamp is before the epoch"))?;
+    s.serialize_u64(d.as_secs())
+}
+
+/// Deserializes a `SystemTime` from a `u64` number of seconds since an
+/// epoch.
+#[cfg(feature = "de")]
+pub fn deserialize_since<'de, D>(d: D, epoch: SystemTime) -> Result<SystemTime, D::Error>
+    where D: Deserializer<'de>
+{
+    d.deserialize_u64(V(epoch))
+}
+
+/// Serialization of `SystemTime`s as seconds since the Unix epoch,
+/// 1970-01-01T00:00:00Z.
+#[cfg(any(feature = "ser", feature = "de"))]
+pub mod unix {
+    #[cfg(feature = "de")]
+    use serde::de::Deserializer;
+    #[cfg(feature = "ser")]
+    use serde::ser::Serializer;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    /// Serializes a `SystemTime` as a `u64` number of seconds since
+    /// the Unix epoch.
+    #[cfg(feature = "ser")]
+    pub fn serialize<S>(t: &SystemTime, s: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        super::serialize_since(t, UNIX_EPOCH, s)
+    }
+
+    /// Deserializes a `SystemTime` from a `u64` number of seconds since
+    /// the Unix epoch.
+    #[cfg(feature = "de")]
+    pub fn deserialize<'de, D>(d: D) -> Result<SystemTime, D::Error>
+        where D: Deserializer<'de>
+    {
+        super::deserialize_since(d, UNIX_EPOCH)
+    }
+}
+
+/// Serialization of `SystemTime`s as seconds since the GPS epoch,
+/// 1980-01-06T00:00:00Z.
+#[cfg(any(feature = "ser", feature = "de"))]
+pub mod gps {
+    #[cfg(feature = "de")]
+    use serde::de::Deserializer;
+    #[cfg(feature = "ser")]
+    use serde::ser::Serializer;
+    use std::time::SystemTime;
+
+    /// Serializes a `SystemTime` as a `u64` number of seconds since
+    /// the GPS epoch.
+    #[cfg(feature = "ser")]
+    pub fn serialize<S>(t: &SystemTime, s: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        super::serialize_since(t, super::gps_epoch(), s)
+    }
+
+    /// Deserializes a `SystemTime` from a `u64` number of seconds since
+    /// the GPS epoch.
+    #[cfg(feature = "de")]
+    pub fn deserialize<'de, D>(d: D) -> Result<SystemTime, D::Error>
+        where D: Deserializer<'de>
+    {
+        super::deserialize_since(d, super::gps_epoch())
+    }
+}
+
+/// Serialization of `SystemTime`s as seconds since 2000-01-01T00:00:00Z.
+#[cfg(any(feature = "ser", feature = "de"))]
+pub mod y2k {
+    #[cfg(feature = "de")]
+    use serde::de::Deserializer;
+    #[cfg(feature = "ser")]
+    use serde::ser::Serializer;
+    use std::time::SystemTime;
+
+    /// Serializes a `SystemTime` as a `u64` number of seconds since
+    /// 2000-01-01.
+    #[cfg(feature = "ser")]
+    pub fn serialize<S>(t: &SystemTime, s: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        super::serialize_since(t, super::y2k_epoch(), s)
+    }
+
+    /// Deserializes a `SystemTime` from a `u64` number of seconds since
+    /// 2000-01-01.
+    #[cfg(feature = "de")]
+    pub fn deserialize<'de, D>(d: D) -> Result<SystemTime, D::Error>
+        where D: Deserializer<'de>
+    {
+        super::deserialize_since(d, super::y2k_epoch())
+    }
+}
+
+#[cfg(feature = "de")]
+struct V(SystemTime);
+
+#[cfg(feature = "de")]
+impl<'de> Visitor<'de> for V {
+    type Value = SystemTime;
+
+    fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str("a number of seconds since an epoch")
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<SystemTime, E>
+        where E: Error
+    {
+        self.0
+            .checked_add(Duration::from_secs(v))
+            .ok_or_else(|| E::invalid_value(Unexpected::Unsigned(v), &self))
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<SystemTime, E>
+        where E: Error
+    {
+        if v < 0 {
+            return Err(E::invalid_value(Unexpected::Signed(v), &self));
+        }
+        self.visit_u64(v as u64)
+    }
+}
+
+#[cfg(all(test, feature = "ser", feature = "de"))]
+mod test {
+    use serde_json;
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    #[derive(Serialize, Deserialize)]
+    struct Foo {
+        #[serde(with = "super::unix")]
+        unix: SystemTime,
+        #[serde(with = "super::gps")]
+        gps: SystemTime,
+        #[serde(with = "super::y2k")]
+        y2k: SystemTime,
+    }
+
+    #[test]
+    fn round_trip() {
+        // 2018-02-13T23:08:32Z
+        let time = UNIX_EPOCH + Duration::from_secs(1518563312);
+        let json = serde_json::to_string(&Foo { unix: time, gps: time, y2k: time }).unwrap();
+        assert_eq!(json, r#"{"unix":1518563312,"gps":1202598512,"y2k":571878512}"#);
+        let foo = serde_json::from_str::<Foo>(&json).unwrap();
+        assert_eq!((foo.unix, foo.gps, foo.y2k), (time, time, time));
+
+        let foo = Foo { unix: time, gps: time, y2k: time + Duration::new(0, 999_999_999) };
+        assert_eq!(serde_json::to_string(&foo).unwrap(), json);
+    }
+
+    #[test]
+    fn invalid() {
+        let time = UNIX_EPOCH + Duration::from_secs(315964799);
+        assert!(serde_json::to_string(&Foo { unix: time, gps: time, y2k: super::y2k_epoch() }).is_err());
+        for json in &[r#"{"unix":0,"gps":-1,"y2k":0}"#, r#"{"unix":0,"gps":0.5,"y2k":0}"#,
+                      r#"{"unix":"0","gps":0,"y2k":0}"#, r#"{"unix":0,"gps":0,"y2k":18446744073709551615}"#] {
+            assert!(serde_json::from_str::<Foo>(json).is_err(), "{}", json);
+        }
+    }
+}
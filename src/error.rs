@@ -0,0 +1,181 @@
+//! Errors produced when parsing durations and timestamps.
+use std::error;
+use std::fmt;
+
+#[cfg(feature = "humantime")]
+use humantime;
+
+/// The cause of a parse failure.
+///
+/// New kinds may be added in minor releases, so matches on it need a
+/// wildcard arm.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// The input was empty.
+    Empty,
+    /// The input contained a character which isn't permitted.
+    InvalidCharacter,
+    /// A number was expected but not found, or was malformed.
+    InvalidNumber,
+    /// A duration component had a missing or unrecognized unit.
+    UnknownUnit,
+    /// A value was too large, or a timestamp component was out of range.
+    OutOfRange,
+    /// Unexpected input followed an otherwise valid value.
+    TrailingInput,
+    /// A timestamp didn't have the expected layout.
+    InvalidFormat,
+    /// The input was longer than the configured limit.
+    TooLong,
+    /// The input had more components than the configured limit.
+    TooManyComponents,
+    /// A timestamp was outside of the configured bounds.
+    OutOfBounds,
+}
+
+impl ErrorKind {
+    /// Returns a short description of the kind, as used by its `Display`
+    /// implementation.
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            ErrorKind::Empty => "empty input",
+            ErrorKind::InvalidCharacter => "invalid character",
+            ErrorKind::InvalidNumber => "expected a number",
+            ErrorKind::UnknownUnit => "unknown unit",
+            ErrorKind::OutOfRange => "value out of range",
+            ErrorKind::TrailingInput => "unexpected trailing input",
+            ErrorKind::InvalidFormat => "invalid format",
+            ErrorKind::TooLong => "input too long",
+            ErrorKind::TooManyComponents => "too many components",
+            ErrorKind::OutOfBounds => "timestamp out of bounds",
+        }
+    }
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str(self.as_str())
+    }
+}
+
+/// An error parsing a duration or timestamp.
+///
+/// When parsing is delegated to the `humantime` crate, its error is available
+/// through `source`.
+///
+/// Errors never allocate: they hold only their kind and, with the
+/// `humantime` feature, `humantime`'s equally small error, so they are
+/// `Copy`. Their messages are the `&'static str`s of `ErrorKind::as_str`.
+/// This holds in every build; the crate requires `std`, so there is no
+/// separate `no_std` configuration.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Error {
+    kind: ErrorKind,
+    #[cfg(feature = "humantime")]
+    source: Option<Source>,
+}
+
+#[cfg(feature = "humantime")]
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Source {
+    Duration(humantime::DurationError),
+    #[cfg(feature = "systemtime")]
+    Timestamp(humantime::TimestampError),
+}
+
+impl Error {
+    pub(crate) fn new(kind: ErrorKind) -> Error {
+        Error {
+            kind,
+            #[cfg(feature = "humantime")]
+            source: None,
+        }
+    }
+
+    #[cfg(feature = "humantime")]
+    pub(crate) fn from_duration(e: humantime::DurationError) -> Error {
+        let kind = match e {
+            humantime::DurationError::InvalidCharacter(_) => ErrorKind::InvalidCharacter,
+            humantime::DurationError::NumberExpected(_) => ErrorKind::InvalidNumber,
+            humantime::DurationError::UnknownUnit(_, _) => ErrorKind::UnknownUnit,
+            humantime::DurationError::NumberOverflow => ErrorKind::OutOfRange,
+            humantime::DurationError::Empty => ErrorKind::Empty,
+        };
+        Error {
+            kind,
+            source: Some(Source::Duration(e)),
+        }
+    }
+
+    #[cfg(all(feature = "humantime", feature = "systemtime"))]
+    pub(crate) fn from_timestamp(e: humantime::TimestampError) -> Error {
+        let kind = match e {
+            humantime::TimestampError::OutOfRange => ErrorKind::OutOfRange,
+            humantime::TimestampError::InvalidDigit => ErrorKind::InvalidNumber,
+            humantime::TimestampError::InvalidFormat => ErrorKind::InvalidFormat,
+        };
+        Error {
+            kind,
+            source: Some(Source::Timestamp(e)),
+        }
+    }
+
+    /// Returns the cause of the error.
+    pub fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.kind, fmt)
+    }
+}
+
+impl error::Error for Error {
+    #[cfg(feature = "humantime")]
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self.source {
+            Some(Source::Duration(ref e)) => Some(e),
+            #[cfg(feature = "systemtime")]
+            Some(Source::Timestamp(ref e)) => Some(e),
+            None => None,
+        }
+    }
+}
+
+impl From<ErrorKind> for Error {
+    fn from(kind: ErrorKind) -> Error {
+        Error::new(kind)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use parse::parse_duration;
+    use super::ErrorKind;
+
+    #[test]
+    fn kind() {
+        assert_eq!(parse_duration("").unwrap_err().kind(), ErrorKind::Empty);
+        assert_eq!(parse_duration("1 fortnight").unwrap_err().kind(), ErrorKind::UnknownUnit);
+        assert_eq!(parse_duration("99999999999999999999s").unwrap_err().kind(),
+                   ErrorKind::OutOfRange);
+        assert_eq!(parse_duration("1x").unwrap_err().to_string(), "unknown unit");
+        assert_eq!(ErrorKind::TrailingInput.as_str(), "unexpected trailing input");
+
+        let e = parse_duration("").unwrap_err();
+        let copy = e;
+        assert_eq!(copy, e);
+    }
+
+    #[test]
+    #[cfg(feature = "humantime")]
+    fn source() {
+        use std::error::Error;
+
+        let e = parse_duration("1 fortnight").unwrap_err();
+        assert!(e.source().is_some());
+    }
+}
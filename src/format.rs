@@ -0,0 +1,890 @@
+//! Formatting of `Duration`s and `SystemTime`s.
+//!
+//! The output of this crate is byte-for-byte stable across releases and does
+//! not depend on the version of `humantime` in use. Changes to it are
+//! considered breaking. This guarantee covers the default format, so it no
+//! longer holds for a process which changes the style of its durations with
+//! `set_global_format`, as described below.
+//!
+//! # Durations
+//!
+//! Durations are formatted as a space-separated list of components from
+//! largest to smallest, omitting those which are zero:
+//!
+//! | Component | Format              | Length                  |
+//! |-----------|---------------------|-------------------------|
+//! | years     | `1year`, `2years`   | 365.25 days (31557600s) |
+//! | months    | `1month`, `2months` | 30.44 days (2630016s)   |
+//! | days      | `1day`, `2days`     | 86400s                  |
+//! | hours     | `2h`                |                         |
+//! | minutes   | `2m`                |                         |
+//! | seconds   | `2s`                |                         |
+//! | millis    | `2ms`               |                         |
+//! | micros    | `2us`               |                         |
+//! | nanos     | `2ns`               |                         |
+//!
+//! A zero duration is formatted as `0s`. For example, 90061.5 seconds is
+//! formatted as `1day 1h 1m 1s 500ms`.
+//!
+//! Every `Duration`, down to the nanosecond, parses back to exactly the same
+//! value after formatting.
+//!
+//! Other styles intended for people rather than parsers can be selected with
+//! `FormatOptions`. Their output may change between releases, but also
+//! round-trips through the parser as long as any unit separator is empty or
+//! whitespace. The fixed-width style is the exception, as it truncates
+//! durations to whole seconds.
+//!
+//! Applications can switch the style used when serializing durations through
+//! the crate root, the `option` module and `De` with `set_global_format`, or
+//! for a single thread and closure with `with_format`. Both change output
+//! which would otherwise be stable, and the global format also changes the
+//! output of libraries serializing through this crate, so it is best left to
+//! applications which own all of their serialized output.
+//!
+//! # Timestamps
+//!
+//! Timestamps are formatted in UTC as `YYYY-MM-DDTHH:MM:SSZ`. If the
+//! timestamp has a nonzero fractional second, exactly nine fractional digits
+//! are written, as in `YYYY-MM-DDTHH:MM:SS.nnnnnnnnnZ`. Only timestamps from
+//! 1970 through the end of year 9999 can be formatted.
+use serde::ser::{self, Serializer};
+use std::cell::Cell;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::RwLock;
+use std::time::Duration;
+#[cfg(feature = "systemtime")]
+use std::time::SystemTime;
+
+#[cfg(feature = "systemtime")]
+use civil;
+use convert::{split, UNIT_NANOS};
+#[cfg(feature = "systemtime")]
+use offset::UtcOffset;
+#[cfg(feature = "systemtime")]
+use rfc3339;
+
+pub use convert::Unit;
+
+const DAY: u64 = 86400;
+
+static GLOBAL_FORMAT: RwLock<FormatOptions> = RwLock::new(FormatOptions::new());
+// lets serialization skip the lock until a global format is set
+static GLOBAL_FORMAT_SET: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    static SCOPED_FORMAT: Cell<Option<FormatOptions>> = const { Cell::new(None) };
+}
+
+/// The names used for the units of a formatted duration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Units {
+    /// The stable format described in the module documentation, such as
+    /// `2days 10h 5s`.
+    Default,
+    /// Full, correctly pluralized unit names separated from their values,
+    /// such as `2 days 10 hours 1 second`.
+    Long,
+    /// Single letter abbreviations for every unit of a second or longer, such
+    /// as `2d 10h 5s`.
+    Short,
+}
+
+impl Units {
+    pub(crate) fn names(self) -> &'static [(&'static str, &'static str); 9] {
+        match self {
+            Units::Default => &[("year", "years"),
+                                ("month", "months"),
+                                ("day", "days"),
+                                ("h", "h"),
+                                ("m", "m"),
+                                ("s", "s"),
+                                ("ms", "ms"),
+                                ("us", "us"),
+                                ("ns", "ns")],
+            Units::Long => &[("year", "years"),
+                             ("month", "months"),
+                             ("day", "days"),
+                             ("hour", "hours"),
+                             ("minute", "minutes"),
+                             ("second", "seconds"),
+                             ("millisecond", "milliseconds"),
+                             ("microsecond", "microseconds"),
+                             ("nanosecond", "nanoseconds")],
+            Units::Short => &[("y", "y"),
+                              ("M", "M"),
+                              ("d", "d"),
+                              ("h", "h"),
+                              ("m", "m"),
+                              ("s", "s"),
+                              ("ms", "ms"),
+                              ("us", "us"),
+                              ("ns", "ns")],
+        }
+    }
+
+    fn separator(self) -> &'static str {
+        match self {
+            Units::Default | Units::Short => "",
+            Units::Long => " ",
+        }
+    }
+}
+
+/// Options controlling how `Duration`s are formatted.
+///
+/// # Examples
+///
+/// ```
+/// use serde_humantime::format::{FormatOptions, Unit, Units};
+/// use std::time::Duration;
+///
+/// let options = FormatOptions::new().units(Units::Long);
+/// assert_eq!(options.format_duration(Duration::new(3601, 0)).to_string(), "1 hour 1 second");
+/// assert_eq!(options.format_duration(Duration::new(7322, 0)).to_string(),
+///            "2 hours 2 minutes 2 seconds");
+///
+/// let options = FormatOptions::new().unit_separator(" ");
+/// assert_eq!(options.format_duration(Duration::new(208800, 0)).to_string(), "2 days 10 h");
+///
+/// let options = FormatOptions::new().units(Units::Long).list(true);
+/// assert_eq!(options.format_duration(Duration::new(183900, 0)).to_string(),
+///            "2 days, 3 hours and 5 minutes");
+///
+/// let options = FormatOptions::new().fixed_width(true);
+/// assert_eq!(options.format_duration(Duration::new(7503, 0)).to_string(), "000d 02h 05m 03s");
+///
+/// let options = FormatOptions::new().single_unit(true);
+/// assert_eq!(options.format_duration(Duration::new(1, 500_000_000)).to_string(), "1500ms");
+///
+/// let options = FormatOptions::new().max_unit(Unit::Hours);
+/// assert_eq!(options.format_duration(Duration::new(129600, 0)).to_string(), "36h");
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct FormatOptions {
+    units: Units,
+    separator: Option<&'static str>,
+    list: bool,
+    fixed_width: bool,
+    single_unit: bool,
+    max_unit: Unit,
+}
+
+impl Default for FormatOptions {
+    fn default() -> FormatOptions {
+        FormatOptions::new()
+    }
+}
+
+impl FormatOptions {
+    /// Returns options producing the default format.
+    pub const fn new() -> FormatOptions {
+        FormatOptions {
+            units: Units::Default,
+            separator: None,
+            list: false,
+            fixed_width: false,
+            single_unit: false,
+            max_unit: Unit::Years,
+        }
+    }
+
+    /// Sets the names used for units.
+    ///
+    /// Defaults to `Units::Default`.
+    pub fn units(mut self, units: Units) -> FormatOptions {
+        self.units = units;
+        self
+    }
+
+    /// Sets the string written between each value and its unit.
+    ///
+    /// Defaults to a single space for `Units::Long`, and to nothing otherwise.
+    pub fn unit_separator(mut self, separator: &'static str) -> FormatOptions {
+        self.separator = Some(separator);
+        self
+    }
+
+    /// If set, components are written as an English list, separated by commas
+    /// except for the last two, which are joined by `and`.
+    ///
+    /// Defaults to `false`, which separates components with single spaces.
+    pub fn list(mut self, list: bool) -> FormatOptions {
+        self.list = list;
+        self
+    }
+
+    /// If set, durations are written as zero-padded days, hours, minutes and
+    /// seconds, as in `000d 02h 05m 03s`, so that they align in tables and
+    /// sort lexicographically.
+    ///
+    /// Durations are truncated to whole seconds, and days are padded to three
+    /// digits, so durations of 1000 days or more are wider. The other options
+    /// are ignored.
+    ///
+    /// Defaults to `false`.
+    pub fn fixed_width(mut self, fixed_width: bool) -> FormatOptions {
+        self.fixed_width = fixed_width;
+        self
+    }
+
+    /// If set, durations are written with exactly one unit, the largest one
+    /// which represents them exactly, as in `1500ms` or `90m`.
+    ///
+    /// Durations which aren't a whole number of microseconds are written in
+    /// nanoseconds. Units shorter than a second only parse back for durations
+    /// under about 584 years, so longer durations which would need one are
+    /// written with multiple units instead.
+    ///
+    /// Defaults to `false`.
+    pub fn single_unit(mut self, single_unit: bool) -> FormatOptions {
+        self.single_unit = single_unit;
+        self
+    }
+
+    /// Sets the largest unit written, so that `Unit::Hours` formats a day and
+    /// a half as `36h` rather than `1day 12h`.
+    ///
+    /// Units shorter than a second only parse back for durations under about
+    /// 584 years, so longer durations are written with seconds and larger
+    /// units when the largest unit is one of them.
+    ///
+    /// Defaults to `Unit::Years`.
+    pub fn max_unit(mut self, max_unit: Unit) -> FormatOptions {
+        self.max_unit = max_unit;
+        self
+    }
+
+    /// Returns a value which formats a `Duration` with these options via its
+    /// `Display` implementation.
+    pub fn format_duration(&self, d: Duration) -> FormattedDuration {
+        FormattedDuration(d, *self)
+    }
+}
+
+/// Sets the options used to format durations serialized through the crate
+/// root, the `option` module and `De`.
+///
+/// This changes the output of every field serialized in the default format
+/// by the process, so it's intended for applications rather than libraries.
+/// Durations formatted with `format_duration` and the other modules of this
+/// crate are unaffected. Parsing is unchanged, so only options which
+/// round-trip, as described in the module documentation, should be used for
+/// values which will be read back.
+///
+/// Options set by `with_format` take precedence on their thread.
+///
+/// # Examples
+///
+/// ```
+/// extern crate serde_humantime;
+/// extern crate serde;
+/// #[macro_use]
+/// extern crate serde_derive;
+/// extern crate serde_json;
+///
+/// use serde_humantime::format::{set_global_format, FormatOptions, Units};
+/// use std::time::Duration;
+///
+/// #[derive(Serialize)]
+/// struct Foo {
+///     #[serde(with = "serde_humantime")]
+///     timeout: Duration,
+/// }
+///
+/// # fn main() {
+/// set_global_format(FormatOptions::new().units(Units::Long));
+/// let foo = Foo { timeout: Duration::from_secs(5400) };
+/// assert_eq!(serde_json::to_string(&foo).unwrap(), r#"{"timeout":"1 hour 30 minutes"}"#);
+/// # }
+/// ```
+pub fn set_global_format(options: FormatOptions) {
+    *GLOBAL_FORMAT.write().unwrap_or_else(|e| e.into_inner()) = options;
+    GLOBAL_FORMAT_SET.store(true, Ordering::Release);
+}
+
+/// Calls a closure with the options used to format durations serialized
+/// through the crate root, the `option` module and `De` on this thread
+/// replaced, restoring the previous options when it returns or panics.
+///
+/// Unlike `set_global_format`, this only affects serialization performed by
+/// the closure, so a library can use it for the output it produces without
+/// changing that of the rest of the process.
+///
+/// # Examples
+///
+/// ```
+/// extern crate serde_humantime;
+/// extern crate serde;
+/// #[macro_use]
+/// extern crate serde_derive;
+/// extern crate serde_json;
+///
+/// use serde_humantime::format::{with_format, FormatOptions, Units};
+/// use std::time::Duration;
+///
+/// #[derive(Serialize)]
+/// struct Foo {
+///     #[serde(with = "serde_humantime")]
+///     timeout: Duration,
+/// }
+///
+/// # fn main() {
+/// let foo = Foo { timeout: Duration::from_secs(5400) };
+/// let long = with_format(FormatOptions::new().units(Units::Long),
+///                        || serde_json::to_string(&foo).unwrap());
+/// assert_eq!(long, r#"{"timeout":"1 hour 30 minutes"}"#);
+/// assert_eq!(serde_json::to_string(&foo).unwrap(), r#"{"timeout":"1h 30m"}"#);
+/// # }
+/// ```
+pub fn with_format<F, R>(options: FormatOptions, f: F) -> R
+    where F: FnOnce() -> R
+{
+    struct Restore(Option<FormatOptions>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            SCOPED_FORMAT.with(|c| c.set(self.0));
+        }
+    }
+
+    let _restore = Restore(SCOPED_FORMAT.with(|c| c.replace(Some(options))));
+    f()
+}
+
+/// Returns the options set by `with_format` on this thread, or otherwise by
+/// `set_global_format`.
+pub(crate) fn global_format() -> FormatOptions {
+    if let Some(options) = SCOPED_FORMAT.with(Cell::get) {
+        return options;
+    }
+    if !GLOBAL_FORMAT_SET.load(Ordering::Acquire) {
+        return FormatOptions::new();
+    }
+    *GLOBAL_FORMAT.read().unwrap_or_else(|e| e.into_inner())
+}
+
+/// A wrapper type which formats a `Duration` via its `Display`
+/// implementation.
+#[derive(Debug, Clone, Copy)]
+pub struct FormattedDuration(Duration, FormatOptions);
+
+/// Returns a value which formats a `Duration` via its `Display`
+/// implementation.
+///
+/// # Examples
+///
+/// ```
+/// use serde_humantime::format::format_duration;
+/// use std::time::Duration;
+///
+/// assert_eq!(format_duration(Duration::new(9420, 0)).to_string(), "2h 37m");
+/// ```
+pub fn format_duration(d: Duration) -> FormattedDuration {
+    FormatOptions::new().format_duration(d)
+}
+
+impl fmt::Display for FormattedDuration {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        if self.1.fixed_width {
+            let secs = self.0.as_secs();
+            return write!(fmt,
+                          "{:03}d {:02}h {:02}m {:02}s",
+                          secs / DAY,
+                          secs % DAY / 3600,
+                          secs % 3600 / 60,
+                          secs % 60);
+        }
+
+        let names = self.1.units.names();
+        let separator = self.1.separator.unwrap_or_else(|| self.1.units.separator());
+
+        let mut largest = self.1.max_unit as usize;
+        if self.0 == Duration::from_secs(0) {
+            return write!(fmt, "0{}{}", separator, names[largest.max(5)].1);
+        }
+
+        if self.1.single_unit {
+            let nanos = self.0.as_nanos();
+            // every duration is a whole number of nanoseconds
+            let i = (largest..UNIT_NANOS.len()).find(|&i| nanos.is_multiple_of(UNIT_NANOS[i])).unwrap();
+            // the parser sums units shorter than a second in nanoseconds, so
+            // longer durations are written with multiple units instead
+            if UNIT_NANOS[i] >= 1_000_000_000 || nanos <= u64::MAX as u128 {
+                let value = nanos / UNIT_NANOS[i];
+                let unit = if value == 1 { names[i].0 } else { names[i].1 };
+                return write!(fmt, "{}{}{}", value, separator, unit);
+            }
+        }
+
+        // as above, durations too long to parse back in nanoseconds need a
+        // unit of at least a second
+        if self.0.as_nanos() > u64::MAX as u128 {
+            largest = largest.min(Unit::Seconds as usize);
+        }
+        let components = split(self.0, largest);
+
+        let mut remaining = components.iter().filter(|&&v| v != 0).count();
+        let mut started = false;
+        for (&value, &(singular, plural)) in components.iter().zip(names) {
+            if value == 0 {
+                continue;
+            }
+            remaining -= 1;
+            if started {
+                fmt.write_str(match (self.1.list, remaining) {
+                    (false, _) => " ",
+                    (true, 0) => " and ",
+                    (true, _) => ", ",
+                })?;
+            }
+            let unit = if value == 1 { singular } else { plural };
+            write!(fmt, "{}{}{}", value, separator, unit)?;
+            started = true;
+        }
+        Ok(())
+    }
+}
+
+/// A wrapper type which formats a `SystemTime` as an RFC 3339 timestamp via
+/// its `Display` implementation.
+///
+/// Formatting fails for timestamps which can't be represented.
+#[cfg(feature = "systemtime")]
+#[derive(Debug, Clone, Copy)]
+pub struct FormattedTimestamp(SystemTime, bool, i32);
+
+/// Returns a value which formats a `SystemTime` as an RFC 3339 timestamp via
+/// its `Display` implementation.
+///
+/// # Examples
+///
+/// ```
+/// use serde_humantime::format::format_rfc3339;
+/// use std::time::{Duration, UNIX_EPOCH};
+///
+/// let time = UNIX_EPOCH + Duration::from_secs(1518563312);
+/// assert_eq!(format_rfc3339(time).to_string(), "2018-02-13T23:08:32Z");
+/// ```
+#[cfg(feature = "systemtime")]
+pub fn format_rfc3339(t: SystemTime) -> FormattedTimestamp {
+    FormattedTimestamp(t, false, 0)
+}
+
+/// Returns a value which formats a `SystemTime` as an RFC 3339 timestamp
+/// with exactly nine fractional digits via its `Display` implementation.
+///
+/// Every formatted timestamp has the same length, so they sort as strings in
+/// the same order as the timestamps themselves.
+///
+/// # Examples
+///
+/// ```
+/// use serde_humantime::format::format_rfc3339_nanos;
+/// use std::time::{Duration, UNIX_EPOCH};
+///
+/// let time = UNIX_EPOCH + Duration::from_secs(1518563312);
+/// assert_eq!(format_rfc3339_nanos(time).to_string(), "2018-02-13T23:08:32.000000000Z");
+/// ```
+#[cfg(feature = "systemtime")]
+pub fn format_rfc3339_nanos(t: SystemTime) -> FormattedTimestamp {
+    FormattedTimestamp(t, true, 0)
+}
+
+/// Returns a value which formats a `SystemTime` as an RFC 3339 timestamp at
+/// a fixed UTC offset via its `Display` implementation.
+///
+/// The offset is written numerically, or as `Z` if it is zero.
+///
+/// # Examples
+///
+/// ```
+/// use serde_humantime::format::format_rfc3339_at;
+/// use serde_humantime::offset::UtcOffset;
+/// use std::time::{Duration, UNIX_EPOCH};
+///
+/// let time = UNIX_EPOCH + Duration::from_secs(1518563312);
+/// let jst = "+09:00".parse::<UtcOffset>().unwrap();
+/// assert_eq!(format_rfc3339_at(time, jst).to_string(), "2018-02-14T08:08:32+09:00");
+/// ```
+#[cfg(feature = "systemtime")]
+pub fn format_rfc3339_at(t: SystemTime, offset: UtcOffset) -> FormattedTimestamp {
+    FormattedTimestamp(t, false, offset.seconds())
+}
+
+#[cfg(feature = "systemtime")]
+impl fmt::Display for FormattedTimestamp {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        let (secs, nanos) = civil::to_unix(self.0);
+        if secs < 0 {
+            return Err(fmt::Error);
+        }
+        let local_secs = secs + self.2 as i64;
+        if self.1 {
+            write_datetime(fmt, local_secs, 0)?;
+            write!(fmt, ".{:09}", nanos)?;
+        } else {
+            write_datetime(fmt, local_secs, nanos)?;
+        }
+        if self.2 == 0 {
+            fmt.write_str("Z")
+        } else {
+            write_offset(fmt, self.2)
+        }
+    }
+}
+
+/// An extension trait adding human readable `Display` adapters to
+/// `Duration` and `SystemTime`.
+///
+/// The adapters produce the same output as serialization, making it
+/// available to log lines and command line output.
+///
+/// # Examples
+///
+/// ```
+/// use serde_humantime::format::{FormatOptions, Humanize, Units};
+/// use std::time::Duration;
+///
+/// let d = Duration::from_secs(5400);
+/// assert_eq!(format!("took {}", d.humanize()), "took 1h 30m");
+///
+/// let options = FormatOptions::new().units(Units::Long);
+/// assert_eq!(format!("took {}", d.humanize_with(&options)), "took 1 hour 30 minutes");
+/// ```
+pub trait Humanize {
+    /// The `Display` adapter.
+    type Display: fmt::Display;
+
+    /// Returns an adapter formatting the value in the default format.
+    fn humanize(&self) -> Self::Display;
+
+    /// Returns an adapter formatting the value with the provided options.
+    ///
+    /// Options which don't apply to the value's type are ignored.
+    fn humanize_with(&self, options: &FormatOptions) -> Self::Display;
+}
+
+impl Humanize for Duration {
+    type Display = FormattedDuration;
+
+    fn humanize(&self) -> FormattedDuration {
+        format_duration(*self)
+    }
+
+    fn humanize_with(&self, options: &FormatOptions) -> FormattedDuration {
+        options.format_duration(*self)
+    }
+}
+
+/// Timestamps are formatted as RFC 3339 timestamps.
+#[cfg(feature = "systemtime")]
+impl Humanize for SystemTime {
+    type Display = HumanizedTimestamp;
+
+    fn humanize(&self) -> HumanizedTimestamp {
+        HumanizedTimestamp(*self)
+    }
+
+    fn humanize_with(&self, _: &FormatOptions) -> HumanizedTimestamp {
+        HumanizedTimestamp(*self)
+    }
+}
+
+/// The `Display` adapter returned by `Humanize` for `SystemTime`s.
+///
+/// Timestamps are written as RFC 3339 timestamps, except for those before
+/// 1970 or after year 9999, which are written in their `Debug` form since
+/// RFC 3339 can't represent them.
+#[cfg(feature = "systemtime")]
+#[derive(Debug, Clone, Copy)]
+pub struct HumanizedTimestamp(SystemTime);
+
+#[cfg(feature = "systemtime")]
+impl fmt::Display for HumanizedTimestamp {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        if rfc3339::in_range(&self.0) {
+            fmt::Display::fmt(&format_rfc3339(self.0), fmt)
+        } else {
+            fmt::Debug::fmt(&self.0, fmt)
+        }
+    }
+}
+
+/// Serializes a value as the string produced by its `Display`
+/// implementation, returning an error with a message if formatting fails.
+///
+/// Formatting into a buffer first turns failures into serialization errors
+/// rather than panics in serializers which use `to_string` for
+/// `collect_str`.
+pub(crate) fn serialize_display<T, S>(value: &T, s: S, msg: &'static str) -> Result<S::Ok, S::Error>
+    where T: fmt::Display + ?Sized,
+          S: Serializer
+{
+    let mut buf = String::new();
+    fmt::write(&mut buf, format_args!("{}", value)).map_err(|_| ser::Error::custom(msg))?;
+    s.serialize_str(&buf)
+}
+
+/// Writes the date and time of a Unix timestamp, without a timezone
+/// designator.
+#[cfg(feature = "systemtime")]
+pub(crate) fn write_datetime(fmt: &mut fmt::Formatter, secs: i64, nanos: u32) -> fmt::Result {
+    let (year, month, day) = civil::civil_from_days(secs.div_euclid(civil::SECS_PER_DAY));
+    if !(0..=9999).contains(&year) {
+        return Err(fmt::Error);
+    }
+    let secs_of_day = secs.rem_euclid(civil::SECS_PER_DAY);
+
+    write!(fmt,
+           "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+           year,
+           month,
+           day,
+           secs_of_day / 3600,
+           secs_of_day % 3600 / 60,
+           secs_of_day % 60)?;
+    if nanos != 0 {
+        write!(fmt, ".{:09}", nanos)?;
+    }
+    Ok(())
+}
+
+/// Writes a UTC offset in the form `+HH:MM`.
+#[cfg(feature = "systemtime")]
+pub(crate) fn write_offset(fmt: &mut fmt::Formatter, offset: i32) -> fmt::Result {
+    let sign = if offset < 0 { '-' } else { '+' };
+    let offset = offset.unsigned_abs();
+    write!(fmt, "{}{:02}:{:02}", sign, offset / 3600, offset % 3600 / 60)
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+    #[cfg(feature = "systemtime")]
+    use std::time::UNIX_EPOCH;
+
+    use super::*;
+
+    const YEAR: u64 = 31557600;
+    const MONTH: u64 = 2630016;
+
+    #[test]
+    fn duration() {
+        let cases = [(Duration::from_secs(0), "0s"),
+                     (Duration::new(90061, 500_000_000), "1day 1h 1m 1s 500ms"),
+                     (Duration::new(2 * YEAR + 2 * MONTH + 2 * DAY, 2_002_002),
+                      "2years 2months 2days 2ms 2us 2ns"),
+                     (Duration::new(MONTH, 0), "1month"),
+                     (Duration::new(MONTH - 1, 0), "30days 10h 33m 35s"),
+                     (Duration::new(u64::MAX, 999_999_999),
+                      "584542046090years 7months 15days 17h 5m 3s 999ms 999us 999ns")];
+        for &(d, expected) in &cases {
+            assert_eq!(format_duration(d).to_string(), expected);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "systemtime")]
+    fn humanize() {
+        let t = UNIX_EPOCH + Duration::from_secs(1518563312);
+        assert_eq!(t.humanize().to_string(), "2018-02-13T23:08:32Z");
+        let early = UNIX_EPOCH - Duration::from_secs(1);
+        assert_eq!(format!("at {}", early.humanize()), format!("at {:?}", early));
+        assert_eq!(Duration::new(90, 5).humanize().to_string(), "1m 30s 5ns");
+    }
+
+    #[test]
+    fn long_units() {
+        let options = FormatOptions::new().units(Units::Long);
+        let cases = [(Duration::from_secs(0), "0 seconds"),
+                     (Duration::new(90061, 1_000_000), "1 day 1 hour 1 minute 1 second 1 millisecond"),
+                     (Duration::new(2 * YEAR + 2 * MONTH + 3 * 3600, 2_002),
+                      "2 years 2 months 3 hours 2 microseconds 2 nanoseconds")];
+        for &(d, expected) in &cases {
+            assert_eq!(options.format_duration(d).to_string(), expected);
+        }
+    }
+
+    #[test]
+    fn unit_separator() {
+        let d = Duration::from_secs(2 * DAY + 10 * 3600);
+        assert_eq!(FormatOptions::new().units(Units::Short).format_duration(d).to_string(),
+                   "2d 10h");
+        assert_eq!(FormatOptions::new().unit_separator(" ").format_duration(d).to_string(),
+                   "2 days 10 h");
+        let options = FormatOptions::new().units(Units::Long).unit_separator("");
+        assert_eq!(options.format_duration(d).to_string(), "2days 10hours");
+        assert_eq!(options.format_duration(Duration::from_secs(0)).to_string(), "0seconds");
+    }
+
+    #[test]
+    fn list() {
+        let options = FormatOptions::new().units(Units::Long).list(true);
+        let cases = [(Duration::from_secs(0), "0 seconds"),
+                     (Duration::from_secs(3600), "1 hour"),
+                     (Duration::from_secs(3660), "1 hour and 1 minute"),
+                     (Duration::from_secs(2 * DAY + 3 * 3600 + 5 * 60),
+                      "2 days, 3 hours and 5 minutes")];
+        for &(d, expected) in &cases {
+            assert_eq!(options.format_duration(d).to_string(), expected);
+        }
+        assert_eq!(FormatOptions::new().list(true).format_duration(Duration::new(61, 5))
+                       .to_string(),
+                   "1m, 1s and 5ns");
+    }
+
+    #[test]
+    fn single_unit() {
+        let options = FormatOptions::new().single_unit(true);
+        let cases = [(Duration::from_secs(0), "0s"),
+                     (Duration::new(1, 500_000_000), "1500ms"),
+                     (Duration::from_secs(5400), "90m"),
+                     (Duration::from_secs(DAY), "1day"),
+                     (Duration::from_secs(2 * MONTH), "2months"),
+                     (Duration::new(1, 1), "1000000001ns"),
+                     (Duration::new(u64::MAX, 1), "584542046090years 7months 15days 17h 5m 3s 1ns")];
+        for &(d, expected) in &cases {
+            assert_eq!(options.format_duration(d).to_string(), expected);
+        }
+        let options = options.units(Units::Long);
+        assert_eq!(options.format_duration(Duration::from_secs(3600)).to_string(), "1 hour");
+    }
+
+    #[test]
+    fn max_unit() {
+        let options = FormatOptions::new().max_unit(Unit::Hours);
+        let cases = [(Duration::from_secs(0), "0s"),
+                     (Duration::from_secs(129600), "36h"),
+                     (Duration::new(YEAR + 61, 5), "8766h 1m 1s 5ns")];
+        for &(d, expected) in &cases {
+            assert_eq!(options.format_duration(d).to_string(), expected);
+        }
+
+        let options = FormatOptions::new().max_unit(Unit::Milliseconds);
+        assert_eq!(options.format_duration(Duration::from_secs(0)).to_string(), "0ms");
+        assert_eq!(options.format_duration(Duration::new(90, 1000)).to_string(), "90000ms 1us");
+        assert_eq!(options.single_unit(true).format_duration(Duration::from_secs(DAY)).to_string(),
+                   "86400000ms");
+        assert_eq!(options.format_duration(Duration::new(u64::MAX, 1000)).to_string(),
+                   "18446744073709551615s 1us");
+    }
+
+    #[test]
+    fn scoped_format() {
+        use std::panic;
+
+        let d = Duration::from_secs(5400);
+        let long = FormatOptions::new().units(Units::Long);
+        let short = FormatOptions::new().units(Units::Short);
+        assert_eq!(global_format().format_duration(d).to_string(), "1h 30m");
+        with_format(long, || {
+            assert_eq!(global_format().format_duration(d).to_string(), "1 hour 30 minutes");
+            with_format(short.max_unit(Unit::Minutes), || {
+                assert_eq!(global_format().format_duration(d).to_string(), "90m");
+            });
+            assert_eq!(global_format().format_duration(d).to_string(), "1 hour 30 minutes");
+        });
+        assert!(panic::catch_unwind(|| with_format(short, || panic!())).is_err());
+        assert_eq!(global_format().format_duration(d).to_string(), "1h 30m");
+    }
+
+    #[test]
+    fn fixed_width() {
+        let options = FormatOptions::new().fixed_width(true).units(Units::Long);
+        let cases = [(Duration::from_secs(0), "000d 00h 00m 00s"),
+                     (Duration::new(59, 999_999_999), "000d 00h 00m 59s"),
+                     (Duration::from_secs(2 * DAY + 3 * 3600 + 5 * 60), "002d 03h 05m 00s"),
+                     (Duration::from_secs(1234 * DAY + 1), "1234d 00h 00m 01s")];
+        for &(d, expected) in &cases {
+            assert_eq!(options.format_duration(d).to_string(), expected);
+        }
+
+        let mut sorted = cases.iter().map(|&(d, _)| options.format_duration(d).to_string())
+            .take(3)
+            .collect::<Vec<_>>();
+        sorted.reverse();
+        sorted.sort();
+        assert_eq!(sorted[0], "000d 00h 00m 00s");
+        assert_eq!(sorted[2], "002d 03h 05m 00s");
+    }
+
+    #[test]
+    #[cfg(feature = "de")]
+    fn round_trip() {
+        use parse::parse_duration;
+
+        let mut styles = vec![FormatOptions::new(),
+                              FormatOptions::new().units(Units::Short),
+                              FormatOptions::new().units(Units::Long),
+                              FormatOptions::new().units(Units::Long).list(true),
+                              FormatOptions::new().unit_separator(" ").list(true),
+                              FormatOptions::new().units(Units::Long).unit_separator(""),
+                              FormatOptions::new().single_unit(true)];
+        for &unit in &[Unit::Years,
+                       Unit::Months,
+                       Unit::Days,
+                       Unit::Hours,
+                       Unit::Minutes,
+                       Unit::Seconds,
+                       Unit::Milliseconds,
+                       Unit::Microseconds,
+                       Unit::Nanoseconds] {
+            styles.push(FormatOptions::new().max_unit(unit));
+            styles.push(FormatOptions::new().max_unit(unit).single_unit(true));
+        }
+        let mut durations = vec![Duration::new(u64::MAX, 999_999_999),
+                                 Duration::new(u64::MAX, 0),
+                                 Duration::new(0, 1),
+                                 Duration::new(YEAR - 1, 999_999_999),
+                                 Duration::new(MONTH * 11 + DAY * 29, 1)];
+        let mut secs = 0;
+        while secs < u64::MAX / 3 {
+            for &nanos in &[0, 1, 7, 999, 1000, 1001, 999_999, 1_000_001, 123_456_789, 999_999_999] {
+                durations.push(Duration::new(secs, nanos));
+            }
+            secs = secs * 3 + 7;
+        }
+
+        for options in &styles {
+            for &d in &durations {
+                let s = options.format_duration(d).to_string();
+                assert_eq!(parse_duration(&s).ok(), Some(d), "{:?} {}", options, s);
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "humantime")]
+    fn matches_humantime() {
+        use humantime;
+
+        let mut secs = 1;
+        while secs < u64::MAX / 3 {
+            for &nanos in &[0, 1, 999, 1000, 123_456_789] {
+                let d = Duration::new(secs, nanos);
+                assert_eq!(format_duration(d).to_string(),
+                           humantime::format_duration(d).to_string());
+                #[cfg(feature = "systemtime")]
+                if secs < 253402300800 {
+                    let t = UNIX_EPOCH + d;
+                    assert_eq!(format_rfc3339(t).to_string(),
+                               humantime::format_rfc3339(t).to_string());
+                }
+            }
+            secs = secs * 3 + 7;
+        }
+    }
+
+    #[cfg(feature = "systemtime")]
+    #[test]
+    fn timestamp_range() {
+        use std::fmt::Write;
+
+        let mut s = String::new();
+        assert!(write!(s, "{}", format_rfc3339(UNIX_EPOCH - Duration::new(0, 1))).is_err());
+        let t = UNIX_EPOCH + Duration::from_secs(253402300800);
+        assert!(write!(s, "{}", format_rfc3339(t)).is_err());
+    }
+}
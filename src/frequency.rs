@@ -0,0 +1,223 @@
+//! Frequencies.
+#[cfg(feature = "de")]
+use serde::de::{Deserialize, Deserializer, Visitor, Error, Unexpected};
+#[cfg(feature = "ser")]
+use serde::ser::{Serialize, Serializer};
+#[cfg(feature = "de")]
+use std::error;
+#[cfg(any(feature = "ser", feature = "de"))]
+use std::fmt;
+#[cfg(feature = "de")]
+use std::str::FromStr;
+use std::time::Duration;
+
+#[cfg(feature = "ser")]
+use format;
+#[cfg(feature = "de")]
+use parse;
+
+const NANOS_PER_SEC: u64 = 1_000_000_000;
+
+/// A frequency, such as a sampling rate.
+///
+/// It is parsed from either a number of hertz, like `10 Hz`, `2.5Hz` or
+/// `1 kHz`, or a period, like `every 100ms` or just `100ms`. It is stored as
+/// a nonzero period, so frequencies in hertz are rounded to the nearest
+/// nanosecond of period.
+///
+/// Frequencies are formatted in hertz if their period evenly divides one
+/// second, as in `10 Hz`, and as a period otherwise, as in `every 1m 30s`.
+/// They are serialized and deserialized as such strings.
+///
+/// # Examples
+///
+/// ```
+/// use serde_humantime::frequency::Frequency;
+/// use std::time::Duration;
+///
+/// let frequency = "10 Hz".parse::<Frequency>().unwrap();
+/// assert_eq!(frequency.period(), Duration::from_millis(100));
+/// assert_eq!("every 100ms".parse::<Frequency>().unwrap(), frequency);
+/// assert_eq!(frequency.to_string(), "10 Hz");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Frequency {
+    period: Duration,
+}
+
+impl Frequency {
+    /// Creates a `Frequency` from its period.
+    ///
+    /// Returns `None` if the period is zero.
+    pub fn from_period(period: Duration) -> Option<Frequency> {
+        if period == Duration::from_secs(0) {
+            return None;
+        }
+        Some(Frequency { period })
+    }
+
+    /// Creates a `Frequency` from a number of hertz.
+    ///
+    /// Returns `None` if the frequency isn't positive and finite, or if its
+    /// period can't be represented.
+    pub fn from_hertz(hertz: f64) -> Option<Frequency> {
+        if !(hertz > 0. && hertz.is_finite()) {
+            return None;
+        }
+        let nanos = (NANOS_PER_SEC as f64 / hertz).round();
+        if nanos >= u128::MAX as f64 {
+            return None;
+        }
+        let nanos = nanos as u128;
+        let secs = nanos / NANOS_PER_SEC as u128;
+        if secs > u64::MAX as u128 {
+            return None;
+        }
+        Frequency::from_period(Duration::new(secs as u64, (nanos % NANOS_PER_SEC as u128) as u32))
+    }
+
+    /// Returns the period of the frequency.
+    pub fn period(&self) -> Duration {
+        self.period
+    }
+
+    /// Returns the frequency in hertz.
+    pub fn hertz(&self) -> f64 {
+        1. / self.period.as_secs_f64()
+    }
+}
+
+impl From<Frequency> for Duration {
+    fn from(frequency: Frequency) -> Duration {
+        frequency.period
+    }
+}
+
+#[cfg(feature = "ser")]
+impl fmt::Display for Frequency {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        let nanos = self.period.as_nanos();
+        if (NANOS_PER_SEC as u128).is_multiple_of(nanos) {
+            write!(fmt, "{} Hz", NANOS_PER_SEC as u128 / nanos)
+        } else {
+            write!(fmt, "every {}", format::format_duration(self.period))
+        }
+    }
+}
+
+/// An error parsing a `Frequency`.
+#[derive(Debug)]
+#[cfg(feature = "de")]
+pub struct ParseFrequencyError(());
+
+#[cfg(feature = "de")]
+impl fmt::Display for ParseFrequencyError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str("invalid frequency")
+    }
+}
+
+#[cfg(feature = "de")]
+impl error::Error for ParseFrequencyError {}
+
+#[cfg(feature = "de")]
+impl FromStr for Frequency {
+    type Err = ParseFrequencyError;
+
+    fn from_str(s: &str) -> Result<Frequency, ParseFrequencyError> {
+        parse(s).ok_or(ParseFrequencyError(()))
+    }
+}
+
+#[cfg(feature = "de")]
+fn parse(s: &str) -> Option<Frequency> {
+    let s = s.trim();
+    if let Some(period) = s.strip_prefix("every ") {
+        return Frequency::from_period(parse::parse_duration(period).ok()?);
+    }
+
+    let (number, scale) = if let Some(number) = s.strip_suffix("kHz") {
+        (number, 1000.)
+    } else if let Some(number) = s.strip_suffix("Hz") {
+        (number, 1.)
+    } else {
+        return Frequency::from_period(parse::parse_duration(s).ok()?);
+    };
+    // only plain decimals, not `inf` or exponents
+    let number = number.trim_end();
+    if number.is_empty() || !number.chars().all(|c| c.is_ascii_digit() || c == '.') {
+        return None;
+    }
+    Frequency::from_hertz(number.parse::<f64>().ok()? * scale)
+}
+
+#[cfg(feature = "ser")]
+impl Serialize for Frequency {
+    fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        s.collect_str(self)
+    }
+}
+
+#[cfg(feature = "de")]
+impl<'de> Deserialize<'de> for Frequency {
+    fn deserialize<D>(d: D) -> Result<Frequency, D::Error>
+        where D: Deserializer<'de>
+    {
+        struct V;
+
+        impl<'de2> Visitor<'de2> for V {
+            type Value = Frequency;
+
+            fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+                fmt.write_str("a frequency")
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Frequency, E>
+                where E: Error
+            {
+                v.parse().map_err(|_| E::invalid_value(Unexpected::Str(v), &self))
+            }
+        }
+
+        d.deserialize_str(V)
+    }
+}
+
+#[cfg(all(test, feature = "ser", feature = "de"))]
+mod test {
+    use serde_json;
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let cases = [("10 Hz", Duration::from_millis(100)),
+                     ("2.5Hz", Duration::from_millis(400)),
+                     ("1 kHz", Duration::from_millis(1)),
+                     ("3 Hz", Duration::new(0, 333_333_333)),
+                     ("every 1m 30s", Duration::from_secs(90)),
+                     ("250ms", Duration::from_millis(250))];
+        for &(s, period) in &cases {
+            assert_eq!(s.parse::<Frequency>().unwrap().period(), period, "{}", s);
+        }
+        for s in &["", "Hz", "0 Hz", "-1 Hz", "inf Hz", "1e3 Hz", "every 0s", "every", "10 MHz"] {
+            assert!(s.parse::<Frequency>().is_err(), "{}", s);
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        for &(s, expected) in &[("10 Hz", "10 Hz"),
+                                ("every 1s", "1 Hz"),
+                                ("0.5 Hz", "every 2s"),
+                                ("3 Hz", "every 333ms 333us 333ns")] {
+            let frequency = serde_json::from_str::<Frequency>(&format!("\"{}\"", s)).unwrap();
+            assert_eq!(serde_json::to_string(&frequency).unwrap(), format!("\"{}\"", expected));
+            assert_eq!(expected.parse::<Frequency>().unwrap(), frequency);
+        }
+        assert!((Frequency::from_hertz(2.5).unwrap().hertz() - 2.5).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,160 @@
+//! GraphQL scalar implementations for `async-graphql`.
+//!
+//! This module is only available with the `async-graphql` feature. It exposes
+//! `De<Duration>` and `De<SystemTime>` as the `Duration` and `Timestamp`
+//! scalars, represented as humantime duration strings and RFC 3339
+//! timestamps respectively.
+#[cfg(feature = "systemtime")]
+use async_graphql::{ContextSelectionSet, InputType, OutputType, Positioned, ServerError, ServerResult};
+use async_graphql::{InputValueError, InputValueResult, Scalar, ScalarType, Value};
+#[cfg(feature = "systemtime")]
+use async_graphql::parser::types::Field;
+#[cfg(feature = "systemtime")]
+use async_graphql::registry::{MetaType, MetaTypeId, Registry};
+#[cfg(feature = "systemtime")]
+use std::borrow::Cow;
+#[cfg(feature = "systemtime")]
+use std::future::{self, Future};
+#[cfg(feature = "systemtime")]
+use std::sync::Arc;
+use std::time::Duration;
+#[cfg(feature = "systemtime")]
+use std::time::SystemTime;
+
+#[cfg(feature = "systemtime")]
+use rfc3339;
+use {format, parse, De};
+
+/// A duration such as `1h 30m`.
+#[Scalar(name = "Duration")]
+impl ScalarType for De<Duration> {
+    fn parse(value: Value) -> InputValueResult<Self> {
+        match value {
+            Value::String(ref s) => match parse::parse_duration(s) {
+                Ok(d) => Ok(De::new(d)),
+                Err(e) => Err(InputValueError::custom(format!("invalid duration `{}`: {}", s, e))),
+            },
+            value => Err(InputValueError::expected_type(value)),
+        }
+    }
+
+    fn to_value(&self) -> Value {
+        Value::String(format::format_duration(self.0).to_string())
+    }
+}
+
+/// An RFC 3339 timestamp such as `2018-02-14T00:28:07Z`.
+///
+/// Resolving a timestamp RFC 3339 can't represent, before 1970 or after year
+/// 9999, fails with a field error, as serializing it with the `rfc3339`
+/// module does. The `InputType` and `OutputType` implementations are written
+/// by hand rather than derived from a `ScalarType` implementation for this
+/// reason, so they aren't available with async-graphql's `boxed-trait`
+/// feature.
+#[cfg(feature = "systemtime")]
+impl InputType for De<SystemTime> {
+    type RawValueType = Self;
+
+    fn type_name() -> Cow<'static, str> {
+        Cow::Borrowed("Timestamp")
+    }
+
+    fn create_type_info(registry: &mut Registry) -> String {
+        registry.create_input_type::<De<SystemTime>, _>(MetaTypeId::Scalar, |_| timestamp_type())
+    }
+
+    fn parse(value: Option<Value>) -> InputValueResult<Self> {
+        match value.unwrap_or_default() {
+            Value::String(ref s) => match rfc3339::parse(s) {
+                Ok(t) => Ok(De::new(t)),
+                Err(e) => Err(InputValueError::custom(format!("invalid timestamp `{}`: {}", s, e))),
+            },
+            value => Err(InputValueError::expected_type(value)),
+        }
+    }
+
+    fn to_value(&self) -> Value {
+        rfc3339::format_checked(self.0).map(Value::String).unwrap_or(Value::Null)
+    }
+
+    fn as_raw_value(&self) -> Option<&Self> {
+        Some(self)
+    }
+}
+
+#[cfg(feature = "systemtime")]
+impl OutputType for De<SystemTime> {
+    fn type_name() -> Cow<'static, str> {
+        Cow::Borrowed("Timestamp")
+    }
+
+    fn create_type_info(registry: &mut Registry) -> String {
+        registry.create_output_type::<De<SystemTime>, _>(MetaTypeId::Scalar, |_| timestamp_type())
+    }
+
+    fn resolve(&self,
+               _: &ContextSelectionSet,
+               field: &Positioned<Field>)
+               -> impl Future<Output = ServerResult<Value>> + Send {
+        future::ready(rfc3339::format_checked(self.0)
+                          .map(Value::String)
+                          .map_err(|e| ServerError::new(e, Some(field.pos))))
+    }
+}
+
+#[cfg(feature = "systemtime")]
+fn timestamp_type() -> MetaType {
+    MetaType::Scalar {
+        name: "Timestamp".to_string(),
+        description: Some("An RFC 3339 timestamp such as `2018-02-14T00:28:07Z`.".to_string()),
+        is_valid: Some(Arc::new(|value| match *value {
+            Value::String(ref s) => rfc3339::parse(s).is_ok(),
+            _ => false,
+        })),
+        visible: None,
+        inaccessible: false,
+        tags: vec![],
+        specified_by_url: None,
+        directive_invocations: vec![],
+        requires_scopes: vec![],
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[cfg(feature = "systemtime")]
+    use async_graphql::InputType;
+    use async_graphql::{ScalarType, Value};
+    use std::time::Duration;
+    #[cfg(feature = "systemtime")]
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use De;
+
+    #[test]
+    fn duration() {
+        let value = Value::String("1h 30m".to_string());
+        let d = <De<Duration> as ScalarType>::parse(value.clone()).ok().map(De::into_inner);
+        assert_eq!(d, Some(Duration::from_secs(5400)));
+        assert_eq!(ScalarType::to_value(&De::new(Duration::from_secs(5400))), value);
+        assert!(<De<Duration> as ScalarType>::parse(Value::String("bogus".to_string())).is_err());
+        assert!(<De<Duration> as ScalarType>::parse(Value::Boolean(true)).is_err());
+    }
+
+    #[cfg(feature = "systemtime")]
+    #[test]
+    fn timestamp() {
+        let value = Value::String("2018-02-13T23:08:32Z".to_string());
+        let t = UNIX_EPOCH + Duration::from_secs(1518563312);
+        let parsed = <De<SystemTime> as InputType>::parse(Some(value.clone())).ok().map(De::into_inner);
+        assert_eq!(parsed, Some(t));
+        assert_eq!(De::new(t).to_value(), value);
+        assert!(<De<SystemTime> as InputType>::parse(Some(Value::String("bogus".to_string()))).is_err());
+
+        // resolving these is an error; as input values they are null
+        let early = De::new(UNIX_EPOCH - Duration::from_secs(1));
+        assert_eq!(early.to_value(), Value::Null);
+        let late = De::new(UNIX_EPOCH + Duration::from_secs(1 << 40));
+        assert_eq!(late.to_value(), Value::Null);
+    }
+}
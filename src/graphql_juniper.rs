@@ -0,0 +1,100 @@
+//! GraphQL scalar implementations for `juniper`.
+//!
+//! This module is only available with the `juniper` feature. It exposes
+//! `De<Duration>` and `De<SystemTime>` as the `Duration` and `Timestamp`
+//! scalars, represented as humantime duration strings and RFC 3339
+//! timestamps respectively, as the `graphql` module does for
+//! `async-graphql`.
+use juniper::graphql_scalar;
+use std::time::Duration;
+#[cfg(feature = "systemtime")]
+use std::time::SystemTime;
+
+#[cfg(feature = "systemtime")]
+use rfc3339;
+use {format, parse, De};
+
+/// A duration such as `1h 30m`.
+#[graphql_scalar(name = "Duration", with = duration, parse_token(String))]
+type DurationScalar = De<Duration>;
+
+mod duration {
+    use juniper::{InputValue, ScalarValue, Value};
+
+    use super::*;
+
+    pub(super) fn to_output<S: ScalarValue>(v: &DurationScalar) -> Value<S> {
+        Value::scalar(format::format_duration(v.0).to_string())
+    }
+
+    pub(super) fn from_input<S: ScalarValue>(v: &InputValue<S>) -> Result<DurationScalar, String> {
+        let s = v.as_string_value().ok_or_else(|| format!("expected a string, found: {}", v))?;
+        parse::parse_duration(s)
+            .map(De::new)
+            .map_err(|e| format!("invalid duration `{}`: {}", s, e))
+    }
+}
+
+/// An RFC 3339 timestamp such as `2018-02-14T00:28:07Z`.
+///
+/// Timestamps RFC 3339 can't represent, before 1970 or after year 9999, are
+/// output as null, as juniper scalars can't fail to resolve. A nullable field
+/// resolves to null, while null propagates from a non-null field to its
+/// nearest nullable parent.
+#[cfg(feature = "systemtime")]
+#[graphql_scalar(name = "Timestamp", with = timestamp, parse_token(String))]
+type TimestampScalar = De<SystemTime>;
+
+#[cfg(feature = "systemtime")]
+mod timestamp {
+    use juniper::{InputValue, ScalarValue, Value};
+
+    use super::*;
+
+    pub(super) fn to_output<S: ScalarValue>(v: &TimestampScalar) -> Value<S> {
+        rfc3339::format_checked(v.0).map(Value::scalar).unwrap_or_else(|_| Value::null())
+    }
+
+    pub(super) fn from_input<S: ScalarValue>(v: &InputValue<S>) -> Result<TimestampScalar, String> {
+        let s = v.as_string_value().ok_or_else(|| format!("expected a string, found: {}", v))?;
+        rfc3339::parse(s)
+            .map(De::new)
+            .map_err(|e| format!("invalid timestamp `{}`: {}", s, e))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use juniper::{DefaultScalarValue, FromInputValue, InputValue, ToInputValue};
+    use std::time::Duration;
+    #[cfg(feature = "systemtime")]
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use De;
+
+    #[test]
+    fn duration() {
+        let value = InputValue::<DefaultScalarValue>::scalar("1h 30m".to_string());
+        let d = De::<Duration>::from_input_value(&value).ok().map(De::into_inner);
+        assert_eq!(d, Some(Duration::from_secs(5400)));
+        assert_eq!(De::new(Duration::from_secs(5400)).to_input_value(), value);
+        let value = InputValue::<DefaultScalarValue>::scalar("bogus".to_string());
+        assert!(De::<Duration>::from_input_value(&value).is_err());
+        assert!(De::<Duration>::from_input_value(&InputValue::<DefaultScalarValue>::scalar(true)).is_err());
+    }
+
+    #[cfg(feature = "systemtime")]
+    #[test]
+    fn timestamp() {
+        let value = InputValue::<DefaultScalarValue>::scalar("2018-02-13T23:08:32Z".to_string());
+        let t = UNIX_EPOCH + Duration::from_secs(1518563312);
+        let parsed = De::<SystemTime>::from_input_value(&value).ok().map(De::into_inner);
+        assert_eq!(parsed, Some(t));
+        assert_eq!(De::new(t).to_input_value(), value);
+
+        let early = De::new(UNIX_EPOCH - Duration::from_secs(1)).to_input_value();
+        assert_eq!(early, InputValue::<DefaultScalarValue>::null());
+        let late = De::new(UNIX_EPOCH + Duration::from_secs(1 << 40)).to_input_value();
+        assert_eq!(late, InputValue::<DefaultScalarValue>::null());
+    }
+}
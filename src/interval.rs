@@ -0,0 +1,226 @@
+//! Intervals of time.
+#[cfg(feature = "de")]
+use serde::de::{Deserialize, Deserializer, Visitor, Error, Unexpected};
+#[cfg(feature = "ser")]
+use serde::ser::{Serialize, Serializer};
+#[cfg(feature = "de")]
+use std::error;
+#[cfg(any(feature = "ser", feature = "de"))]
+use std::fmt;
+use std::ops::{Bound, RangeBounds};
+#[cfg(feature = "de")]
+use std::str::FromStr;
+use std::time::SystemTime;
+
+#[cfg(feature = "de")]
+use date;
+#[cfg(feature = "ser")]
+use format;
+use rfc3339;
+
+/// A half-open interval of time, such as a retention window.
+///
+/// It is written as its start and end separated by a `/`, as in ISO 8601.
+/// Each end is an RFC 3339 timestamp, a `YYYY-MM-DD` date standing for
+/// midnight UTC, or `..` for an interval unbounded on that side, as in
+/// `../2024-01-01` or `2024-01-01/..`. The start is included in the
+/// interval, and the end is not.
+///
+/// Intervals are formatted with RFC 3339 timestamps, and are serialized and
+/// deserialized as such strings.
+///
+/// # Examples
+///
+/// ```
+/// use serde_humantime::interval::Interval;
+/// use std::ops::Bound;
+/// use std::time::{Duration, UNIX_EPOCH};
+///
+/// let interval = "2020-01-01/..".parse::<Interval>().unwrap();
+/// let start = UNIX_EPOCH + Duration::from_secs(1577836800);
+/// assert_eq!(interval.start(), Bound::Included(start));
+/// assert_eq!(interval.end(), Bound::Unbounded);
+/// assert!(interval.contains(&(start + Duration::from_secs(1))));
+/// assert_eq!(interval.to_string(), "2020-01-01T00:00:00Z/..");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Interval {
+    start: Option<SystemTime>,
+    end: Option<SystemTime>,
+}
+
+impl Interval {
+    /// Creates an `Interval` from its start and end, where `None` leaves the
+    /// interval unbounded on that side.
+    ///
+    /// Returns `None` if the start is after the end, or if either is before
+    /// 1970 or after year 9999, outside of the range of RFC 3339 timestamps.
+    pub fn new(start: Option<SystemTime>, end: Option<SystemTime>) -> Option<Interval> {
+        if start.iter().chain(end.iter()).any(|t| !rfc3339::in_range(t)) {
+            return None;
+        }
+        if let (Some(start), Some(end)) = (start, end) {
+            if start > end {
+                return None;
+            }
+        }
+        Some(Interval { start, end })
+    }
+
+    /// Returns the start of the interval.
+    pub fn start(&self) -> Bound<SystemTime> {
+        self.start.map_or(Bound::Unbounded, Bound::Included)
+    }
+
+    /// Returns the end of the interval.
+    pub fn end(&self) -> Bound<SystemTime> {
+        self.end.map_or(Bound::Unbounded, Bound::Excluded)
+    }
+
+    /// Determines if a timestamp is within the interval.
+    pub fn contains(&self, t: &SystemTime) -> bool {
+        RangeBounds::contains(self, t)
+    }
+}
+
+impl RangeBounds<SystemTime> for Interval {
+    fn start_bound(&self) -> Bound<&SystemTime> {
+        self.start.as_ref().map_or(Bound::Unbounded, Bound::Included)
+    }
+
+    fn end_bound(&self) -> Bound<&SystemTime> {
+        self.end.as_ref().map_or(Bound::Unbounded, Bound::Excluded)
+    }
+}
+
+#[cfg(feature = "ser")]
+impl fmt::Display for Interval {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self.start {
+            Some(start) => write!(fmt, "{}", format::format_rfc3339(start))?,
+            None => fmt.write_str("..")?,
+        }
+        fmt.write_str("/")?;
+        match self.end {
+            Some(end) => write!(fmt, "{}", format::format_rfc3339(end)),
+            None => fmt.write_str(".."),
+        }
+    }
+}
+
+/// An error parsing an `Interval`.
+#[derive(Debug)]
+#[cfg(feature = "de")]
+pub struct ParseIntervalError(());
+
+#[cfg(feature = "de")]
+impl fmt::Display for ParseIntervalError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str("invalid interval")
+    }
+}
+
+#[cfg(feature = "de")]
+impl error::Error for ParseIntervalError {}
+
+#[cfg(feature = "de")]
+impl FromStr for Interval {
+    type Err = ParseIntervalError;
+
+    fn from_str(s: &str) -> Result<Interval, ParseIntervalError> {
+        parse(s).ok_or(ParseIntervalError(()))
+    }
+}
+
+#[cfg(feature = "de")]
+fn parse(s: &str) -> Option<Interval> {
+    let mut it = s.split('/');
+    let start = parse_end(it.next()?)?;
+    let end = parse_end(it.next()?)?;
+    if it.next().is_some() {
+        return None;
+    }
+    Interval::new(start, end)
+}
+
+#[cfg(feature = "de")]
+pub(crate) fn parse_end(s: &str) -> Option<Option<SystemTime>> {
+    if s == ".." {
+        return Some(None);
+    }
+    date::parse(s).or_else(|| rfc3339::parse(s).ok()).map(Some)
+}
+
+#[cfg(feature = "ser")]
+impl Serialize for Interval {
+    fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        format::serialize_display(self, s, "interval can't be represented")
+    }
+}
+
+#[cfg(feature = "de")]
+impl<'de> Deserialize<'de> for Interval {
+    fn deserialize<D>(d: D) -> Result<Interval, D::Error>
+        where D: Deserializer<'de>
+    {
+        struct V;
+
+        impl<'de2> Visitor<'de2> for V {
+            type Value = Interval;
+
+            fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+                fmt.write_str("an interval")
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Interval, E>
+                where E: Error
+            {
+                v.parse().map_err(|_| E::invalid_value(Unexpected::Str(v), &self))
+            }
+        }
+
+        d.deserialize_str(V)
+    }
+}
+
+#[cfg(all(test, feature = "ser", feature = "de"))]
+mod test {
+    use serde_json;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let t2020 = UNIX_EPOCH + Duration::from_secs(1577836800);
+        let t2024 = UNIX_EPOCH + Duration::from_secs(1704067200);
+        let cases = [("2020-01-01/2024-01-01", Some(t2020), Some(t2024)),
+                     ("2020-01-01T00:00:00Z/..", Some(t2020), None),
+                     ("../2024-01-01", None, Some(t2024)),
+                     ("../..", None, None)];
+        for &(s, start, end) in &cases {
+            assert_eq!(s.parse::<Interval>().unwrap(), Interval::new(start, end).unwrap(), "{}", s);
+        }
+        for s in &["", "..", "2020-01-01", "2024-01-01/2020-01-01", "2020-01-01/../..", "./..",
+                   "1960-01-01/.."] {
+            assert!(s.parse::<Interval>().is_err(), "{}", s);
+        }
+
+        let interval = "../2024-01-01".parse::<Interval>().unwrap();
+        assert!(interval.contains(&t2020));
+        assert!(!interval.contains(&t2024));
+    }
+
+    #[test]
+    fn round_trip() {
+        for s in &["2020-01-01T00:00:00Z/2024-01-01T00:00:00Z", "../2024-01-01T00:00:00Z", "../.."] {
+            let interval = serde_json::from_str::<Interval>(&format!("\"{}\"", s)).unwrap();
+            assert_eq!(serde_json::to_string(&interval).unwrap(), format!("\"{}\"", s));
+        }
+
+        assert_eq!(Interval::new(Some(UNIX_EPOCH - Duration::from_secs(1)), None), None);
+        assert_eq!(Interval::new(None, Some(UNIX_EPOCH + Duration::from_secs(1 << 40))), None);
+    }
+}
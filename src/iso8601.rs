@@ -0,0 +1,277 @@
+//! Serialization of `Duration`s as ISO 8601 durations.
+//!
+//! Durations are serialized in the form `P2DT3H4M5.5S`. Years and months are
+//! never emitted since their length is ambiguous, but are accepted when
+//! deserializing with the same fixed lengths `humantime` uses (365.25 and
+//! 30.44 days respectively). Weeks (`P2W`) are accepted as well, and the
+//! `weeks` submodule prefers them on output for exact multiples.
+//!
+//! # Examples
+//!
+//! ```
+//! extern crate serde_humantime;
+//! extern crate serde;
+//! #[macro_use]
+//! extern crate serde_derive;
+//!
+//! use std::time::Duration;
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Foo {
+//!     #[serde(with = "serde_humantime::iso8601")]
+//!     timeout: Duration,
+//!     #[serde(with = "serde_humantime::iso8601::weeks")]
+//!     retention: Duration,
+//! }
+//!
+//! # fn main() {}
+//! ```
+#[cfg(feature = "de")]
+use serde::de::{Deserializer, Visitor, Error, Unexpected};
+#[cfg(feature = "ser")]
+use serde::ser::Serializer;
+use std::fmt;
+use std::time::Duration;
+
+const WEEK: u64 = 7 * DAY;
+const DAY: u64 = 86400;
+
+/// Serializes a `Duration` as an ISO 8601 duration.
+#[cfg(feature = "ser")]
+pub fn serialize<S>(d: &Duration, s: S) -> Result<S::Ok, S::Error>
+    where S: Serializer
+{
+    s.collect_str(&Iso8601(*d, false))
+}
+
+/// Deserializes a `Duration` from an ISO 8601 duration.
+#[cfg(feature = "de")]
+pub fn deserialize<'de, D>(d: D) -> Result<Duration, D::Error>
+    where D: Deserializer<'de>
+{
+    d.deserialize_str(V)
+}
+
+/// Serialization of `Duration`s as ISO 8601 durations, preferring weeks.
+///
+/// Durations which are an exact number of weeks are serialized in the form
+/// `P2W`, and all others as in the parent module.
+pub mod weeks {
+    #[cfg(feature = "de")]
+    use serde::de::Deserializer;
+    #[cfg(feature = "ser")]
+    use serde::ser::Serializer;
+    use std::time::Duration;
+
+    #[cfg(feature = "ser")]
+    use super::Iso8601;
+    #[cfg(feature = "de")]
+    use super::V;
+
+    /// Serializes a `Duration` as an ISO 8601 duration, using weeks when
+    /// possible.
+    #[cfg(feature = "ser")]
+    pub fn serialize<S>(d: &Duration, s: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        s.collect_str(&Iso8601(*d, true))
+    }
+
+    /// Deserializes a `Duration` from an ISO 8601 duration.
+    #[cfg(feature = "de")]
+    pub fn deserialize<'de, D>(d: D) -> Result<Duration, D::Error>
+        where D: Deserializer<'de>
+    {
+        d.deserialize_str(V)
+    }
+}
+
+#[cfg(feature = "ser")]
+pub(crate) struct Iso8601(pub(crate) Duration, pub(crate) bool);
+
+#[cfg(feature = "ser")]
+impl fmt::Display for Iso8601 {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        let secs = self.0.as_secs();
+        let nanos = self.0.subsec_nanos();
+
+        if self.1 && secs != 0 && secs.is_multiple_of(WEEK) && nanos == 0 {
+            return write!(fmt, "P{}W", secs / WEEK);
+        }
+
+        fmt.write_str("P")?;
+        let days = secs / DAY;
+        if days != 0 {
+            write!(fmt, "{}D", days)?;
+        }
+
+        let hours = secs % DAY / 3600;
+        let minutes = secs % 3600 / 60;
+        let seconds = secs % 60;
+        if hours == 0 && minutes == 0 && seconds == 0 && nanos == 0 {
+            if days == 0 {
+                fmt.write_str("T0S")?;
+            }
+            return Ok(());
+        }
+
+        fmt.write_str("T")?;
+        if hours != 0 {
+            write!(fmt, "{}H", hours)?;
+        }
+        if minutes != 0 {
+            write!(fmt, "{}M", minutes)?;
+        }
+        if seconds != 0 || nanos != 0 {
+            write!(fmt, "{}", seconds)?;
+            if nanos != 0 {
+                let mut nanos = nanos;
+                let mut width = 9;
+                while nanos.is_multiple_of(10) {
+                    nanos /= 10;
+                    width -= 1;
+                }
+                write!(fmt, ".{:01$}", nanos, width)?;
+            }
+            fmt.write_str("S")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "de")]
+struct V;
+
+#[cfg(feature = "de")]
+impl<'de> Visitor<'de> for V {
+    type Value = Duration;
+
+    fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str("an ISO 8601 duration")
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Duration, E>
+        where E: Error
+    {
+        parse(v).ok_or_else(|| E::invalid_value(Unexpected::Str(v), &self))
+    }
+}
+
+#[cfg(feature = "de")]
+pub(crate) fn parse(s: &str) -> Option<Duration> {
+    let mut rest = s.strip_prefix('P')?;
+    if rest.is_empty() {
+        return None;
+    }
+
+    let mut secs = 0u64;
+    let mut nanos = 0u32;
+    let mut time = false;
+    // the designators in the order they're required to appear
+    let mut allowed: &[(char, u64)] = &[('Y', 31557600), ('M', 2630016), ('W', WEEK), ('D', DAY)];
+
+    while !rest.is_empty() {
+        if rest.starts_with('T') {
+            if time {
+                return None;
+            }
+            time = true;
+            allowed = &[('H', 3600), ('M', 60), ('S', 1)];
+            rest = &rest[1..];
+            if rest.is_empty() {
+                return None;
+            }
+            continue;
+        }
+
+        let end = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+        if end == 0 {
+            return None;
+        }
+        let value = rest[..end].parse::<u64>().ok()?;
+        rest = &rest[end..];
+
+        let mut fraction = None;
+        if rest.starts_with('.') || rest.starts_with(',') {
+            let end = rest[1..].find(|c: char| !c.is_ascii_digit()).map_or(rest.len(), |i| i + 1);
+            if end == 1 || end > 10 {
+                return None;
+            }
+            fraction = Some(&rest[1..end]);
+            rest = &rest[end..];
+        }
+
+        let designator = rest.chars().next()?;
+        rest = &rest[designator.len_utf8()..];
+        let idx = allowed.iter().position(|&(c, _)| c == designator)?;
+        let unit = allowed[idx].1;
+        allowed = &allowed[idx + 1..];
+
+        secs = secs.checked_add(value.checked_mul(unit)?)?;
+        if let Some(fraction) = fraction {
+            // only the smallest component, seconds, may be fractional
+            if unit != 1 || !rest.is_empty() {
+                return None;
+            }
+            nanos = format!("{:0<9}", fraction).parse().ok()?;
+        }
+    }
+
+    Some(Duration::new(secs, nanos))
+}
+
+#[cfg(all(test, feature = "ser", feature = "de"))]
+mod test {
+    use serde_json;
+    use std::time::Duration;
+
+    use super::parse;
+
+    #[test]
+    fn parse_components() {
+        assert_eq!(parse("PT15S"), Some(Duration::from_secs(15)));
+        assert_eq!(parse("P1DT2H3M4.5S"), Some(Duration::new(93784, 500_000_000)));
+        assert_eq!(parse("P2W"), Some(Duration::from_secs(14 * 86400)));
+        assert_eq!(parse("P1W2D"), Some(Duration::from_secs(9 * 86400)));
+        assert_eq!(parse("P1Y"), Some(Duration::from_secs(31557600)));
+        assert_eq!(parse("P1M"), Some(Duration::from_secs(2630016)));
+        assert_eq!(parse("PT1M"), Some(Duration::from_secs(60)));
+        assert_eq!(parse("PT0,25S"), Some(Duration::new(0, 250_000_000)));
+        assert_eq!(parse("P0D"), Some(Duration::from_secs(0)));
+    }
+
+    #[test]
+    fn parse_invalid() {
+        for s in &["", "P", "PT", "15S", "P1H", "PT1D", "P1D1W", "P1.5DT1H", "PT1.5S2M",
+                   "PT1.S", "PT1.0000000001S", "P1DT", "PT1S1S", "P-1D",
+                   "P1é", "PT1日", "P2ß"] {
+            assert_eq!(parse(s), None, "{}", s);
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        #[derive(Serialize, Deserialize)]
+        struct Foo {
+            #[serde(with = "super")]
+            time: Duration,
+            #[serde(with = "super::weeks")]
+            weeks: Duration,
+        }
+
+        let cases = [(Duration::from_secs(0), "PT0S", "PT0S"),
+                     (Duration::from_secs(14 * 86400), "P14D", "P2W"),
+                     (Duration::from_secs(15 * 86400), "P15D", "P15D"),
+                     (Duration::new(93784, 500_000_000), "P1DT2H3M4.5S", "P1DT2H3M4.5S"),
+                     (Duration::new(0, 1), "PT0.000000001S", "PT0.000000001S"),
+                     (Duration::from_secs(3600), "PT1H", "PT1H")];
+        for &(dur, plain, weeks) in &cases {
+            let foo = Foo { time: dur, weeks: dur };
+            let json = serde_json::to_string(&foo).unwrap();
+            assert_eq!(json, format!(r#"{{"time":"{}","weeks":"{}"}}"#, plain, weeks));
+            let foo = serde_json::from_str::<Foo>(&json).unwrap();
+            assert_eq!(foo.time, dur);
+            assert_eq!(foo.weeks, dur);
+        }
+    }
+}
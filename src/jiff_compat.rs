@@ -0,0 +1,313 @@
+//! Serialization of `jiff` types in the format of the crate root.
+//!
+//! This module is only available with the `jiff` feature. Its `timestamp`
+//! submodule serializes `Timestamp`s like `SystemTime`s, as RFC 3339
+//! timestamps in UTC, and every `TimestampFormat` is also a
+//! `Format<Timestamp>`, so `De<Timestamp>` works like `De<SystemTime>`.
+//!
+//! Its `span` submodule serializes `Span`s as duration strings like
+//! `1year 2months 3h`, with a leading `-` for negative spans. Unlike
+//! `Duration`s, spans keep each unit separately, so calendar units like years,
+//! months, weeks and days round-trip as written rather than being converted
+//! to a number of seconds. Spans are always serialized as strings, even in
+//! formats which aren't human readable.
+//!
+//! # Examples
+//!
+//! ```
+//! extern crate jiff;
+//! extern crate serde_humantime;
+//! extern crate serde;
+//! #[macro_use]
+//! extern crate serde_derive;
+//!
+//! use jiff::{Span, Timestamp};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Subscription {
+//!     #[serde(with = "serde_humantime::jiff_compat::timestamp")]
+//!     started: Timestamp,
+//!     #[serde(with = "serde_humantime::jiff_compat::span")]
+//!     renews_every: Span,
+//! }
+//!
+//! # fn main() {}
+//! ```
+#[cfg(feature = "de")]
+use jiff::Span;
+#[cfg(feature = "systemtime")]
+use jiff::Timestamp;
+#[cfg(all(feature = "de", feature = "systemtime"))]
+use serde::de::{Deserializer, Error};
+#[cfg(all(feature = "ser", feature = "systemtime"))]
+use serde::ser::Serializer;
+#[cfg(all(feature = "de", feature = "systemtime"))]
+use std::convert::TryFrom;
+#[cfg(all(feature = "systemtime", any(feature = "ser", feature = "de")))]
+use std::time::SystemTime;
+
+#[cfg(feature = "systemtime")]
+use marker::{Format, TimestampFormat};
+
+/// The names of each of a `Span`'s units, from years to nanoseconds, with
+/// the name used when formatting first.
+#[cfg(any(feature = "ser", feature = "de"))]
+const UNITS: [&[&str]; 10] = [
+    &["year", "years", "y"],
+    &["month", "months", "M"],
+    &["week", "weeks", "w"],
+    &["day", "days", "d"],
+    &["h", "hours", "hour", "hr", "hrs"],
+    &["m", "minutes", "minute", "min", "mins"],
+    &["s", "seconds", "second", "secs", "sec"],
+    &["ms", "milliseconds", "millisecond", "millis", "msec"],
+    &["us", "microseconds", "microsecond", "usec"],
+    &["ns", "nanoseconds", "nanosecond", "nanos", "nsec"],
+];
+
+/// Serialization of `Timestamp`s as RFC 3339 timestamps in UTC.
+#[cfg(all(feature = "systemtime", any(feature = "ser", feature = "de")))]
+pub mod timestamp {
+    use jiff::Timestamp;
+    #[cfg(feature = "de")]
+    use serde::de::Deserializer;
+    #[cfg(feature = "ser")]
+    use serde::ser::Serializer;
+
+    #[cfg(any(feature = "ser", feature = "de"))]
+    use marker::{Format, Human};
+
+    /// Serializes a `Timestamp` as an RFC 3339 timestamp in UTC.
+    #[cfg(feature = "ser")]
+    pub fn serialize<S>(t: &Timestamp, s: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        <Human as Format<Timestamp>>::serialize(t, s)
+    }
+
+    /// Deserializes a `Timestamp` from an RFC 3339 timestamp.
+    #[cfg(feature = "de")]
+    pub fn deserialize<'de, D>(d: D) -> Result<Timestamp, D::Error>
+        where D: Deserializer<'de>
+    {
+        <Human as Format<Timestamp>>::deserialize(d)
+    }
+}
+
+/// Serialization of `Span`s as duration strings.
+#[cfg(any(feature = "ser", feature = "de"))]
+pub mod span {
+    use jiff::Span;
+    #[cfg(feature = "de")]
+    use serde::de::{Deserializer, Visitor, Error, Unexpected};
+    #[cfg(feature = "ser")]
+    use serde::ser::Serializer;
+    #[cfg(any(feature = "ser", feature = "de"))]
+    use std::fmt;
+
+    /// Serializes a `Span` as a duration string, prefixed with `-` if it is
+    /// negative.
+    #[cfg(feature = "ser")]
+    pub fn serialize<S>(span: &Span, s: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        s.collect_str(&Formatted(span))
+    }
+
+    /// Deserializes a `Span` from a duration string, optionally prefixed
+    /// with `-`.
+    #[cfg(feature = "de")]
+    pub fn deserialize<'de, D>(d: D) -> Result<Span, D::Error>
+        where D: Deserializer<'de>
+    {
+        d.deserialize_str(V)
+    }
+
+    #[cfg(feature = "ser")]
+    struct Formatted<'a>(&'a Span);
+
+    #[cfg(feature = "ser")]
+    impl<'a> fmt::Display for Formatted<'a> {
+        fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+            let span = self.0;
+            if span.is_zero() {
+                return fmt.write_str("0s");
+            }
+            if span.is_negative() {
+                fmt.write_str("-")?;
+            }
+            let values = [
+                span.get_years() as i64,
+                span.get_months() as i64,
+                span.get_weeks() as i64,
+                span.get_days() as i64,
+                span.get_hours() as i64,
+                span.get_minutes(),
+                span.get_seconds(),
+                span.get_milliseconds(),
+                span.get_microseconds(),
+                span.get_nanoseconds(),
+            ];
+            let mut started = false;
+            for (&value, names) in values.iter().zip(super::UNITS.iter()) {
+                if value == 0 {
+                    continue;
+                }
+                if started {
+                    fmt.write_str(" ")?;
+                }
+                let value = value.unsigned_abs();
+                write!(fmt, "{}{}", value, names[0])?;
+                // only the calendar units are spelled out
+                if value > 1 && names[0].len() > 2 {
+                    fmt.write_str("s")?;
+                }
+                started = true;
+            }
+            Ok(())
+        }
+    }
+
+    #[cfg(feature = "de")]
+    struct V;
+
+    #[cfg(feature = "de")]
+    impl<'de> Visitor<'de> for V {
+        type Value = Span;
+
+        fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+            fmt.write_str("a duration")
+        }
+
+        fn visit_str<E>(self, v: &str) -> Result<Span, E>
+            where E: Error
+        {
+            super::parse_span(v).ok_or_else(|| E::invalid_value(Unexpected::Str(v), &self))
+        }
+    }
+}
+
+/// Parses a span, keeping each unit separately.
+#[cfg(feature = "de")]
+fn parse_span(s: &str) -> Option<Span> {
+    let (negative, mut rest) = match s.strip_prefix('-') {
+        Some(s) => (true, s),
+        None => (false, s),
+    };
+    if rest.trim().is_empty() {
+        return None;
+    }
+
+    let mut values = [0i64; 10];
+    rest = rest.trim_start();
+    while !rest.is_empty() {
+        let end = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+        let number = &rest[..end];
+        rest = rest[end..].trim_start();
+        let end = rest.find(|c: char| !c.is_ascii_alphabetic()).unwrap_or(rest.len());
+        let unit = &rest[..end];
+        rest = rest[end..].trim_start();
+        if number.is_empty() || unit.is_empty() {
+            return None;
+        }
+
+        let i = UNITS.iter().position(|names| names.contains(&unit))?;
+        values[i] = values[i].checked_add(number.parse().ok()?)?;
+    }
+
+    let span = Span::new()
+        .try_years(values[0]).ok()?
+        .try_months(values[1]).ok()?
+        .try_weeks(values[2]).ok()?
+        .try_days(values[3]).ok()?
+        .try_hours(values[4]).ok()?
+        .try_minutes(values[5]).ok()?
+        .try_seconds(values[6]).ok()?
+        .try_milliseconds(values[7]).ok()?
+        .try_microseconds(values[8]).ok()?
+        .try_nanoseconds(values[9]).ok()?;
+    Some(if negative { span.negate() } else { span })
+}
+
+#[cfg(feature = "systemtime")]
+impl<F> Format<Timestamp> for F
+    where F: TimestampFormat
+{
+    #[cfg(feature = "ser")]
+    fn serialize<S>(value: &Timestamp, s: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        <F as Format<SystemTime>>::serialize(&SystemTime::from(*value), s)
+    }
+
+    #[cfg(feature = "de")]
+    fn deserialize<'de, D>(d: D) -> Result<Timestamp, D::Error>
+        where D: Deserializer<'de>
+    {
+        let t = <F as Format<SystemTime>>::deserialize(d)?;
+        Timestamp::try_from(t).map_err(|_| D::Error::custom("timestamp is out of range"))
+    }
+}
+
+#[cfg(all(test, feature = "ser", feature = "de"))]
+mod test {
+    use jiff::{Span, ToSpan};
+    #[cfg(feature = "systemtime")]
+    use jiff::Timestamp;
+    use serde_json;
+
+    #[cfg(feature = "systemtime")]
+    use De;
+
+    #[derive(Serialize, Deserialize)]
+    struct Foo {
+        #[serde(with = "super::span")]
+        span: Span,
+    }
+
+    #[test]
+    fn spans() {
+        let cases = [
+            (r#"{"span":"1year 2months 3h"}"#, 1.year().months(2).hours(3)),
+            (r#"{"span":"2weeks 1day"}"#, 2.weeks().days(1)),
+            (r#"{"span":"-1month 30m 15s"}"#, -1.month().minutes(30).seconds(15)),
+            (r#"{"span":"90m 1500ms"}"#, 90.minutes().milliseconds(1500)),
+            (r#"{"span":"0s"}"#, Span::new()),
+        ];
+        for &(json, span) in &cases {
+            let foo = serde_json::from_str::<Foo>(json).unwrap();
+            assert_eq!(foo.span.fieldwise(), span, "{}", json);
+            assert_eq!(serde_json::to_string(&foo).unwrap(), json);
+        }
+
+        let foo = serde_json::from_str::<Foo>(r#"{"span":"1 y 2 M 3 hours 4 hrs"}"#).unwrap();
+        assert_eq!(foo.span.fieldwise(), 1.year().months(2).hours(7));
+
+        for json in &[r#"{"span":""}"#, r#"{"span":"-"}"#, r#"{"span":"--1s"}"#,
+                      r#"{"span":"1 fortnight"}"#, r#"{"span":"20000years"}"#, r#"{"span":5}"#] {
+            assert!(serde_json::from_str::<Foo>(json).is_err(), "{}", json);
+        }
+    }
+
+    #[cfg(feature = "systemtime")]
+    #[test]
+    fn timestamps() {
+        #[derive(Serialize, Deserialize)]
+        struct Bar {
+            #[serde(with = "super::timestamp")]
+            started: Timestamp,
+            deadline: Option<De<Timestamp>>,
+        }
+
+        // 2018-02-13T23:08:32.5Z
+        let t = Timestamp::from_millisecond(1518563312500).unwrap();
+        let json = r#"{"started":"2018-02-13T23:08:32.500000000Z","deadline":"2018-02-13T23:08:32.500000000Z"}"#;
+        let bar = serde_json::from_str::<Bar>(json).unwrap();
+        assert_eq!(bar.started, t);
+        assert_eq!(bar.deadline.as_ref().map(|t| *t.as_ref()), Some(t));
+        assert_eq!(serde_json::to_string(&bar).unwrap(), json);
+
+        let early = Bar { started: Timestamp::from_second(-1).unwrap(), deadline: None };
+        assert!(serde_json::to_string(&early).is_err());
+    }
+}
@@ -0,0 +1,160 @@
+//! Serialization of `SystemTime`s as JavaScript `Date` values.
+//!
+//! Timestamps are serialized as an `f64` number of whole milliseconds since
+//! the Unix epoch, the value returned by `Date.getTime()`, so that they
+//! arrive as numbers in JavaScript through `serde-wasm-bindgen`. Fractions of
+//! a millisecond are discarded, rounding towards the past. Timestamps outside
+//! of the range of a `Date`, 100,000,000 days either side of the epoch, fail
+//! to serialize.
+//!
+//! Deserialization accepts such numbers as well as the RFC 3339 strings
+//! produced by the crate root, so a struct shared between a wasm frontend and
+//! a backend can be read from either.
+//!
+//! # Examples
+//!
+//! ```
+//! extern crate serde_humantime;
+//! extern crate serde;
+//! #[macro_use]
+//! extern crate serde_derive;
+//!
+//! use std::time::SystemTime;
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Event {
+//!     #[serde(with = "serde_humantime::js_date")]
+//!     time: SystemTime,
+//! }
+//!
+//! # fn main() {}
+//! ```
+#[cfg(feature = "de")]
+use serde::de::{Deserializer, Visitor, Error, Unexpected};
+#[cfg(feature = "ser")]
+use serde::ser::{self, Serializer};
+#[cfg(feature = "de")]
+use std::fmt;
+use std::time::SystemTime;
+
+#[cfg(feature = "de")]
+use rfc3339;
+use unix_millis;
+
+// the largest number of milliseconds from the epoch a `Date` can hold
+const MAX_MILLIS: u64 = 8_640_000_000_000_000;
+
+/// Serializes a `SystemTime` as an `f64` number of milliseconds since the
+/// Unix epoch.
+///
+/// Returns an error if the timestamp can't be held by a JavaScript `Date`.
+#[cfg(feature = "ser")]
+pub fn serialize<S>(t: &SystemTime, s: S) -> Result<S::Ok, S::Error>
+    where S: Serializer
+{
+    let millis = unix_millis::to_millis(*t);
+    if millis.unsigned_abs() > MAX_MILLIS as u128 {
+        return Err(ser::Error::custom("timestamp is out of range for a JavaScript Date"));
+    }
+    s.serialize_f64(millis as f64)
+}
+
+/// Deserializes a `SystemTime` from a number of milliseconds since the Unix
+/// epoch or an RFC 3339 timestamp.
+#[cfg(feature = "de")]
+pub fn deserialize<'de, D>(d: D) -> Result<SystemTime, D::Error>
+    where D: Deserializer<'de>
+{
+    d.deserialize_any(V)
+}
+
+#[cfg(feature = "de")]
+struct V;
+
+#[cfg(feature = "de")]
+impl V {
+    fn millis<E>(&self, negative: bool, millis: u64, unexpected: Unexpected) -> Result<SystemTime, E>
+        where E: Error
+    {
+        if millis > MAX_MILLIS {
+            return Err(E::invalid_value(unexpected, self));
+        }
+        unix_millis::from_millis(negative, millis).ok_or_else(|| E::invalid_value(unexpected, self))
+    }
+}
+
+#[cfg(feature = "de")]
+impl<'de> Visitor<'de> for V {
+    type Value = SystemTime;
+
+    fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str("a number of milliseconds since the Unix epoch or an RFC 3339 timestamp")
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<SystemTime, E>
+        where E: Error
+    {
+        self.millis(false, v, Unexpected::Unsigned(v))
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<SystemTime, E>
+        where E: Error
+    {
+        self.millis(v < 0, v.unsigned_abs(), Unexpected::Signed(v))
+    }
+
+    fn visit_f64<E>(self, v: f64) -> Result<SystemTime, E>
+        where E: Error
+    {
+        // `Date.getTime()` only returns whole numbers, and NaN for invalid dates
+        if v.fract() != 0. || v.abs() > MAX_MILLIS as f64 {
+            return Err(E::invalid_value(Unexpected::Float(v), &self));
+        }
+        self.millis(v < 0., v.abs() as u64, Unexpected::Float(v))
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<SystemTime, E>
+        where E: Error
+    {
+        rfc3339::parse(v).map_err(|_| E::invalid_value(Unexpected::Str(v), &self))
+    }
+}
+
+#[cfg(all(test, feature = "ser", feature = "de"))]
+mod test {
+    use serde_json;
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    #[derive(Serialize, Deserialize)]
+    struct Foo {
+        #[serde(with = "super")]
+        time: SystemTime,
+    }
+
+    #[test]
+    fn round_trip() {
+        // 2018-02-13T23:08:32.500Z
+        let time = UNIX_EPOCH + Duration::from_millis(1518563312500);
+        for json in &[r#"{"time":1518563312500.0}"#, r#"{"time":1518563312500}"#,
+                      r#"{"time":"2018-02-13T23:08:32.500000000Z"}"#] {
+            assert_eq!(serde_json::from_str::<Foo>(json).unwrap().time, time, "{}", json);
+        }
+        let json = serde_json::to_string(&Foo { time: time + Duration::new(0, 999_999) }).unwrap();
+        assert_eq!(json, r#"{"time":1518563312500.0}"#);
+
+        let time = UNIX_EPOCH - Duration::new(1, 500_000);
+        let json = serde_json::to_string(&Foo { time }).unwrap();
+        assert_eq!(json, r#"{"time":-1001.0}"#);
+        assert_eq!(serde_json::from_str::<Foo>(&json).unwrap().time, time - Duration::new(0, 500_000));
+    }
+
+    #[test]
+    fn invalid() {
+        let time = UNIX_EPOCH + Duration::from_millis(super::MAX_MILLIS + 1);
+        assert!(serde_json::to_string(&Foo { time }).is_err());
+        for json in &[r#"{"time":0.5}"#, r#"{"time":8640000000000001}"#, r#"{"time":-8.7e15}"#,
+                      r#"{"time":"yesterday"}"#, r#"{"time":null}"#] {
+            assert!(serde_json::from_str::<Foo>(json).is_err(), "{}", json);
+        }
+    }
+}
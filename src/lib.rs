@@ -1,10 +1,10 @@
-//! A crate providing Serde deserializers for `Duration`s via the `humantime`
-//! crate.
+//! A crate providing Serde serializers and deserializers for `Duration`s via
+//! the `humantime` crate.
 //!
 //! # Examples
 //!
-//! You can use the `deserialize` function with the `with` or `deserialize_with`
-//! annotations:
+//! You can use the `serialize` and `deserialize` functions with the `with`,
+//! `serialize_with` or `deserialize_with` annotations:
 //!
 //! ```
 //! extern crate serde_humantime;
@@ -14,7 +14,7 @@
 //!
 //! use std::time::Duration;
 //!
-//! #[derive(Deserialize)]
+//! #[derive(Serialize, Deserialize)]
 //! struct Foo {
 //!     #[serde(with = "serde_humantime")]
 //!     timeout: Duration,
@@ -41,87 +41,907 @@
 //!
 //! # fn main() {}
 //! ```
+//!
+//! The root `serialize` and `deserialize` functions, the `option` module and
+//! the `Serde` alias mirror the layout of the `humantime-serde` crate, so a
+//! project can switch between the two by renaming the dependency in its
+//! `Cargo.toml`:
+//!
+//! ```toml
+//! [dependencies]
+//! humantime-serde = { package = "serde-humantime", version = "0.1" }
+//! ```
+//!
+//! # Features
+//!
+//! By default, parsing is performed by the `humantime` crate. Disabling the
+//! default `humantime` feature drops that dependency in favor of an internal
+//! parser which accepts the same grammar.
+//!
+//! The `humantime` crate is re-exported as `serde_humantime::humantime` so
+//! code which uses it directly can avoid depending on a different version.
+//! This is `humantime` 1.x. Its errors are small `Copy` values, which this
+//! crate's `Error` relies on, unlike those of `humantime` 2.x.
+//!
+//! The default `ser` and `de` features enable serialization and
+//! deserialization support respectively. Crates which only need one direction
+//! can disable default features and enable just one of them, which compiles
+//! out the other half of the crate, including the `format` module or the
+//! duration parser.
+//!
+//! The default `systemtime` feature enables support for `SystemTime`s.
+//! Disabling it removes the `rfc3339` module, the timestamp formatting and
+//! parsing code, and the `SystemTime` functions and implementations, leaving
+//! only `Duration` support.
+//!
+//! The `noalloc` module serializes durations without allocating. The
+//! `heapless` feature adds a function there which formats durations into
+//! `heapless::String`s.
+//!
+//! The `humantime-serde` feature enables conversions to and from the
+//! `humantime-serde` crate's types in the `compat` module. That crate depends
+//! on `humantime` 2.x, so enabling it builds both major versions of
+//! `humantime`. The re-export is still 1.x.
+//!
+//! The `defmt` feature implements `defmt::Format` for `De` and
+//! `NonZeroDuration` in the `defmt_format` module, for logging deserialized
+//! configuration with `defmt`.
+//!
+//! The `async-graphql` feature implements GraphQL scalars for `De<Duration>`
+//! and `De<SystemTime>`. The `juniper` feature does the same for `juniper` in
+//! the `graphql_juniper` module.
+//!
+//! The `serde_json` feature enables the `migrate` module, which rewrites
+//! numeric durations in existing JSON documents into this crate's format.
+//!
+//! The `rayon` feature enables the `par` module, which parses large batches
+//! of durations and timestamps in parallel.
+//!
+//! The `testing` feature enables the `testing` module and its
+//! `assert_roundtrip!` macro, which check that values round-trip through this
+//! crate's formats in downstream tests.
+//!
+//! The `wasm` feature enables the `js_date` module, which serializes
+//! timestamps as the millisecond numbers used by JavaScript `Date`s for wasm
+//! frontends.
+//!
+//! The `chrono` feature enables the `date::chrono` module, which serializes
+//! `chrono::NaiveDate`s in the same `YYYY-MM-DD` format as the `date` module,
+//! and the `chrono_compat` module, which does the same for chrono's
+//! timestamps and durations in the format of the crate root.
+//!
+//! The `time` feature enables the `date::time` module, which does the same
+//! for `time::Date`s. The `time03` feature also enables the `time_compat`
+//! module, which serializes `time::OffsetDateTime`s and `time::Duration`s in
+//! the format of the crate root.
+//!
+//! The `jiff` feature enables the `jiff_compat` module, which serializes
+//! `jiff::Timestamp`s and `jiff::Span`s, keeping a span's calendar units.
+//!
+//! The `chrono-tz` feature enables the `zoned` module, which parses
+//! timestamps in named timezones like `2024-03-10 09:00 America/New_York`.
+//!
+//! The `tzdb` feature enables the `local` module, which formats and parses
+//! timestamps in the system's local timezone.
 #![warn(missing_docs)]
 #![doc(html_root_url="https://docs.rs/serde-humantime/0.1.1")]
 
-extern crate humantime;
+#[cfg(feature = "async-graphql")]
+extern crate async_graphql;
+#[cfg(feature = "chrono")]
+extern crate chrono;
+#[cfg(feature = "chrono-tz")]
+extern crate chrono_tz;
+#[cfg(feature = "defmt")]
+extern crate defmt;
+#[cfg(feature = "heapless")]
+extern crate heapless;
+/// The version of the `humantime` crate this crate was built against, from
+/// the 1.x series.
+///
+/// Only available with the `humantime` feature.
+#[cfg(feature = "humantime")]
+pub extern crate humantime;
+#[cfg(feature = "humantime-serde")]
+extern crate humantime_serde;
+#[cfg(feature = "jiff")]
+extern crate jiff;
+#[cfg(feature = "juniper")]
+extern crate juniper;
+#[cfg(feature = "rayon")]
+extern crate rayon;
 extern crate serde;
+#[cfg(feature = "time")]
+extern crate time;
 
-#[cfg(test)]
+#[cfg(all(test, feature = "ser", feature = "de"))]
+extern crate bincode;
+#[cfg(all(test, feature = "ser", feature = "de"))]
 #[macro_use]
 extern crate serde_derive;
-#[cfg(test)]
+#[cfg(any(feature = "serde_json", all(test, feature = "ser", feature = "de")))]
 extern crate serde_json;
 
-use serde::de::{Deserialize, Deserializer, Visitor, Error, Unexpected};
+#[cfg(feature = "de")]
+use serde::de::{Deserialize, Deserializer};
+#[cfg(feature = "ser")]
+use serde::ser::{Serialize, Serializer};
+use std::borrow::Borrow;
 use std::fmt;
+use std::iter::Sum;
+use std::marker::PhantomData;
+use std::mem::ManuallyDrop;
 use std::time::Duration;
+#[cfg(feature = "systemtime")]
+use std::time::SystemTime;
+
+mod civil;
+#[cfg(feature = "de")]
+mod error;
+#[cfg(feature = "de")]
+mod parse;
+mod traits;
+
+#[cfg(all(feature = "systemtime", any(feature = "ser", feature = "de")))]
+pub mod age;
+#[cfg(feature = "de")]
+pub mod batch;
+pub mod buckets;
+pub mod calendar;
+#[cfg(feature = "chrono")]
+pub mod chrono_compat;
+#[cfg(feature = "systemtime")]
+pub mod clock;
+#[cfg(feature = "humantime-serde")]
+pub mod compat;
+pub mod convert;
+#[cfg(all(any(feature = "systemtime", feature = "chrono", feature = "time"), any(feature = "ser", feature = "de")))]
+pub mod date;
+#[cfg(feature = "defmt")]
+pub mod defmt_format;
+#[cfg(any(feature = "ser", feature = "de"))]
+pub mod duration_days;
+#[cfg(any(feature = "ser", feature = "de"))]
+pub mod duration_nanos_u128;
+pub mod duration_or_range;
+#[cfg(all(feature = "systemtime", feature = "de"))]
+pub mod elapsed;
+#[cfg(feature = "systemtime")]
+pub mod epoch;
+#[cfg(feature = "ser")]
+pub mod format;
+pub mod frequency;
+#[cfg(feature = "async-graphql")]
+pub mod graphql;
+#[cfg(feature = "juniper")]
+pub mod graphql_juniper;
+#[cfg(feature = "systemtime")]
+pub mod interval;
+#[cfg(any(feature = "ser", feature = "de"))]
+pub mod iso8601;
+#[cfg(feature = "jiff")]
+pub mod jiff_compat;
+#[cfg(all(feature = "wasm", any(feature = "ser", feature = "de")))]
+pub mod js_date;
+#[cfg(all(feature = "tzdb", any(feature = "ser", feature = "de")))]
+pub mod local;
+pub mod map_keys;
+pub mod marker;
+#[cfg(feature = "serde_json")]
+pub mod migrate;
+#[cfg(feature = "ser")]
+pub mod noalloc;
+pub mod non_zero;
+#[cfg(all(feature = "ser", feature = "de"))]
+pub mod normalize;
+pub mod offset;
+#[cfg(feature = "rayon")]
+pub mod par;
+pub mod period;
+pub mod prelude;
+#[cfg(all(feature = "ser", feature = "systemtime"))]
+pub mod relative;
+pub mod repeating;
+#[cfg(feature = "systemtime")]
+pub mod rfc3339;
+pub mod signed;
+#[cfg(any(feature = "ser", feature = "de"))]
+pub mod structured;
+#[cfg(any(feature = "ser", feature = "de"))]
+pub mod sum;
+#[cfg(all(feature = "systemtime", any(feature = "ser", feature = "de")))]
+pub mod temporal;
+#[cfg(feature = "testing")]
+#[macro_use]
+pub mod testing;
+#[cfg(feature = "time03")]
+pub mod time_compat;
+#[cfg(any(feature = "ser", feature = "de"))]
+pub mod timedelta;
+#[cfg(all(feature = "systemtime", any(feature = "ser", feature = "de")))]
+pub mod unix_millis;
+#[cfg(all(feature = "systemtime", any(feature = "ser", feature = "de")))]
+pub mod unix_secs;
+#[cfg(feature = "de")]
+pub mod validate;
+#[cfg(all(feature = "chrono-tz", any(feature = "ser", feature = "de")))]
+pub mod zoned;
+
+#[cfg(feature = "de")]
+pub use error::{Error, ErrorKind};
+#[cfg(feature = "de")]
+pub use parse::{parse_duration, parse_duration_const, parse_duration_partial, ParseOptions};
+pub use traits::HumanTime;
+
+#[cfg(any(feature = "ser", feature = "de"))]
+use marker::Format;
+use marker::Human;
 
-/// A wrapper type which implements `Deserialize` for types involving
-/// `Duration`.
+/// A wrapper type which implements `Serialize` and `Deserialize` for types
+/// involving `Duration` and `SystemTime`.
+///
+/// Outside of this crate it is constructed through its `Deserialize`
+/// implementations, `wrap_vec`, summing wrapped durations, and conversions
+/// such as those from the `humantime-serde` crate's `Serde` and from
+/// `chrono::DateTime<Utc>`. It has no public constructor from a bare value.
+///
+/// The second type parameter selects the format the value is serialized in,
+/// and defaults to the format of the crate root. See the `marker` module.
 ///
-/// It can only be constructed through its `Deserialize` implementations.
-pub struct De<T>(T);
+/// `De<T, F>` has the same memory layout as `T`.
+#[repr(transparent)]
+pub struct De<T, F = Human>(T, PhantomData<F>);
+
+/// An alias of `De` matching the name used by the `humantime-serde` crate.
+pub type Serde<T, F = Human> = De<T, F>;
+
+impl<T, F> De<T, F> {
+    pub(crate) fn new(v: T) -> De<T, F> {
+        De(v, PhantomData)
+    }
 
-impl<T> De<T> {
     /// Consumes the `De`, returning the inner value.
     pub fn into_inner(self) -> T {
         self.0
     }
+
+    /// Unwraps each value of a `Vec` of `De`s without reallocating.
+    pub fn unwrap_vec(v: Vec<De<T, F>>) -> Vec<T> {
+        let mut v = ManuallyDrop::new(v);
+        // SAFETY: De<T, F> is repr(transparent), so the allocation has the
+        // same layout for both element types.
+        unsafe { Vec::from_raw_parts(v.as_mut_ptr() as *mut T, v.len(), v.capacity()) }
+    }
+
+    /// Wraps each value of a `Vec` in a `De` without reallocating.
+    pub fn wrap_vec(v: Vec<T>) -> Vec<De<T, F>> {
+        let mut v = ManuallyDrop::new(v);
+        // SAFETY: De<T, F> is repr(transparent), so the allocation has the
+        // same layout for both element types.
+        unsafe { Vec::from_raw_parts(v.as_mut_ptr() as *mut De<T, F>, v.len(), v.capacity()) }
+    }
 }
 
-impl<'de> Deserialize<'de> for De<Duration> {
-    fn deserialize<D>(d: D) -> Result<De<Duration>, D::Error>
-        where D: Deserializer<'de>
+impl<T, F> AsRef<T> for De<T, F> {
+    fn as_ref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T, F> Borrow<T> for De<T, F> {
+    fn borrow(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<F> PartialEq<Duration> for De<Duration, F> {
+    fn eq(&self, other: &Duration) -> bool {
+        self.0 == *other
+    }
+}
+
+impl<F> PartialEq<De<Duration, F>> for Duration {
+    fn eq(&self, other: &De<Duration, F>) -> bool {
+        *self == other.0
+    }
+}
+
+/// Sums wrapped durations.
+///
+/// # Panics
+///
+/// Panics if the sum overflows, like `Duration`'s own implementation.
+impl<F> Sum<De<Duration, F>> for Duration {
+    fn sum<I>(iter: I) -> Duration
+        where I: Iterator<Item = De<Duration, F>>
+    {
+        iter.map(|d| d.0).sum()
+    }
+}
+
+impl<'a, F> Sum<&'a De<Duration, F>> for Duration {
+    fn sum<I>(iter: I) -> Duration
+        where I: Iterator<Item = &'a De<Duration, F>>
+    {
+        iter.map(|d| d.0).sum()
+    }
+}
+
+impl<F> Sum for De<Duration, F> {
+    fn sum<I>(iter: I) -> De<Duration, F>
+        where I: Iterator<Item = De<Duration, F>>
     {
-        deserialize(d).map(De)
+        De::new(iter.sum())
+    }
+}
+
+impl<'a, F> Sum<&'a De<Duration, F>> for De<Duration, F> {
+    fn sum<I>(iter: I) -> De<Duration, F>
+        where I: Iterator<Item = &'a De<Duration, F>>
+    {
+        De::new(iter.sum())
+    }
+}
+
+#[cfg(feature = "systemtime")]
+impl<F> PartialEq<SystemTime> for De<SystemTime, F> {
+    fn eq(&self, other: &SystemTime) -> bool {
+        self.0 == *other
+    }
+}
+
+#[cfg(feature = "systemtime")]
+impl<F> PartialEq<De<SystemTime, F>> for SystemTime {
+    fn eq(&self, other: &De<SystemTime, F>) -> bool {
+        *self == other.0
+    }
+}
+
+/// Formats the wrapped value.
+///
+/// The alternate form (`{:#?}`) also includes the human readable
+/// representation of the value, as in `De { value: 5400s, human: "1h 30m" }`.
+impl<F> fmt::Debug for De<Duration, F> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        debug(fmt, &self.0, human_duration(self.0))
+    }
+}
+
+/// Formats the wrapped value.
+///
+/// The alternate form (`{:#?}`) also includes the human readable
+/// representation of the value.
+impl<F> fmt::Debug for De<Option<Duration>, F> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        debug(fmt, &self.0, self.0.and_then(human_duration))
+    }
+}
+
+/// Formats the wrapped value.
+///
+/// The alternate form (`{:#?}`) also includes the RFC 3339 representation of
+/// the value.
+#[cfg(feature = "systemtime")]
+impl<F> fmt::Debug for De<SystemTime, F> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        debug(fmt, &self.0, human_systemtime(self.0))
+    }
+}
+
+/// Formats the wrapped value.
+///
+/// The alternate form (`{:#?}`) also includes the RFC 3339 representation of
+/// the value.
+#[cfg(feature = "systemtime")]
+impl<F> fmt::Debug for De<Option<SystemTime>, F> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        debug(fmt, &self.0, self.0.and_then(human_systemtime))
+    }
+}
+
+fn debug(fmt: &mut fmt::Formatter, value: &dyn fmt::Debug, human: Option<String>) -> fmt::Result {
+    if !fmt.alternate() {
+        return fmt.debug_tuple("De").field(value).finish();
     }
+
+    let mut s = fmt.debug_struct("De");
+    s.field("value", value);
+    if let Some(human) = human {
+        s.field("human", &human);
+    }
+    s.finish()
+}
+
+#[cfg(feature = "ser")]
+fn human_duration(d: Duration) -> Option<String> {
+    Some(format::format_duration(d).to_string())
+}
+
+#[cfg(not(feature = "ser"))]
+fn human_duration(_: Duration) -> Option<String> {
+    None
+}
+
+#[cfg(all(feature = "ser", feature = "systemtime"))]
+fn human_systemtime(t: SystemTime) -> Option<String> {
+    use std::fmt::Write;
+
+    // formatting fails for timestamps outside of RFC 3339's range
+    let mut s = String::new();
+    write!(s, "{}", format::format_rfc3339(t)).ok().map(|_| s)
+}
+
+#[cfg(all(not(feature = "ser"), feature = "systemtime"))]
+fn human_systemtime(_: SystemTime) -> Option<String> {
+    None
 }
 
-impl<'de> Deserialize<'de> for De<Option<Duration>> {
-    fn deserialize<D>(d: D) -> Result<De<Option<Duration>>, D::Error>
+#[cfg(feature = "de")]
+impl<'de, T, F> Deserialize<'de> for De<T, F>
+    where F: Format<T>
+{
+    fn deserialize<D>(d: D) -> Result<De<T, F>, D::Error>
         where D: Deserializer<'de>
     {
-        match Option::<De<Duration>>::deserialize(d)? {
-            Some(De(dur)) => Ok(De(Some(dur))),
-            None => Ok(De(None)),
-        }
+        F::deserialize(d).map(De::new)
     }
 }
 
-/// Deserializes a `Duration` via the humantime crate.
+#[cfg(feature = "ser")]
+impl<T, F> Serialize for De<T, F>
+    where F: Format<T>
+{
+    fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        F::serialize(&self.0, s)
+    }
+}
+
+/// Serializes a `Duration`, `SystemTime`, or `Option`, `Vec` or map of either.
 ///
-/// This function can be used with `serde_derive`'s `with` and
-/// `deserialize_with` annotations.
-pub fn deserialize<'de, D>(d: D) -> Result<Duration, D::Error>
-    where D: Deserializer<'de>
+/// `Option`s, `Vec`s and the values of `HashMap`s and `BTreeMap`s may be
+/// nested, as in `Option<Vec<Duration>>` or `HashMap<String, Vec<Duration>>`.
+/// Durations are formatted as by the humantime crate, and timestamps as
+/// RFC 3339 timestamps. Formats which aren't human readable, like bincode,
+/// instead use serde's own compact encoding of seconds and nanoseconds. This
+/// function can be used with `serde_derive`'s `with` and `serialize_with`
+/// annotations.
+#[cfg(feature = "ser")]
+pub fn serialize<T, S>(d: &T, s: S) -> Result<S::Ok, S::Error>
+    where T: HumanTime,
+          S: Serializer
 {
-    struct V;
+    d.serialize(s)
+}
+
+/// Deserializes a `Duration`, `SystemTime`, or `Option`, `Vec` or map of either.
+///
+/// `Option`s, `Vec`s and the values of `HashMap`s and `BTreeMap`s may be
+/// nested, as in `Option<Vec<Duration>>` or `HashMap<String, Vec<Duration>>`.
+/// Durations are parsed as by the humantime crate, and timestamps as
+/// RFC 3339 timestamps. In human readable formats like JSON, durations may
+/// also be plain numbers of seconds, as in `30` or `1.5`, easing migration
+/// from configuration which used them. Other formats, like bincode, use
+/// serde's own compact encoding of seconds and nanoseconds. This function
+/// can be used with `serde_derive`'s `with` and `deserialize_with`
+/// annotations.
+#[cfg(feature = "de")]
+pub fn deserialize<'de, T, D>(d: D) -> Result<T, D::Error>
+    where T: HumanTime,
+          D: Deserializer<'de>
+{
+    T::deserialize(d)
+}
+
+/// Serialization of `Option`s of durations and timestamps.
+///
+/// The root `serialize` and `deserialize` functions already support
+/// `Option`s. This module mirrors the layout of the `humantime-serde` crate,
+/// so `#[serde(with = "humantime_serde::option")]` attributes keep working
+/// after switching crates.
+#[cfg(any(feature = "ser", feature = "de"))]
+pub mod option {
+    #[cfg(feature = "de")]
+    use serde::de::Deserializer;
+    #[cfg(feature = "ser")]
+    use serde::ser::Serializer;
+
+    use HumanTime;
+
+    /// Serializes an `Option` of a duration or timestamp.
+    #[cfg(feature = "ser")]
+    pub fn serialize<T, S>(d: &Option<T>, s: S) -> Result<S::Ok, S::Error>
+        where T: HumanTime,
+              S: Serializer
+    {
+        ::serialize(d, s)
+    }
+
+    /// Deserializes an `Option` of a duration or timestamp.
+    #[cfg(feature = "de")]
+    pub fn deserialize<'de, T, D>(d: D) -> Result<Option<T>, D::Error>
+        where T: HumanTime,
+              D: Deserializer<'de>
+    {
+        ::deserialize(d)
+    }
+
+    /// Serialization of `Option`s which represents `None` as an empty string.
+    ///
+    /// Formats like CSV and `application/x-www-form-urlencoded` write absent
+    /// values as empty strings, so this module allows optional durations and
+    /// timestamps to round-trip through them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate serde_humantime;
+    /// extern crate serde;
+    /// #[macro_use]
+    /// extern crate serde_derive;
+    ///
+    /// use std::time::Duration;
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// struct Row {
+    ///     #[serde(with = "serde_humantime::option::empty")]
+    ///     timeout: Option<Duration>,
+    /// }
+    ///
+    /// # fn main() {}
+    /// ```
+    pub mod empty {
+        #[cfg(feature = "de")]
+        use serde::de::{Deserializer, Error, IntoDeserializer, Visitor};
+        #[cfg(feature = "ser")]
+        use serde::ser::Serializer;
+        #[cfg(feature = "de")]
+        use std::fmt;
+        #[cfg(feature = "de")]
+        use std::marker::PhantomData;
+
+        use HumanTime;
+
+        /// Serializes an `Option` of a duration or timestamp, writing `None`
+        /// as an empty string.
+        #[cfg(feature = "ser")]
+        pub fn serialize<T, S>(d: &Option<T>, s: S) -> Result<S::Ok, S::Error>
+            where T: HumanTime,
+                  S: Serializer
+        {
+            match *d {
+                Some(ref d) => d.serialize(s),
+                None => s.serialize_str(""),
+            }
+        }
+
+        /// Deserializes an `Option` of a duration or timestamp, reading an
+        /// empty string as `None`.
+        #[cfg(feature = "de")]
+        pub fn deserialize<'de, T, D>(d: D) -> Result<Option<T>, D::Error>
+            where T: HumanTime,
+                  D: Deserializer<'de>
+        {
+            d.deserialize_str(V(PhantomData))
+        }
+
+        #[cfg(feature = "de")]
+        struct V<T>(PhantomData<T>);
+
+        #[cfg(feature = "de")]
+        impl<'de, T> Visitor<'de> for V<T>
+            where T: HumanTime
+        {
+            type Value = Option<T>;
+
+            fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+                fmt.write_str("a duration, timestamp, or empty string")
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Option<T>, E>
+                where E: Error
+            {
+                if v.is_empty() {
+                    Ok(None)
+                } else {
+                    T::deserialize(v.into_deserializer()).map(Some)
+                }
+            }
+
+            fn visit_none<E>(self) -> Result<Option<T>, E>
+                where E: Error
+            {
+                Ok(None)
+            }
+
+            fn visit_unit<E>(self) -> Result<Option<T>, E>
+                where E: Error
+            {
+                Ok(None)
+            }
+
+            fn visit_some<D>(self, d: D) -> Result<Option<T>, D::Error>
+                where D: Deserializer<'de>
+            {
+                deserialize(d)
+            }
+        }
+    }
+}
+
+/// Serialization of `Option<Vec<T>>`s of durations or timestamps.
+///
+/// The root `serialize` and `deserialize` functions support any nesting of
+/// `Option`s and `Vec`s. This module fixes the shape in its signatures,
+/// which documents it at the field and makes type mismatches easier to
+/// read.
+///
+/// # Examples
+///
+/// ```
+/// extern crate serde_humantime;
+/// extern crate serde;
+/// #[macro_use]
+/// extern crate serde_derive;
+///
+/// use std::time::{Duration, SystemTime};
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Retry {
+///     #[serde(with = "serde_humantime::option_vec")]
+///     backoff: Option<Vec<Duration>>,
+///     #[serde(with = "serde_humantime::vec_option")]
+///     attempts: Vec<Option<SystemTime>>,
+/// }
+///
+/// # fn main() {}
+/// ```
+#[cfg(any(feature = "ser", feature = "de"))]
+pub mod option_vec {
+    #[cfg(feature = "de")]
+    use serde::de::Deserializer;
+    #[cfg(feature = "ser")]
+    use serde::ser::Serializer;
+
+    use HumanTime;
+
+    /// Serializes an `Option<Vec<T>>` of durations or timestamps.
+    #[cfg(feature = "ser")]
+    pub fn serialize<T, S>(d: &Option<Vec<T>>, s: S) -> Result<S::Ok, S::Error>
+        where T: HumanTime,
+              S: Serializer
+    {
+        ::serialize(d, s)
+    }
+
+    /// Deserializes an `Option<Vec<T>>` of durations or timestamps.
+    #[cfg(feature = "de")]
+    pub fn deserialize<'de, T, D>(d: D) -> Result<Option<Vec<T>>, D::Error>
+        where T: HumanTime,
+              D: Deserializer<'de>
+    {
+        ::deserialize(d)
+    }
+}
+
+/// Serialization of `Vec<Option<T>>`s of durations or timestamps.
+///
+/// See the `option_vec` module.
+#[cfg(any(feature = "ser", feature = "de"))]
+pub mod vec_option {
+    #[cfg(feature = "de")]
+    use serde::de::Deserializer;
+    #[cfg(feature = "ser")]
+    use serde::ser::Serializer;
+
+    use HumanTime;
+
+    /// Serializes a `Vec<Option<T>>` of durations or timestamps.
+    #[cfg(feature = "ser")]
+    pub fn serialize<T, S>(d: &Vec<Option<T>>, s: S) -> Result<S::Ok, S::Error>
+        where T: HumanTime,
+              S: Serializer
+    {
+        ::serialize(d, s)
+    }
+
+    /// Deserializes a `Vec<Option<T>>` of durations or timestamps.
+    #[cfg(feature = "de")]
+    pub fn deserialize<'de, T, D>(d: D) -> Result<Vec<Option<T>>, D::Error>
+        where T: HumanTime,
+              D: Deserializer<'de>
+    {
+        ::deserialize(d)
+    }
+}
+
+/// Serialization of maps with duration or timestamp values.
+///
+/// The root `serialize` and `deserialize` functions support `HashMap`s and
+/// `BTreeMap`s with any key type serde supports. Like `option_vec`, this
+/// module fixes the shape in its signatures. It covers `HashMap`s, and its
+/// `btree` submodule `BTreeMap`s.
+///
+/// # Examples
+///
+/// ```
+/// extern crate serde_humantime;
+/// extern crate serde;
+/// #[macro_use]
+/// extern crate serde_derive;
+///
+/// use std::collections::{BTreeMap, HashMap};
+/// use std::time::{Duration, SystemTime};
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Config {
+///     #[serde(with = "serde_humantime::map_values")]
+///     timeouts: HashMap<String, Duration>,
+///     #[serde(with = "serde_humantime::map_values::btree")]
+///     last_seen: BTreeMap<String, SystemTime>,
+/// }
+///
+/// # fn main() {}
+/// ```
+#[cfg(any(feature = "ser", feature = "de"))]
+pub mod map_values {
+    #[cfg(feature = "de")]
+    use serde::de::{Deserialize, DeserializeOwned, Deserializer};
+    #[cfg(feature = "ser")]
+    use serde::ser::{Serialize, Serializer};
+    use std::collections::HashMap;
+    use std::hash::{BuildHasher, Hash};
+
+    use HumanTime;
+    #[cfg(feature = "ser")]
+    use traits::Ref;
+    #[cfg(feature = "de")]
+    use traits::Wrap;
+
+    /// Serializes a `HashMap` of durations or timestamps.
+    #[cfg(feature = "ser")]
+    pub fn serialize<K, V, H, S>(m: &HashMap<K, V, H>, s: S) -> Result<S::Ok, S::Error>
+        where K: Serialize + Eq + Hash,
+              V: HumanTime,
+              H: BuildHasher,
+              S: Serializer
+    {
+        s.collect_map(m.iter().map(|(k, v)| (k, Ref(v))))
+    }
 
-    impl<'de2> Visitor<'de2> for V {
-        type Value = Duration;
+    /// Deserializes a `HashMap` of durations or timestamps.
+    #[cfg(feature = "de")]
+    pub fn deserialize<'de, K, V, H, D>(d: D) -> Result<HashMap<K, V, H>, D::Error>
+        where K: DeserializeOwned + Eq + Hash,
+              V: HumanTime,
+              H: BuildHasher + Default,
+              D: Deserializer<'de>
+    {
+        HashMap::<K, Wrap<V>, H>::deserialize(d)
+            .map(|m| m.into_iter().map(|(k, v)| (k, v.0)).collect())
+    }
+
+    /// Serialization of `BTreeMap`s of durations or timestamps.
+    pub mod btree {
+        #[cfg(feature = "de")]
+        use serde::de::{Deserialize, DeserializeOwned, Deserializer};
+        #[cfg(feature = "ser")]
+        use serde::ser::{Serialize, Serializer};
+        use std::collections::BTreeMap;
 
-        fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
-            fmt.write_str("a duration")
+        use HumanTime;
+        #[cfg(feature = "ser")]
+        use traits::Ref;
+        #[cfg(feature = "de")]
+        use traits::Wrap;
+
+        /// Serializes a `BTreeMap` of durations or timestamps.
+        #[cfg(feature = "ser")]
+        pub fn serialize<K, V, S>(m: &BTreeMap<K, V>, s: S) -> Result<S::Ok, S::Error>
+            where K: Serialize + Ord,
+                  V: HumanTime,
+                  S: Serializer
+        {
+            s.collect_map(m.iter().map(|(k, v)| (k, Ref(v))))
         }
 
-        fn visit_str<E>(self, v: &str) -> Result<Duration, E>
-            where E: Error
+        /// Deserializes a `BTreeMap` of durations or timestamps.
+        #[cfg(feature = "de")]
+        pub fn deserialize<'de, K, V, D>(d: D) -> Result<BTreeMap<K, V>, D::Error>
+            where K: DeserializeOwned + Ord,
+                  V: HumanTime,
+                  D: Deserializer<'de>
         {
-            humantime::parse_duration(v).map_err(|_| E::invalid_value(Unexpected::Str(v), &self))
+            BTreeMap::<K, Wrap<V>>::deserialize(d)
+                .map(|m| m.into_iter().map(|(k, v)| (k, v.0)).collect())
         }
     }
+}
+
+/// Deserializes a `Duration` via the humantime crate.
+///
+/// This function has a signature specific to `Duration`, so using it with
+/// `deserialize_with` on a field of the wrong type produces a plain type
+/// mismatch error.
+#[cfg(feature = "de")]
+pub fn deserialize_duration<'de, D>(d: D) -> Result<Duration, D::Error>
+    where D: Deserializer<'de>
+{
+    deserialize(d)
+}
+
+/// Deserializes an `Option<Duration>` via the humantime crate.
+#[cfg(feature = "de")]
+pub fn deserialize_duration_option<'de, D>(d: D) -> Result<Option<Duration>, D::Error>
+    where D: Deserializer<'de>
+{
+    deserialize(d)
+}
 
-    d.deserialize_str(V)
+/// Deserializes a `SystemTime` from an RFC 3339 timestamp via the humantime
+/// crate.
+#[cfg(all(feature = "de", feature = "systemtime"))]
+pub fn deserialize_systemtime<'de, D>(d: D) -> Result<SystemTime, D::Error>
+    where D: Deserializer<'de>
+{
+    rfc3339::deserialize(d)
 }
 
-#[cfg(test)]
+/// Deserializes an `Option<SystemTime>` from an RFC 3339 timestamp via the
+/// humantime crate.
+#[cfg(all(feature = "de", feature = "systemtime"))]
+pub fn deserialize_systemtime_option<'de, D>(d: D) -> Result<Option<SystemTime>, D::Error>
+    where D: Deserializer<'de>
+{
+    deserialize(d)
+}
+
+/// Serializes a `Duration` via the humantime crate.
+///
+/// This function has a signature specific to `Duration`, so using it with
+/// `serialize_with` on a field of the wrong type produces a plain type
+/// mismatch error.
+#[cfg(feature = "ser")]
+pub fn serialize_duration<S>(d: &Duration, s: S) -> Result<S::Ok, S::Error>
+    where S: Serializer
+{
+    serialize(d, s)
+}
+
+/// Serializes an `Option<Duration>` via the humantime crate.
+#[cfg(feature = "ser")]
+pub fn serialize_duration_option<S>(d: &Option<Duration>, s: S) -> Result<S::Ok, S::Error>
+    where S: Serializer
+{
+    serialize(d, s)
+}
+
+/// Serializes a `SystemTime` as an RFC 3339 timestamp via the humantime
+/// crate.
+#[cfg(all(feature = "ser", feature = "systemtime"))]
+pub fn serialize_systemtime<S>(t: &SystemTime, s: S) -> Result<S::Ok, S::Error>
+    where S: Serializer
+{
+    rfc3339::serialize(t, s)
+}
+
+/// Serializes an `Option<SystemTime>` as an RFC 3339 timestamp via the
+/// humantime crate.
+#[cfg(all(feature = "ser", feature = "systemtime"))]
+pub fn serialize_systemtime_option<S>(t: &Option<SystemTime>, s: S) -> Result<S::Ok, S::Error>
+    where S: Serializer
+{
+    serialize(t, s)
+}
+
+#[cfg(all(test, feature = "ser", feature = "de"))]
 mod test {
     use super::*;
 
     #[test]
     fn with() {
-        #[derive(Deserialize)]
+        #[derive(Serialize, Deserialize)]
         struct Foo {
             #[serde(with = "super")]
             time: Duration,
@@ -130,6 +950,85 @@ mod test {
         let json = r#"{"time": "15 seconds"}"#;
         let foo = serde_json::from_str::<Foo>(json).unwrap();
         assert_eq!(foo.time, Duration::from_secs(15));
+
+        let foo = Foo { time: Duration::new(90, 5) };
+        let json = serde_json::to_string(&foo).unwrap();
+        assert_eq!(json, r#"{"time":"1m 30s 5ns"}"#);
+    }
+
+    #[test]
+    fn numbers() {
+        let cases = [("30", Duration::from_secs(30)),
+                     ("1.5", Duration::from_millis(1500)),
+                     ("0", Duration::from_secs(0)),
+                     ("1e-9", Duration::new(0, 1))];
+        for &(json, d) in &cases {
+            assert_eq!(serde_json::from_str::<De<Duration>>(json).unwrap(), d, "{}", json);
+        }
+        let d = serde_json::from_str::<De<Vec<Option<Duration>>>>(r#"[15, "1m", null]"#).unwrap();
+        assert_eq!(d.into_inner(), [Some(Duration::from_secs(15)), Some(Duration::from_secs(60)), None]);
+
+        for json in &["-1", "-0.5", "1e300", "true"] {
+            assert!(serde_json::from_str::<De<Duration>>(json).is_err(), "{}", json);
+        }
+        assert!(serde_json::from_str::<De<Duration, marker::Iso8601>>("30").is_err());
+    }
+
+    #[test]
+    fn compact() {
+        #[derive(Serialize, Deserialize)]
+        struct Foo {
+            #[serde(with = "super")]
+            time: Duration,
+            retention: De<Option<Duration>, marker::Iso8601>,
+        }
+
+        let d = Duration::new(5400, 5);
+        let bytes = bincode::serialize(&De::<_>::new(d)).unwrap();
+        assert_eq!(bytes, bincode::serialize(&d).unwrap());
+        assert_eq!(bincode::deserialize::<De<Duration>>(&bytes).unwrap(), d);
+
+        let foo = Foo { time: d, retention: De::new(Some(Duration::from_secs(86400))) };
+        let bytes = bincode::serialize(&foo).unwrap();
+        assert_eq!(bytes.len(), 12 + 1 + 12);
+        let foo = bincode::deserialize::<Foo>(&bytes).unwrap();
+        assert_eq!((foo.time, foo.retention.into_inner()), (d, Some(Duration::from_secs(86400))));
+    }
+
+    #[cfg(feature = "systemtime")]
+    #[test]
+    fn compact_systemtime() {
+        let t = std::time::UNIX_EPOCH + Duration::new(1518563312, 5);
+        let bytes = bincode::serialize(&De::<_>::new(t)).unwrap();
+        assert_eq!(bytes, bincode::serialize(&t).unwrap());
+        assert_eq!(bincode::deserialize::<De<SystemTime>>(&bytes).unwrap(), t);
+        assert_eq!(serde_json::to_string(&De::<_>::new(t)).unwrap(), r#""2018-02-13T23:08:32.000000005Z""#);
+    }
+
+    #[cfg(feature = "systemtime")]
+    #[test]
+    fn generic() {
+        #[derive(Serialize, Deserialize)]
+        struct Foo {
+            #[serde(with = "super")]
+            time: Duration,
+            #[serde(with = "super")]
+            stamp: SystemTime,
+            #[serde(with = "super::option", default)]
+            maybe_time: Option<Duration>,
+            maybe_stamp: Option<Serde<SystemTime>>,
+        }
+
+        let json = r#"{"time":"15s","stamp":"1970-01-01T00:00:15Z","maybe_stamp":null}"#;
+        let foo = serde_json::from_str::<Foo>(json).unwrap();
+        assert_eq!(foo.time, Duration::from_secs(15));
+        assert_eq!(foo.stamp, std::time::UNIX_EPOCH + Duration::from_secs(15));
+        assert_eq!(foo.maybe_time, None);
+        assert!(foo.maybe_stamp.is_none());
+
+        let foo = Foo { maybe_time: Some(Duration::from_secs(1)), ..foo };
+        assert_eq!(serde_json::to_string(&foo).unwrap(),
+                   r#"{"time":"15s","stamp":"1970-01-01T00:00:15Z","maybe_time":"1s","maybe_stamp":null}"#);
     }
 
     #[test]
@@ -151,4 +1050,217 @@ mod test {
         let foo = serde_json::from_str::<Foo>(json).unwrap();
         assert_eq!(foo.time.into_inner(), None);
     }
+
+    #[test]
+    fn empty_option() {
+        #[derive(Serialize, Deserialize, PartialEq, Debug)]
+        struct Foo {
+            #[serde(with = "option::empty")]
+            time: Option<Duration>,
+        }
+
+        let foo = serde_json::from_str::<Foo>(r#"{"time": ""}"#).unwrap();
+        assert_eq!(foo, Foo { time: None });
+        assert_eq!(serde_json::to_string(&foo).unwrap(), r#"{"time":""}"#);
+
+        let foo = serde_json::from_str::<Foo>(r#"{"time": "15s"}"#).unwrap();
+        assert_eq!(foo, Foo { time: Some(Duration::from_secs(15)) });
+        assert_eq!(serde_json::to_string(&foo).unwrap(), r#"{"time":"15s"}"#);
+
+        assert!(serde_json::from_str::<Foo>(r#"{"time": "bogus"}"#).is_err());
+    }
+
+    #[test]
+    fn partial_eq() {
+        let time = serde_json::from_str::<De<Duration>>(r#""15s""#).unwrap();
+        assert!(time == Duration::from_secs(15));
+        assert!(Duration::from_secs(15) == time);
+        assert!(time != Duration::from_secs(16));
+    }
+
+    #[test]
+    fn sum() {
+        let budgets = serde_json::from_str::<Vec<De<Duration>>>(r#"["1h", "30m", "15s"]"#).unwrap();
+        let total: Duration = budgets.iter().sum();
+        assert_eq!(total, Duration::from_secs(5415));
+        let total: De<Duration> = budgets.into_iter().sum();
+        assert_eq!(serde_json::to_string(&total).unwrap(), r#""1h 30m 15s""#);
+    }
+
+    #[cfg(feature = "systemtime")]
+    #[test]
+    fn partial_eq_systemtime() {
+        let stamp = serde_json::from_str::<De<SystemTime>>(r#""1970-01-01T00:00:15Z""#).unwrap();
+        let expected = std::time::UNIX_EPOCH + Duration::from_secs(15);
+        assert!(stamp == expected);
+        assert!(expected == stamp);
+    }
+
+    #[test]
+    fn debug() {
+        let time = serde_json::from_str::<De<Duration>>(r#""1h 30m""#).unwrap();
+        assert_eq!(format!("{:?}", time), "De(5400s)");
+        assert_eq!(format!("{:#?}", time), "De {\n    value: 5400s,\n    human: \"1h 30m\",\n}");
+
+        let time = serde_json::from_str::<De<Option<Duration>>>("null").unwrap();
+        assert_eq!(format!("{:#?}", time), "De {\n    value: None,\n}");
+    }
+
+    #[test]
+    fn borrow() {
+        use std::collections::HashMap;
+
+        let time = serde_json::from_str::<De<Duration>>(r#""15s""#).unwrap();
+        let mut map = HashMap::new();
+        map.insert(Duration::from_secs(15), "fifteen");
+        assert_eq!(map.get(time.borrow()), Some(&"fifteen"));
+        assert_eq!(*time.as_ref(), Duration::from_secs(15));
+    }
+
+    #[test]
+    fn wrap_vec() {
+        let times = vec![Duration::from_secs(1), Duration::from_secs(2)];
+        let ptr = times.as_ptr() as usize;
+        let wrapped: Vec<De<Duration>> = De::wrap_vec(times);
+        assert_eq!(wrapped.as_ptr() as usize, ptr);
+        assert_eq!(serde_json::to_string(&wrapped).unwrap(), r#"["1s","2s"]"#);
+        let wrapped = De::<_, marker::Iso8601>::wrap_vec(De::unwrap_vec(wrapped));
+        assert_eq!(serde_json::to_string(&wrapped).unwrap(), r#"["PT1S","PT2S"]"#);
+
+        let times = serde_json::from_str::<Vec<De<Duration>>>(r#"["3s","4s"]"#).unwrap();
+        let ptr = times.as_ptr() as usize;
+        let unwrapped = De::unwrap_vec(times);
+        assert_eq!(unwrapped.as_ptr() as usize, ptr);
+        assert_eq!(unwrapped, [Duration::from_secs(3), Duration::from_secs(4)]);
+    }
+
+    #[cfg(feature = "systemtime")]
+    #[test]
+    fn deserialize_with() {
+        #[derive(Deserialize)]
+        struct Foo {
+            #[serde(deserialize_with = "deserialize_duration")]
+            time: Duration,
+            #[serde(deserialize_with = "deserialize_duration_option")]
+            maybe_time: Option<Duration>,
+            #[serde(deserialize_with = "deserialize_systemtime")]
+            stamp: SystemTime,
+            #[serde(deserialize_with = "deserialize_systemtime_option")]
+            maybe_stamp: Option<SystemTime>,
+        }
+
+        let json = r#"{
+            "time": "15 seconds",
+            "maybe_time": null,
+            "stamp": "1970-01-01T00:00:15Z",
+            "maybe_stamp": "1970-01-01T00:00:15Z"
+        }"#;
+        let foo = serde_json::from_str::<Foo>(json).unwrap();
+        assert_eq!(foo.time, Duration::from_secs(15));
+        assert_eq!(foo.maybe_time, None);
+        assert_eq!(foo.stamp, std::time::UNIX_EPOCH + Duration::from_secs(15));
+        assert_eq!(foo.maybe_stamp, Some(std::time::UNIX_EPOCH + Duration::from_secs(15)));
+    }
+
+    #[cfg(feature = "systemtime")]
+    #[test]
+    fn serialize_with() {
+        #[derive(Serialize)]
+        struct Foo {
+            #[serde(serialize_with = "serialize_duration")]
+            time: Duration,
+            #[serde(serialize_with = "serialize_duration_option")]
+            maybe_time: Option<Duration>,
+            #[serde(serialize_with = "serialize_systemtime")]
+            stamp: SystemTime,
+            #[serde(serialize_with = "serialize_systemtime_option")]
+            maybe_stamp: Option<SystemTime>,
+        }
+
+        let foo = Foo {
+            time: Duration::from_secs(15),
+            maybe_time: None,
+            stamp: std::time::UNIX_EPOCH + Duration::from_secs(15),
+            maybe_stamp: Some(std::time::UNIX_EPOCH + Duration::from_secs(15)),
+        };
+        let json = serde_json::to_string(&foo).unwrap();
+        assert_eq!(json,
+                   r#"{"time":"15s","maybe_time":null,"stamp":"1970-01-01T00:00:15Z","maybe_stamp":"1970-01-01T00:00:15Z"}"#);
+    }
+
+    #[test]
+    fn nested() {
+        #[derive(Serialize, Deserialize, PartialEq, Debug)]
+        struct Foo {
+            #[serde(with = "option_vec")]
+            backoff: Option<Vec<Duration>>,
+            #[serde(with = "vec_option")]
+            timeouts: Vec<Option<Duration>>,
+            #[serde(with = "super")]
+            stages: Vec<Vec<Duration>>,
+        }
+
+        let json = r#"{"backoff":["1s","2s"],"timeouts":["15s",null],"stages":[["1m"],[]]}"#;
+        let foo = serde_json::from_str::<Foo>(json).unwrap();
+        assert_eq!(foo,
+                   Foo {
+                       backoff: Some(vec![Duration::from_secs(1), Duration::from_secs(2)]),
+                       timeouts: vec![Some(Duration::from_secs(15)), None],
+                       stages: vec![vec![Duration::from_secs(60)], vec![]],
+                   });
+        assert_eq!(serde_json::to_string(&foo).unwrap(), json);
+
+        let json = r#"{"backoff":null,"timeouts":[],"stages":[]}"#;
+        assert_eq!(serde_json::to_string(&serde_json::from_str::<Foo>(json).unwrap()).unwrap(), json);
+        assert!(serde_json::from_str::<Foo>(r#"{"backoff":["bogus"],"timeouts":[],"stages":[]}"#).is_err());
+
+        let d = serde_json::from_str::<De<Vec<Option<Duration>>, marker::Iso8601>>(r#"["PT1M",null]"#).unwrap();
+        assert_eq!(d.into_inner(), [Some(Duration::from_secs(60)), None]);
+
+        #[derive(Serialize, Deserialize)]
+        struct Bar {
+            #[serde(with = "super")]
+            timeouts: std::collections::BTreeMap<String, Option<Duration>>,
+            retention: De<std::collections::HashMap<String, Vec<Duration>>, marker::Iso8601>,
+        }
+
+        let json = r#"{"timeouts":{"read":"15s","write":null},"retention":{"logs":["P1D"]}}"#;
+        let bar = serde_json::from_str::<Bar>(json).unwrap();
+        assert_eq!(bar.timeouts["read"], Some(Duration::from_secs(15)));
+        assert_eq!(bar.timeouts["write"], None);
+        assert_eq!(bar.retention.as_ref()["logs"], [Duration::from_secs(86400)]);
+        assert_eq!(serde_json::to_string(&bar).unwrap(), json);
+        assert!(serde_json::from_str::<Bar>(r#"{"timeouts":{"read":"bogus"},"retention":{}}"#).is_err());
+
+        type Hasher = std::hash::BuildHasherDefault<std::collections::hash_map::DefaultHasher>;
+        let d = serde_json::from_str::<De<std::collections::HashMap<String, Duration, Hasher>>>(r#"{"read":"15s"}"#)
+            .unwrap();
+        assert_eq!(d.as_ref()["read"], Duration::from_secs(15));
+    }
+
+    #[test]
+    fn map_values() {
+        #[derive(Serialize, Deserialize)]
+        struct Foo {
+            #[serde(with = "map_values")]
+            retries: std::collections::HashMap<String, Duration>,
+            #[serde(with = "map_values::btree")]
+            limits: std::collections::BTreeMap<u32, Duration>,
+        }
+
+        let json = r#"{"retries":{"connect":"2s"},"limits":{"1":"1m","2":"1h"}}"#;
+        let foo = serde_json::from_str::<Foo>(json).unwrap();
+        assert_eq!(foo.retries["connect"], Duration::from_secs(2));
+        assert_eq!(foo.limits[&2], Duration::from_secs(3600));
+        assert_eq!(serde_json::to_string(&foo).unwrap(), json);
+
+        #[derive(Serialize)]
+        struct Bar {
+            #[serde(serialize_with = "map_values::btree::serialize")]
+            limits: std::collections::BTreeMap<&'static str, Duration>,
+        }
+
+        let bar = Bar { limits: vec![("read", Duration::from_secs(60))].into_iter().collect() };
+        assert_eq!(serde_json::to_string(&bar).unwrap(), r#"{"limits":{"read":"1m"}}"#);
+    }
 }
@@ -0,0 +1,136 @@
+//! Serialization of `SystemTime`s in the system's local timezone.
+//!
+//! This module is only available with the `tzdb` feature. Timestamps are
+//! serialized as RFC 3339 timestamps with the local UTC offset in effect at
+//! that instant, such as `2024-03-10T09:00:00-04:00`, using the platform's
+//! timezone database (and the `TZ` environment variable where supported).
+//!
+//! When deserializing, timestamps with a `Z` or numeric UTC offset are
+//! accepted as-is, while timestamps without one, such as
+//! `2024-03-10 09:00`, are interpreted as a local wall clock time. When a
+//! wall clock time occurs twice because of a daylight saving time transition,
+//! the earlier instant is used. Wall clock times skipped by a transition are
+//! rejected.
+//!
+//! # Examples
+//!
+//! ```
+//! extern crate serde_humantime;
+//! extern crate serde;
+//! #[macro_use]
+//! extern crate serde_derive;
+//!
+//! use std::time::SystemTime;
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Job {
+//!     #[serde(with = "serde_humantime::local")]
+//!     next_run: SystemTime,
+//! }
+//!
+//! # fn main() {}
+//! ```
+#[cfg(feature = "ser")]
+use chrono::{DateTime, Offset};
+use chrono::{Local, TimeZone};
+#[cfg(feature = "de")]
+use chrono::{LocalResult, NaiveDate};
+#[cfg(feature = "de")]
+use serde::de::{Deserializer, Visitor, Error, Unexpected};
+#[cfg(feature = "ser")]
+use serde::ser::{self, Serializer};
+use std::fmt;
+use std::time::SystemTime;
+
+use civil;
+#[cfg(feature = "ser")]
+use format;
+
+/// Serializes a `SystemTime` as an RFC 3339 timestamp in local time.
+#[cfg(feature = "ser")]
+pub fn serialize<S>(t: &SystemTime, s: S) -> Result<S::Ok, S::Error>
+    where S: Serializer
+{
+    let local = LocalTimestamp::new(*t).ok_or_else(|| ser::Error::custom("timestamp out of range"))?;
+    s.collect_str(&local)
+}
+
+/// Deserializes a `SystemTime`, interpreting timestamps without a UTC offset
+/// in local time.
+#[cfg(feature = "de")]
+pub fn deserialize<'de, D>(d: D) -> Result<SystemTime, D::Error>
+    where D: Deserializer<'de>
+{
+    d.deserialize_str(V)
+}
+
+#[cfg(feature = "ser")]
+struct LocalTimestamp {
+    secs: i64,
+    nanos: u32,
+    offset: i32,
+}
+
+#[cfg(feature = "ser")]
+impl LocalTimestamp {
+    fn new(t: SystemTime) -> Option<LocalTimestamp> {
+        let (secs, nanos) = civil::to_unix(t);
+        let utc = DateTime::from_timestamp(secs, nanos)?;
+        let offset = Local.offset_from_utc_datetime(&utc.naive_utc()).fix().local_minus_utc();
+        let local_secs = secs + offset as i64;
+        let (year, _, _) = civil::civil_from_days(local_secs.div_euclid(civil::SECS_PER_DAY));
+        if !(0..=9999).contains(&year) {
+            return None;
+        }
+        Some(LocalTimestamp {
+            secs: local_secs,
+            nanos,
+            offset,
+        })
+    }
+}
+
+#[cfg(feature = "ser")]
+impl fmt::Display for LocalTimestamp {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        format::write_datetime(fmt, self.secs, self.nanos)?;
+        format::write_offset(fmt, self.offset)
+    }
+}
+
+#[cfg(feature = "de")]
+struct V;
+
+#[cfg(feature = "de")]
+impl<'de> Visitor<'de> for V {
+    type Value = SystemTime;
+
+    fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str("a timestamp")
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<SystemTime, E>
+        where E: Error
+    {
+        parse(v).ok_or_else(|| E::invalid_value(Unexpected::Str(v), &self))
+    }
+}
+
+#[cfg(feature = "de")]
+fn parse(s: &str) -> Option<SystemTime> {
+    if let Some((rest, offset)) = civil::split_offset(s) {
+        let dt = civil::parse_datetime(rest)?;
+        let secs = civil::days_from_civil(dt.year, dt.month, dt.day) * civil::SECS_PER_DAY +
+                   (dt.hour * 3600 + dt.minute * 60 + dt.second) as i64 - offset as i64;
+        return civil::from_unix(secs, dt.nanos);
+    }
+
+    let dt = civil::parse_datetime(s)?;
+    let naive = NaiveDate::from_ymd_opt(dt.year as i32, dt.month, dt.day)?
+        .and_hms_nano_opt(dt.hour, dt.minute, dt.second, dt.nanos)?;
+    match Local.from_local_datetime(&naive) {
+        LocalResult::Single(t) => Some(SystemTime::from(t)),
+        LocalResult::Ambiguous(a, b) => Some(SystemTime::from(a.min(b))),
+        LocalResult::None => None,
+    }
+}
@@ -0,0 +1,279 @@
+//! Serialization of maps keyed by durations or timestamps.
+//!
+//! Keys are always serialized as strings in the format of the crate root,
+//! like `5m` or `2018-02-14T00:28:07Z`, even in formats which aren't human
+//! readable, where `De` and the crate root use a compact numeric encoding.
+//! Strings are the only keys some formats, like JSON, support. Values are
+//! serialized with their own `Serialize` implementations.
+//!
+//! The functions of this module work with `HashMap`s, and those of its
+//! `btree` submodule with `BTreeMap`s.
+//!
+//! # Examples
+//!
+//! ```
+//! extern crate serde_humantime;
+//! extern crate serde;
+//! #[macro_use]
+//! extern crate serde_derive;
+//!
+//! use std::collections::HashMap;
+//! use std::time::Duration;
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct BucketConfig {
+//!     capacity: u32,
+//! }
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Config {
+//!     #[serde(with = "serde_humantime::map_keys")]
+//!     buckets: HashMap<Duration, BucketConfig>,
+//! }
+//!
+//! # fn main() {}
+//! ```
+#[cfg(not(feature = "de"))]
+use serde::de;
+#[cfg(feature = "de")]
+use serde::de::{Deserialize, DeserializeOwned};
+use serde::de::Deserializer;
+#[cfg(not(feature = "ser"))]
+use serde::ser;
+#[cfg(feature = "ser")]
+use serde::ser::Serialize;
+use serde::ser::Serializer;
+#[cfg(any(feature = "ser", feature = "de"))]
+use std::collections::HashMap;
+#[cfg(feature = "de")]
+use std::hash::{BuildHasher, Hash};
+#[cfg(any(feature = "ser", feature = "de"))]
+use std::marker::PhantomData;
+use std::time::Duration;
+#[cfg(feature = "systemtime")]
+use std::time::SystemTime;
+
+#[cfg(all(feature = "ser", feature = "systemtime"))]
+use format;
+#[cfg(any(feature = "ser", feature = "de"))]
+use marker::{self, Human};
+
+/// A type which can be used as a map key with this module.
+///
+/// This trait is implemented for `Duration` and `SystemTime`. Like
+/// `HumanTime`, both of its methods exist whichever of the `ser` and `de`
+/// features are enabled, and the crate's own implementations of a method
+/// return an error without the corresponding feature.
+pub trait TimeKey: Sized {
+    /// Serializes the key as a string.
+    #[cfg(feature = "ser")]
+    fn serialize_key<S>(&self, s: S) -> Result<S::Ok, S::Error>
+        where S: Serializer;
+
+    /// Serializes the key as a string.
+    ///
+    /// Returns an error unless overridden, since the `ser` feature is
+    /// disabled.
+    #[cfg(not(feature = "ser"))]
+    fn serialize_key<S>(&self, _: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        Err(ser::Error::custom("serialization support is disabled"))
+    }
+
+    /// Deserializes a key from a string.
+    #[cfg(feature = "de")]
+    fn deserialize_key<'de, D>(d: D) -> Result<Self, D::Error>
+        where D: Deserializer<'de>;
+
+    /// Deserializes a key from a string.
+    ///
+    /// Returns an error unless overridden, since the `de` feature is
+    /// disabled.
+    #[cfg(not(feature = "de"))]
+    fn deserialize_key<'de, D>(_: D) -> Result<Self, D::Error>
+        where D: Deserializer<'de>
+    {
+        Err(de::Error::custom("deserialization support is disabled"))
+    }
+}
+
+impl TimeKey for Duration {
+    #[cfg(feature = "ser")]
+    fn serialize_key<S>(&self, s: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        s.collect_str(&marker::FormattedDuration::<Human>(self, PhantomData))
+    }
+
+    #[cfg(feature = "de")]
+    fn deserialize_key<'de, D>(d: D) -> Result<Duration, D::Error>
+        where D: Deserializer<'de>
+    {
+        d.deserialize_str(marker::DurationVisitor::<Human>(PhantomData))
+    }
+}
+
+#[cfg(feature = "systemtime")]
+impl TimeKey for SystemTime {
+    #[cfg(feature = "ser")]
+    fn serialize_key<S>(&self, s: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        format::serialize_display(&marker::FormattedTimestamp::<Human>(self, PhantomData),
+                                  s,
+                                  "timestamp can't be represented in this format")
+    }
+
+    #[cfg(feature = "de")]
+    fn deserialize_key<'de, D>(d: D) -> Result<SystemTime, D::Error>
+        where D: Deserializer<'de>
+    {
+        d.deserialize_str(marker::TimestampVisitor::<Human>(PhantomData))
+    }
+}
+
+/// Serializes a `HashMap` keyed by durations or timestamps.
+#[cfg(feature = "ser")]
+pub fn serialize<K, V, H, S>(m: &HashMap<K, V, H>, s: S) -> Result<S::Ok, S::Error>
+    where K: TimeKey,
+          V: Serialize,
+          S: Serializer
+{
+    s.collect_map(m.iter().map(|(k, v)| (Ref(k), v)))
+}
+
+/// Deserializes a `HashMap` keyed by durations or timestamps.
+#[cfg(feature = "de")]
+pub fn deserialize<'de, K, V, H, D>(d: D) -> Result<HashMap<K, V, H>, D::Error>
+    where K: TimeKey + Eq + Hash,
+          V: DeserializeOwned,
+          H: BuildHasher + Default,
+          D: Deserializer<'de>
+{
+    HashMap::<Wrap<K>, V, H>::deserialize(d)
+        .map(|m| m.into_iter().map(|(k, v)| (k.0, v)).collect())
+}
+
+/// Serialization of `BTreeMap`s keyed by durations or timestamps.
+#[cfg(any(feature = "ser", feature = "de"))]
+pub mod btree {
+    #[cfg(feature = "de")]
+    use serde::de::{Deserialize, DeserializeOwned, Deserializer};
+    #[cfg(feature = "ser")]
+    use serde::ser::{Serialize, Serializer};
+    use std::collections::BTreeMap;
+
+    use super::TimeKey;
+    #[cfg(feature = "ser")]
+    use super::Ref;
+    #[cfg(feature = "de")]
+    use super::Wrap;
+
+    /// Serializes a `BTreeMap` keyed by durations or timestamps.
+    #[cfg(feature = "ser")]
+    pub fn serialize<K, V, S>(m: &BTreeMap<K, V>, s: S) -> Result<S::Ok, S::Error>
+        where K: TimeKey,
+              V: Serialize,
+              S: Serializer
+    {
+        s.collect_map(m.iter().map(|(k, v)| (Ref(k), v)))
+    }
+
+    /// Deserializes a `BTreeMap` keyed by durations or timestamps.
+    #[cfg(feature = "de")]
+    pub fn deserialize<'de, K, V, D>(d: D) -> Result<BTreeMap<K, V>, D::Error>
+        where K: TimeKey + Ord,
+              V: DeserializeOwned,
+              D: Deserializer<'de>
+    {
+        BTreeMap::<Wrap<K>, V>::deserialize(d)
+            .map(|m| m.into_iter().map(|(k, v)| (k.0, v)).collect())
+    }
+}
+
+#[cfg(feature = "ser")]
+struct Ref<'a, K: 'a>(&'a K);
+
+#[cfg(feature = "ser")]
+impl<'a, K> Serialize for Ref<'a, K>
+    where K: TimeKey
+{
+    fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        self.0.serialize_key(s)
+    }
+}
+
+#[cfg(feature = "de")]
+#[derive(PartialEq, Eq, Hash, PartialOrd, Ord)]
+struct Wrap<K>(K);
+
+#[cfg(feature = "de")]
+impl<'de, K> Deserialize<'de> for Wrap<K>
+    where K: TimeKey
+{
+    fn deserialize<D>(d: D) -> Result<Wrap<K>, D::Error>
+        where D: Deserializer<'de>
+    {
+        K::deserialize_key(d).map(Wrap)
+    }
+}
+
+#[cfg(all(test, feature = "ser", feature = "de"))]
+mod test {
+    use bincode;
+    use serde_json;
+    use std::collections::{BTreeMap, HashMap};
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::BuildHasherDefault;
+    use std::time::Duration;
+    #[cfg(feature = "systemtime")]
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Foo {
+        #[serde(with = "super")]
+        buckets: HashMap<Duration, u32>,
+        #[serde(with = "super::btree")]
+        limits: BTreeMap<Duration, String>,
+    }
+
+    #[test]
+    fn round_trip() {
+        let json = r#"{"buckets":{"5m":10},"limits":{"1s":"low","1h 30m":"high"}}"#;
+        let foo = serde_json::from_str::<Foo>(json).unwrap();
+        assert_eq!(foo.buckets[&Duration::from_secs(300)], 10);
+        assert_eq!(foo.limits.keys().cloned().collect::<Vec<_>>(),
+                   [Duration::from_secs(1), Duration::from_secs(5400)]);
+        assert_eq!(serde_json::to_string(&foo).unwrap(), json);
+
+        // keys are strings even in formats which aren't human readable
+        let bytes = bincode::serialize(&foo).unwrap();
+        assert!(bytes.windows(6).any(|w| w == b"1h 30m"));
+        assert_eq!(bincode::deserialize::<Foo>(&bytes).unwrap(), foo);
+
+        assert!(serde_json::from_str::<Foo>(r#"{"buckets":{"soon":1},"limits":{}}"#).is_err());
+
+        type Hasher = BuildHasherDefault<DefaultHasher>;
+        let mut de = serde_json::Deserializer::from_str(r#"{"5m":10}"#);
+        let buckets: HashMap<Duration, u32, Hasher> = super::deserialize(&mut de).unwrap();
+        assert_eq!(buckets[&Duration::from_secs(300)], 10);
+    }
+
+    #[cfg(feature = "systemtime")]
+    #[test]
+    fn timestamps() {
+        #[derive(Serialize, Deserialize)]
+        struct Bar {
+            #[serde(with = "super")]
+            events: HashMap<SystemTime, String>,
+        }
+
+        let json = r#"{"events":{"2018-02-13T23:08:32Z":"deploy"}}"#;
+        let bar = serde_json::from_str::<Bar>(json).unwrap();
+        assert_eq!(bar.events[&(UNIX_EPOCH + Duration::from_secs(1518563312))], "deploy");
+        assert_eq!(serde_json::to_string(&bar).unwrap(), json);
+    }
+}
@@ -0,0 +1,616 @@
+//! Marker types selecting the format used by `De`.
+//!
+//! `De` takes a second type parameter naming the format its value is
+//! serialized in. It defaults to `Human`, the format of the crate root, so
+//! `De<Duration>` is unchanged, while `De<Duration, Iso8601>` serializes as
+//! an ISO 8601 duration. This keeps the choice of format in field types
+//! rather than in `with` annotations.
+//!
+//! # Examples
+//!
+//! ```
+//! extern crate serde_humantime;
+//! extern crate serde;
+//! #[macro_use]
+//! extern crate serde_derive;
+//!
+//! use serde_humantime::De;
+//! use serde_humantime::marker::Iso8601;
+//! use std::time::Duration;
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Foo {
+//!     timeout: De<Duration>,
+//!     retention: De<Option<Duration>, Iso8601>,
+//! }
+//!
+//! # fn main() {}
+//! ```
+//!
+//! Custom formats can be defined by implementing `DurationFormat` or
+//! `TimestampFormat` for a marker type:
+//!
+//! ```
+//! extern crate serde_humantime;
+//! extern crate serde_json;
+//!
+//! use serde_humantime::{De, Error, ErrorKind};
+//! use serde_humantime::marker::DurationFormat;
+//! use std::fmt;
+//! use std::time::Duration;
+//!
+//! /// Whole minutes, like `90min`.
+//! struct Minutes;
+//!
+//! impl DurationFormat for Minutes {
+//!     fn parse(s: &str) -> Result<Duration, Error> {
+//!         let minutes = s.strip_suffix("min").ok_or(ErrorKind::UnknownUnit)?;
+//!         let minutes = minutes.parse::<u64>().map_err(|_| ErrorKind::InvalidNumber)?;
+//!         Ok(Duration::from_secs(minutes * 60))
+//!     }
+//!
+//!     fn format(d: &Duration, fmt: &mut fmt::Formatter) -> fmt::Result {
+//!         write!(fmt, "{}min", d.as_secs() / 60)
+//!     }
+//! }
+//!
+//! # fn main() {
+//! let d = serde_json::from_str::<De<Duration, Minutes>>(r#""90min""#).unwrap();
+//! assert_eq!(d.into_inner(), Duration::from_secs(5400));
+//! # }
+//! ```
+#[cfg(feature = "de")]
+use serde::de::{Deserialize, Deserializer, Expected, Visitor, Error as DeError, Unexpected};
+#[cfg(feature = "ser")]
+use serde::ser::{Serialize, Serializer};
+use std::collections::{BTreeMap, HashMap};
+#[cfg(any(feature = "ser", feature = "de"))]
+use std::fmt;
+use std::hash::{BuildHasher, Hash};
+#[cfg(any(feature = "ser", feature = "de"))]
+use std::marker::PhantomData;
+use std::time::Duration;
+#[cfg(feature = "systemtime")]
+use std::time::SystemTime;
+
+#[cfg(feature = "ser")]
+use format;
+#[cfg(any(feature = "ser", feature = "de"))]
+use iso8601;
+#[cfg(all(feature = "ser", feature = "systemtime"))]
+use offset::UtcOffset;
+#[cfg(feature = "de")]
+use parse;
+#[cfg(all(feature = "de", feature = "systemtime"))]
+use rfc3339;
+use traits::MapKey;
+#[cfg(feature = "de")]
+use {De, Error, ErrorKind};
+
+/// A format in which values of type `T` can be serialized.
+///
+/// Formats supporting `T` automatically support `Option<T>`, `Vec<T>`, and
+/// `HashMap`s and `BTreeMap`s with values of type `T` as well.
+pub trait Format<T> {
+    /// Serializes a value.
+    #[cfg(feature = "ser")]
+    fn serialize<S>(value: &T, s: S) -> Result<S::Ok, S::Error>
+        where S: Serializer;
+
+    /// Deserializes a value.
+    #[cfg(feature = "de")]
+    fn deserialize<'de, D>(d: D) -> Result<T, D::Error>
+        where D: Deserializer<'de>;
+}
+
+/// A string representation of `Duration`s.
+///
+/// Every implementation is a `Format<Duration>`, serialized as a string in
+/// human readable formats like JSON. Other formats, like bincode, use serde's
+/// own compact encoding of seconds and nanoseconds instead.
+pub trait DurationFormat {
+    /// Parses a duration.
+    #[cfg(feature = "de")]
+    fn parse(s: &str) -> Result<Duration, Error>;
+
+    /// Formats a duration.
+    #[cfg(feature = "ser")]
+    fn format(d: &Duration, fmt: &mut fmt::Formatter) -> fmt::Result;
+
+    /// Describes the format in deserialization errors.
+    ///
+    /// Defaults to `a duration`.
+    #[cfg(feature = "de")]
+    fn expecting(fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str("a duration")
+    }
+
+    /// Returns the unit of plain numbers accepted in place of strings, or
+    /// `None` if only strings are accepted.
+    ///
+    /// Numbers are only accepted by human readable formats like JSON.
+    /// Defaults to `None`.
+    #[cfg(feature = "de")]
+    fn number_unit() -> Option<Duration> {
+        None
+    }
+}
+
+impl<F> Format<Duration> for F
+    where F: DurationFormat
+{
+    #[cfg(feature = "ser")]
+    fn serialize<S>(value: &Duration, s: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        if !s.is_human_readable() {
+            return value.serialize(s);
+        }
+        s.collect_str(&FormattedDuration::<F>(value, PhantomData))
+    }
+
+    #[cfg(feature = "de")]
+    fn deserialize<'de, D>(d: D) -> Result<Duration, D::Error>
+        where D: Deserializer<'de>
+    {
+        if !d.is_human_readable() {
+            return <Duration as Deserialize>::deserialize(d);
+        }
+        if F::number_unit().is_some() {
+            d.deserialize_any(DurationVisitor::<F>(PhantomData))
+        } else {
+            d.deserialize_str(DurationVisitor::<F>(PhantomData))
+        }
+    }
+}
+
+#[cfg(feature = "ser")]
+pub(crate) struct FormattedDuration<'a, F>(pub(crate) &'a Duration, pub(crate) PhantomData<F>);
+
+#[cfg(feature = "ser")]
+impl<'a, F> fmt::Display for FormattedDuration<'a, F>
+    where F: DurationFormat
+{
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        F::format(self.0, fmt)
+    }
+}
+
+#[cfg(feature = "de")]
+pub(crate) struct DurationVisitor<F>(pub(crate) PhantomData<F>);
+
+#[cfg(feature = "de")]
+impl<'de, F> Visitor<'de> for DurationVisitor<F>
+    where F: DurationFormat
+{
+    type Value = Duration;
+
+    fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        F::expecting(fmt)
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Duration, E>
+        where E: DeError
+    {
+        let unit = F::number_unit().ok_or_else(|| E::invalid_type(Unexpected::Unsigned(v), &self))?;
+        let nanos = (v as u128).checked_mul(unit.as_nanos());
+        match nanos {
+            Some(nanos) if nanos / 1_000_000_000 <= u64::MAX as u128 => {
+                Ok(Duration::new((nanos / 1_000_000_000) as u64, (nanos % 1_000_000_000) as u32))
+            }
+            _ => Err(E::invalid_value(Unexpected::Unsigned(v), &self)),
+        }
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<Duration, E>
+        where E: DeError
+    {
+        if v < 0 {
+            return Err(E::invalid_value(Unexpected::Signed(v), &self));
+        }
+        self.visit_u64(v as u64)
+    }
+
+    fn visit_f64<E>(self, v: f64) -> Result<Duration, E>
+        where E: DeError
+    {
+        let unit = F::number_unit().ok_or_else(|| E::invalid_type(Unexpected::Float(v), &self))?;
+        Duration::try_from_secs_f64(v * unit.as_secs_f64())
+            .map_err(|_| E::invalid_value(Unexpected::Float(v), &self))
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Duration, E>
+        where E: DeError
+    {
+        F::parse(v).map_err(|e| invalid_value(e, v, &self))
+    }
+}
+
+#[cfg(feature = "de")]
+pub(crate) fn invalid_value<E>(e: Error, v: &str, exp: &dyn Expected) -> E
+    where E: DeError
+{
+    match e.kind() {
+        // don't echo back input which was rejected for its size
+        ErrorKind::TooLong | ErrorKind::TooManyComponents => E::custom(e),
+        _ => E::invalid_value(Unexpected::Str(v), exp),
+    }
+}
+
+/// The format used by the crate root: durations as by the humantime crate,
+/// or plain numbers of seconds, and timestamps as RFC 3339 timestamps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Human;
+
+impl DurationFormat for Human {
+    #[cfg(feature = "de")]
+    fn parse(s: &str) -> Result<Duration, Error> {
+        parse::parse_duration(s)
+    }
+
+    #[cfg(feature = "ser")]
+    fn format(d: &Duration, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&format::global_format().format_duration(*d), fmt)
+    }
+
+    #[cfg(feature = "de")]
+    fn number_unit() -> Option<Duration> {
+        Some(Duration::from_secs(1))
+    }
+}
+
+#[cfg(feature = "systemtime")]
+impl TimestampFormat for Human {
+    #[cfg(feature = "de")]
+    fn parse(s: &str) -> Result<SystemTime, Error> {
+        Rfc3339::parse(s)
+    }
+
+    #[cfg(feature = "ser")]
+    fn format(t: &SystemTime, fmt: &mut fmt::Formatter) -> fmt::Result {
+        Rfc3339::format(t, fmt)
+    }
+
+    #[cfg(feature = "de")]
+    fn expecting(fmt: &mut fmt::Formatter) -> fmt::Result {
+        Rfc3339::expecting(fmt)
+    }
+}
+
+/// ISO 8601 durations, as in the `iso8601` module.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Iso8601;
+
+impl DurationFormat for Iso8601 {
+    #[cfg(feature = "de")]
+    fn parse(s: &str) -> Result<Duration, Error> {
+        iso8601::parse(s).ok_or_else(|| ErrorKind::InvalidFormat.into())
+    }
+
+    #[cfg(feature = "ser")]
+    fn format(d: &Duration, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&iso8601::Iso8601(*d, false), fmt)
+    }
+
+    #[cfg(feature = "de")]
+    fn expecting(fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str("an ISO 8601 duration")
+    }
+}
+
+/// RFC 3339 timestamps, as in the `rfc3339` module.
+#[cfg(feature = "systemtime")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Rfc3339;
+
+#[cfg(feature = "systemtime")]
+impl TimestampFormat for Rfc3339 {
+    #[cfg(feature = "de")]
+    fn parse(s: &str) -> Result<SystemTime, Error> {
+        rfc3339::parse(s)
+    }
+
+    #[cfg(feature = "ser")]
+    fn format(t: &SystemTime, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&format::format_rfc3339(*t), fmt)
+    }
+
+    #[cfg(feature = "de")]
+    fn expecting(fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str("an RFC 3339 timestamp")
+    }
+}
+
+/// RFC 3339 timestamps at a fixed UTC offset, given in seconds east of UTC.
+///
+/// Timestamps are serialized at the offset, as in `2018-02-14T08:08:32+09:00`
+/// for `FixedOffset<32400>`, and fail to serialize if the offset isn't a
+/// whole number of minutes less than a day. Timestamps with any offset are
+/// accepted when deserializing.
+///
+/// # Examples
+///
+/// ```
+/// extern crate serde_humantime;
+/// extern crate serde;
+/// #[macro_use]
+/// extern crate serde_derive;
+///
+/// use serde_humantime::De;
+/// use serde_humantime::marker::FixedOffset;
+/// use std::time::SystemTime;
+///
+/// type Jst = FixedOffset<{ 9 * 3600 }>;
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Export {
+///     generated: De<SystemTime, Jst>,
+/// }
+///
+/// # fn main() {}
+/// ```
+#[cfg(feature = "systemtime")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct FixedOffset<const SECONDS: i32>;
+
+#[cfg(feature = "systemtime")]
+impl<const SECONDS: i32> TimestampFormat for FixedOffset<SECONDS> {
+    #[cfg(feature = "de")]
+    fn parse(s: &str) -> Result<SystemTime, Error> {
+        rfc3339::parse_with_offset(s)
+    }
+
+    #[cfg(feature = "ser")]
+    fn format(t: &SystemTime, fmt: &mut fmt::Formatter) -> fmt::Result {
+        let offset = UtcOffset::from_seconds(SECONDS).ok_or(fmt::Error)?;
+        fmt::Display::fmt(&format::format_rfc3339_at(*t, offset), fmt)
+    }
+
+    #[cfg(feature = "de")]
+    fn expecting(fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str("an RFC 3339 timestamp")
+    }
+}
+
+/// A string representation of `SystemTime`s.
+///
+/// Every implementation is a `Format<SystemTime>`, serialized as a string in
+/// human readable formats like JSON. Other formats, like bincode, use serde's
+/// own compact encoding of seconds and nanoseconds since the Unix epoch
+/// instead. Timestamps which `format` fails on produce a serialization error.
+#[cfg(feature = "systemtime")]
+pub trait TimestampFormat {
+    /// Parses a timestamp.
+    #[cfg(feature = "de")]
+    fn parse(s: &str) -> Result<SystemTime, Error>;
+
+    /// Formats a timestamp.
+    #[cfg(feature = "ser")]
+    fn format(t: &SystemTime, fmt: &mut fmt::Formatter) -> fmt::Result;
+
+    /// Describes the format in deserialization errors.
+    ///
+    /// Defaults to `a timestamp`.
+    #[cfg(feature = "de")]
+    fn expecting(fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str("a timestamp")
+    }
+}
+
+#[cfg(feature = "systemtime")]
+impl<F> Format<SystemTime> for F
+    where F: TimestampFormat
+{
+    #[cfg(feature = "ser")]
+    fn serialize<S>(value: &SystemTime, s: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        if !s.is_human_readable() {
+            return value.serialize(s);
+        }
+        format::serialize_display(&FormattedTimestamp::<F>(value, PhantomData),
+                                  s,
+                                  "timestamp can't be represented in this format")
+    }
+
+    #[cfg(feature = "de")]
+    fn deserialize<'de, D>(d: D) -> Result<SystemTime, D::Error>
+        where D: Deserializer<'de>
+    {
+        if !d.is_human_readable() {
+            return <SystemTime as Deserialize>::deserialize(d);
+        }
+        d.deserialize_str(TimestampVisitor::<F>(PhantomData))
+    }
+}
+
+#[cfg(all(feature = "ser", feature = "systemtime"))]
+pub(crate) struct FormattedTimestamp<'a, F>(pub(crate) &'a SystemTime, pub(crate) PhantomData<F>);
+
+#[cfg(all(feature = "ser", feature = "systemtime"))]
+impl<'a, F> fmt::Display for FormattedTimestamp<'a, F>
+    where F: TimestampFormat
+{
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        F::format(self.0, fmt)
+    }
+}
+
+#[cfg(all(feature = "de", feature = "systemtime"))]
+pub(crate) struct TimestampVisitor<F>(pub(crate) PhantomData<F>);
+
+#[cfg(all(feature = "de", feature = "systemtime"))]
+impl<'de, F> Visitor<'de> for TimestampVisitor<F>
+    where F: TimestampFormat
+{
+    type Value = SystemTime;
+
+    fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        F::expecting(fmt)
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<SystemTime, E>
+        where E: DeError
+    {
+        F::parse(v).map_err(|e| invalid_value(e, v, &self))
+    }
+}
+
+impl<T, F> Format<Option<T>> for F
+    where F: Format<T>
+{
+    #[cfg(feature = "ser")]
+    fn serialize<S>(value: &Option<T>, s: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        match *value {
+            Some(ref v) => s.serialize_some(&Ref::<T, F>(v, PhantomData)),
+            None => s.serialize_none(),
+        }
+    }
+
+    #[cfg(feature = "de")]
+    fn deserialize<'de, D>(d: D) -> Result<Option<T>, D::Error>
+        where D: Deserializer<'de>
+    {
+        <Option<De<T, F>> as Deserialize>::deserialize(d).map(|v| v.map(De::into_inner))
+    }
+}
+
+impl<T, F> Format<Vec<T>> for F
+    where F: Format<T>
+{
+    #[cfg(feature = "ser")]
+    fn serialize<S>(value: &Vec<T>, s: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        s.collect_seq(value.iter().map(|v| Ref::<T, F>(v, PhantomData)))
+    }
+
+    #[cfg(feature = "de")]
+    fn deserialize<'de, D>(d: D) -> Result<Vec<T>, D::Error>
+        where D: Deserializer<'de>
+    {
+        <Vec<De<T, F>> as Deserialize>::deserialize(d).map(De::unwrap_vec)
+    }
+}
+
+impl<K, T, H, F> Format<HashMap<K, T, H>> for F
+    where K: MapKey + Eq + Hash,
+          H: BuildHasher + Default,
+          F: Format<T>
+{
+    #[cfg(feature = "ser")]
+    fn serialize<S>(value: &HashMap<K, T, H>, s: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        s.collect_map(value.iter().map(|(k, v)| (k, Ref::<T, F>(v, PhantomData))))
+    }
+
+    #[cfg(feature = "de")]
+    fn deserialize<'de, D>(d: D) -> Result<HashMap<K, T, H>, D::Error>
+        where D: Deserializer<'de>
+    {
+        <HashMap<K, De<T, F>, H> as Deserialize>::deserialize(d)
+            .map(|m| m.into_iter().map(|(k, v)| (k, v.into_inner())).collect())
+    }
+}
+
+impl<K, T, F> Format<BTreeMap<K, T>> for F
+    where K: MapKey + Ord,
+          F: Format<T>
+{
+    #[cfg(feature = "ser")]
+    fn serialize<S>(value: &BTreeMap<K, T>, s: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        s.collect_map(value.iter().map(|(k, v)| (k, Ref::<T, F>(v, PhantomData))))
+    }
+
+    #[cfg(feature = "de")]
+    fn deserialize<'de, D>(d: D) -> Result<BTreeMap<K, T>, D::Error>
+        where D: Deserializer<'de>
+    {
+        <BTreeMap<K, De<T, F>> as Deserialize>::deserialize(d)
+            .map(|m| m.into_iter().map(|(k, v)| (k, v.into_inner())).collect())
+    }
+}
+
+#[cfg(feature = "ser")]
+struct Ref<'a, T: 'a, F>(&'a T, PhantomData<F>);
+
+#[cfg(feature = "ser")]
+impl<'a, T, F> Serialize for Ref<'a, T, F>
+    where F: Format<T>
+{
+    fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        F::serialize(self.0, s)
+    }
+}
+
+#[cfg(all(test, feature = "ser", feature = "de"))]
+mod test {
+    use serde_json;
+    use std::time::Duration;
+
+    use De;
+    use super::Iso8601;
+    #[cfg(feature = "systemtime")]
+    use super::{FixedOffset, Rfc3339};
+
+    #[test]
+    fn iso8601() {
+        let d = serde_json::from_str::<De<Duration, Iso8601>>(r#""PT1H30M""#).unwrap();
+        assert_eq!(d.into_inner(), Duration::from_secs(5400));
+        assert_eq!(serde_json::to_string(&De::<_, Iso8601>::new(Duration::from_secs(90))).unwrap(),
+                   r#""PT1M30S""#);
+
+        let d = serde_json::from_str::<De<Option<Duration>, Iso8601>>("null").unwrap();
+        assert_eq!(d.into_inner(), None);
+        let d = serde_json::from_str::<De<Option<Duration>, Iso8601>>(r#""P1D""#).unwrap();
+        assert_eq!(serde_json::to_string(&d).unwrap(), r#""P1D""#);
+    }
+
+    #[test]
+    #[cfg(feature = "systemtime")]
+    fn rfc3339() {
+        use std::time::{SystemTime, UNIX_EPOCH};
+
+        let json = r#""1970-01-01T00:00:15+00:00""#;
+        let t = serde_json::from_str::<De<SystemTime, Rfc3339>>(json).unwrap();
+        assert_eq!(t.into_inner(), UNIX_EPOCH + Duration::from_secs(15));
+
+        let t = De::<_, Rfc3339>::new(UNIX_EPOCH + Duration::from_secs(15));
+        assert_eq!(serde_json::to_string(&t).unwrap(), r#""1970-01-01T00:00:15Z""#);
+
+        let t = De::<_, Rfc3339>::new(UNIX_EPOCH + Duration::from_secs(253402300800));
+        assert!(serde_json::to_string(&t).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "systemtime")]
+    fn fixed_offset() {
+        use std::time::{SystemTime, UNIX_EPOCH};
+
+        let time = UNIX_EPOCH + Duration::new(1518563312, 5);
+        let t = De::<_, FixedOffset<32400>>::new(time);
+        let json = serde_json::to_string(&t).unwrap();
+        assert_eq!(json, r#""2018-02-14T08:08:32.000000005+09:00""#);
+        let t = serde_json::from_str::<De<SystemTime, FixedOffset<32400>>>(&json).unwrap();
+        assert_eq!(t.into_inner(), time);
+
+        for json in &[r#""2018-02-13T23:08:32Z""#, r#""2018-02-13T15:08:32-08:00""#] {
+            let t = serde_json::from_str::<De<SystemTime, FixedOffset<0>>>(json).unwrap();
+            assert_eq!(t.into_inner(), UNIX_EPOCH + Duration::from_secs(1518563312));
+        }
+        let t = De::<_, FixedOffset<-28800>>::new(UNIX_EPOCH + Duration::from_secs(1518563312));
+        assert_eq!(serde_json::to_string(&t).unwrap(), r#""2018-02-13T15:08:32-08:00""#);
+
+        // the offset moves the epoch to 1969
+        let t = De::<_, FixedOffset<-3600>>::new(UNIX_EPOCH);
+        assert_eq!(serde_json::to_string(&t).unwrap(), r#""1969-12-31T23:00:00-01:00""#);
+        assert!(serde_json::to_string(&De::<_, FixedOffset<30>>::new(time)).is_err());
+    }
+}
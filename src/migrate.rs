@@ -0,0 +1,141 @@
+//! Migration of existing JSON documents to this crate's duration format.
+//!
+//! Configuration written before adopting this crate often stores durations
+//! as plain numbers of seconds or milliseconds. `humanize_value` rewrites
+//! such fields in a `serde_json::Value` into formatted durations in place, so
+//! documents can be migrated in bulk before switching their fields over to
+//! this crate.
+//!
+//! # Examples
+//!
+//! ```
+//! extern crate serde_humantime;
+//! #[macro_use]
+//! extern crate serde_json;
+//!
+//! use serde_humantime::migrate::{humanize_value, Scale};
+//!
+//! # fn main() {
+//! let mut config = json!({
+//!     "timeout": 90,
+//!     "retry": { "backoff": 1500 },
+//! });
+//! humanize_value(&mut config, &["/timeout"], Scale::Seconds).unwrap();
+//! humanize_value(&mut config, &["/retry/backoff"], Scale::Milliseconds).unwrap();
+//! assert_eq!(config, json!({
+//!     "timeout": "1m 30s",
+//!     "retry": { "backoff": "1s 500ms" },
+//! }));
+//! # }
+//! ```
+use serde_json::Value;
+use std::error;
+use std::fmt;
+use std::time::Duration;
+
+use format;
+
+/// The unit of the numbers being migrated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Scale {
+    /// Numbers of seconds.
+    Seconds,
+    /// Numbers of milliseconds.
+    Milliseconds,
+}
+
+/// An error migrating a field which isn't a valid duration.
+#[derive(Debug)]
+pub struct MigrateError {
+    pointer: String,
+}
+
+impl MigrateError {
+    /// Returns the JSON pointer to the field which couldn't be migrated.
+    pub fn pointer(&self) -> &str {
+        &self.pointer
+    }
+}
+
+impl fmt::Display for MigrateError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "value at `{}` is not a duration", self.pointer)
+    }
+}
+
+impl error::Error for MigrateError {}
+
+/// Rewrites numeric durations at the given JSON pointers into formatted
+/// durations.
+///
+/// Numbers may be fractional, but not negative. Pointers to fields which
+/// don't exist or already hold strings are skipped, so a document can be
+/// migrated more than once. Any other value is an error, in which case
+/// fields preceding it will already have been rewritten.
+pub fn humanize_value(value: &mut Value, pointers: &[&str], scale: Scale) -> Result<(), MigrateError> {
+    for &pointer in pointers {
+        let field = match value.pointer_mut(pointer) {
+            Some(field) => field,
+            None => continue,
+        };
+        let d = match *field {
+            Value::String(_) => continue,
+            Value::Number(ref n) => to_duration(n, scale),
+            _ => None,
+        };
+        let d = d.ok_or_else(|| MigrateError { pointer: pointer.to_string() })?;
+        *field = Value::String(format::format_duration(d).to_string());
+    }
+    Ok(())
+}
+
+fn to_duration(n: &serde_json::Number, scale: Scale) -> Option<Duration> {
+    if let Some(n) = n.as_u64() {
+        return Some(match scale {
+            Scale::Seconds => Duration::from_secs(n),
+            Scale::Milliseconds => Duration::from_millis(n),
+        });
+    }
+    let n = n.as_f64()?;
+    let secs = match scale {
+        Scale::Seconds => n,
+        Scale::Milliseconds => n / 1000.,
+    };
+    Duration::try_from_secs_f64(secs).ok()
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn humanize() {
+        let mut value = json!({
+            "a": 3600,
+            "b": 1.5,
+            "c": "2h",
+            "nested": { "d": [0, 250] },
+        });
+        humanize_value(&mut value, &["/a", "/b", "/c", "/missing"], Scale::Seconds).unwrap();
+        humanize_value(&mut value, &["/nested/d/1"], Scale::Milliseconds).unwrap();
+        assert_eq!(value,
+                   json!({
+                       "a": "1h",
+                       "b": "1s 500ms",
+                       "c": "2h",
+                       "nested": { "d": [0, "250ms"] },
+                   }));
+    }
+
+    #[test]
+    fn invalid() {
+        let mut value = json!({ "a": -1, "b": true, "c": 1 });
+        for &pointer in &["/a", "/b"] {
+            let err = humanize_value(&mut value, &["/c", pointer], Scale::Seconds).unwrap_err();
+            assert_eq!(err.pointer(), pointer);
+        }
+        assert_eq!(value, json!({ "a": -1, "b": true, "c": "1s" }));
+    }
+}
@@ -0,0 +1,141 @@
+//! Allocation-free serialization of `Duration`s.
+//!
+//! The `serialize` function in this module writes durations in the stable
+//! format described in the `format` module, but formats into a fixed-size
+//! buffer on the stack rather than relying on `Serializer::collect_str`,
+//! whose default implementation allocates a `String`. It can be used with
+//! `serialize_with` to keep allocations out of hot serialization paths. The
+//! crate itself still requires `std`, so this doesn't make it usable on
+//! targets without one.
+//!
+//! Output is unaffected by `set_global_format`, whose styles may not fit in
+//! the buffer, so it only matches that of the crate root while the global
+//! format is left at its default.
+//!
+//! With the `heapless` feature, `format_heapless` formats a duration into a
+//! `heapless::String`.
+//!
+//! # Examples
+//!
+//! ```
+//! extern crate serde_humantime;
+//! extern crate serde;
+//! #[macro_use]
+//! extern crate serde_derive;
+//!
+//! use std::time::Duration;
+//!
+//! #[derive(Serialize)]
+//! struct Report {
+//!     #[serde(serialize_with = "serde_humantime::noalloc::serialize")]
+//!     uptime: Duration,
+//! }
+//!
+//! # fn main() {}
+//! ```
+use serde::ser::{Error, Serializer};
+use std::fmt::{self, Write};
+use std::str;
+use std::time::Duration;
+
+#[cfg(feature = "heapless")]
+use heapless;
+
+use format::format_duration;
+
+/// The longest formatted durations are like
+/// `584542046089years 11months 29days 23h 59m 59s 999ms 999us 999ns`.
+const MAX_LEN: usize = 64;
+
+pub(crate) struct Buffer {
+    bytes: [u8; MAX_LEN],
+    len: usize,
+}
+
+impl Buffer {
+    pub(crate) fn new() -> Buffer {
+        Buffer {
+            bytes: [0; MAX_LEN],
+            len: 0,
+        }
+    }
+
+    pub(crate) fn as_str(&self) -> &str {
+        // only whole strs are copied into the buffer
+        str::from_utf8(&self.bytes[..self.len]).unwrap()
+    }
+}
+
+impl Write for Buffer {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let end = self.len + s.len();
+        if end > self.bytes.len() {
+            return Err(fmt::Error);
+        }
+        self.bytes[self.len..end].copy_from_slice(s.as_bytes());
+        self.len = end;
+        Ok(())
+    }
+}
+
+/// Serializes a `Duration` without allocating.
+pub fn serialize<S>(d: &Duration, s: S) -> Result<S::Ok, S::Error>
+    where S: Serializer
+{
+    let mut buf = Buffer::new();
+    write!(buf, "{}", format_duration(*d)).map_err(|_| S::Error::custom("duration too long"))?;
+    s.serialize_str(buf.as_str())
+}
+
+/// Formats a `Duration` into a `heapless::String`.
+///
+/// Returns an error if the formatted duration doesn't fit in `N` bytes. The
+/// longest possible duration requires 63 bytes.
+///
+/// # Examples
+///
+/// ```
+/// use serde_humantime::noalloc::format_heapless;
+/// use std::time::Duration;
+///
+/// let s = format_heapless::<16>(Duration::from_secs(5400)).unwrap();
+/// assert_eq!(s, "1h 30m");
+/// ```
+#[cfg(feature = "heapless")]
+pub fn format_heapless<const N: usize>(d: Duration) -> Result<heapless::String<N>, fmt::Error> {
+    let mut s = heapless::String::new();
+    write!(s, "{}", format_duration(d))?;
+    Ok(s)
+}
+
+#[cfg(all(test, feature = "de"))]
+mod test {
+    use serde_json;
+    use std::time::Duration;
+
+    fn longest() -> Duration {
+        Duration::new(584542046089 * 31557600 + 11 * 2630016 + 29 * 86400 + 86399, 999_999_999)
+    }
+
+    #[test]
+    fn serialize() {
+        for &d in &[Duration::from_secs(0),
+                    Duration::new(5400, 5),
+                    Duration::new(u64::MAX, 999_999_999),
+                    longest()] {
+            let mut out = vec![];
+            super::serialize(&d, &mut serde_json::Serializer::new(&mut out)).unwrap();
+            assert_eq!(out, serde_json::to_vec(&::De::<_>::new(d)).unwrap());
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "heapless")]
+    fn heapless() {
+        let d = longest();
+        assert_eq!(::format::format_duration(d).to_string().len(), 63);
+        assert!(super::format_heapless::<62>(d).is_err());
+        assert_eq!(super::format_heapless::<63>(d).unwrap(),
+                   ::format::format_duration(d).to_string().as_str());
+    }
+}
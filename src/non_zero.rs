@@ -0,0 +1,151 @@
+//! Durations which are never zero.
+#[cfg(feature = "de")]
+use serde::de::{Deserialize, Deserializer, Visitor, Error, Unexpected};
+#[cfg(feature = "ser")]
+use serde::ser::{Serialize, Serializer};
+#[cfg(feature = "de")]
+use std::error;
+#[cfg(any(feature = "ser", feature = "de"))]
+use std::fmt;
+#[cfg(feature = "de")]
+use std::str::FromStr;
+use std::time::Duration;
+
+#[cfg(feature = "ser")]
+use format;
+#[cfg(feature = "de")]
+use parse;
+
+/// A duration which is known not to be zero.
+///
+/// Fields like poll intervals and timeouts are often meaningless when zero.
+/// Using this type for them rejects zero durations when deserializing, so
+/// the rest of a program doesn't need to check for them.
+///
+/// It is serialized and deserialized as a duration string like the crate
+/// root.
+///
+/// # Examples
+///
+/// ```
+/// use serde_humantime::non_zero::NonZeroDuration;
+/// use std::time::Duration;
+///
+/// let interval = "30s".parse::<NonZeroDuration>().unwrap();
+/// assert_eq!(interval.get(), Duration::from_secs(30));
+/// assert!("0s".parse::<NonZeroDuration>().is_err());
+/// assert!(NonZeroDuration::new(Duration::from_secs(0)).is_none());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct NonZeroDuration(Duration);
+
+impl NonZeroDuration {
+    /// Creates a `NonZeroDuration`, returning `None` if the duration is zero.
+    pub fn new(d: Duration) -> Option<NonZeroDuration> {
+        if d == Duration::from_secs(0) {
+            return None;
+        }
+        Some(NonZeroDuration(d))
+    }
+
+    /// Returns the duration.
+    pub fn get(&self) -> Duration {
+        self.0
+    }
+}
+
+impl From<NonZeroDuration> for Duration {
+    fn from(d: NonZeroDuration) -> Duration {
+        d.0
+    }
+}
+
+#[cfg(feature = "ser")]
+impl fmt::Display for NonZeroDuration {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&format::format_duration(self.0), fmt)
+    }
+}
+
+/// An error parsing a `NonZeroDuration`.
+#[derive(Debug)]
+#[cfg(feature = "de")]
+pub struct ParseNonZeroDurationError(());
+
+#[cfg(feature = "de")]
+impl fmt::Display for ParseNonZeroDurationError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str("invalid nonzero duration")
+    }
+}
+
+#[cfg(feature = "de")]
+impl error::Error for ParseNonZeroDurationError {}
+
+#[cfg(feature = "de")]
+impl FromStr for NonZeroDuration {
+    type Err = ParseNonZeroDurationError;
+
+    fn from_str(s: &str) -> Result<NonZeroDuration, ParseNonZeroDurationError> {
+        parse::parse_duration(s)
+            .ok()
+            .and_then(NonZeroDuration::new)
+            .ok_or(ParseNonZeroDurationError(()))
+    }
+}
+
+#[cfg(feature = "ser")]
+impl Serialize for NonZeroDuration {
+    fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        s.collect_str(self)
+    }
+}
+
+#[cfg(feature = "de")]
+impl<'de> Deserialize<'de> for NonZeroDuration {
+    fn deserialize<D>(d: D) -> Result<NonZeroDuration, D::Error>
+        where D: Deserializer<'de>
+    {
+        struct V;
+
+        impl<'de2> Visitor<'de2> for V {
+            type Value = NonZeroDuration;
+
+            fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+                fmt.write_str("a nonzero duration")
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<NonZeroDuration, E>
+                where E: Error
+            {
+                v.parse().map_err(|_| E::invalid_value(Unexpected::Str(v), &self))
+            }
+        }
+
+        d.deserialize_str(V)
+    }
+}
+
+#[cfg(all(test, feature = "ser", feature = "de"))]
+mod test {
+    use serde_json;
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let d = serde_json::from_str::<NonZeroDuration>(r#""1m 30s""#).unwrap();
+        assert_eq!(d.get(), Duration::from_secs(90));
+        assert_eq!(serde_json::to_string(&d).unwrap(), r#""1m 30s""#);
+        assert_eq!(NonZeroDuration::new(Duration::new(0, 1)).map(Duration::from), Some(Duration::new(0, 1)));
+
+        for json in &[r#""0s""#, r#""0h 0m""#, r#""""#, "30"] {
+            assert!(serde_json::from_str::<NonZeroDuration>(json).is_err(), "{}", json);
+        }
+        let err = serde_json::from_str::<NonZeroDuration>(r#""0s""#).unwrap_err();
+        assert!(err.to_string().contains("expected a nonzero duration"), "{}", err);
+    }
+}
@@ -0,0 +1,81 @@
+//! Normalization of duration and timestamp strings.
+//!
+//! Configuration formatters and pre-commit hooks can use these functions to
+//! rewrite time fields into the form this crate serializes them in, so that
+//! equivalent values are always written the same way. Durations follow the
+//! options set by `format::set_global_format`, like the crate root's
+//! serializer.
+//!
+//! # Examples
+//!
+//! ```
+//! use serde_humantime::normalize::{normalize_duration_str, normalize_timestamp_str};
+//!
+//! assert_eq!(normalize_duration_str("90 minutes").unwrap(), "1h 30m");
+//! assert_eq!(normalize_timestamp_str("2018-02-14 00:28:07").unwrap(), "2018-02-14T00:28:07Z");
+//! assert!(normalize_duration_str("soon").is_err());
+//! ```
+#[cfg(feature = "systemtime")]
+use std::time::{Duration, UNIX_EPOCH};
+
+use format;
+use parse;
+#[cfg(feature = "systemtime")]
+use rfc3339;
+use Error;
+#[cfg(feature = "systemtime")]
+use ErrorKind;
+
+// 10000-01-01T00:00:00Z
+#[cfg(feature = "systemtime")]
+const MAX_SECS: u64 = 253402300800;
+
+/// Parses a duration and returns it in the form the crate root serializes
+/// it in.
+pub fn normalize_duration_str(s: &str) -> Result<String, Error> {
+    let d = parse::parse_duration(s)?;
+    Ok(format::global_format().format_duration(d).to_string())
+}
+
+/// Parses a timestamp and returns it in the form the crate root serializes
+/// it in.
+///
+/// Timestamps which can't be serialized, those before 1970 or after year
+/// 9999, return an error of kind `ErrorKind::OutOfRange`.
+#[cfg(feature = "systemtime")]
+pub fn normalize_timestamp_str(s: &str) -> Result<String, Error> {
+    let t = rfc3339::parse(s)?;
+    if t >= UNIX_EPOCH + Duration::from_secs(MAX_SECS) {
+        return Err(ErrorKind::OutOfRange.into());
+    }
+    Ok(format::format_rfc3339(t).to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ErrorKind;
+
+    #[test]
+    fn duration() {
+        for &(s, expected) in &[("1h 30m", "1h 30m"),
+                                ("90min", "1h 30m"),
+                                ("1 day 0 hours", "1day"),
+                                ("0s", "0s"),
+                                ("1500ms", "1s 500ms")] {
+            assert_eq!(normalize_duration_str(s).unwrap(), expected, "{}", s);
+        }
+        assert_eq!(normalize_duration_str("").unwrap_err().kind(), ErrorKind::Empty);
+    }
+
+    #[test]
+    #[cfg(feature = "systemtime")]
+    fn timestamp() {
+        for &(s, expected) in &[("2018-02-13T23:08:32Z", "2018-02-13T23:08:32Z"),
+                                ("2018-02-13T23:08:32.500000000+00:00", "2018-02-13T23:08:32.500000000Z"),
+                                ("20180213T230832Z", "2018-02-13T23:08:32Z")] {
+            assert_eq!(normalize_timestamp_str(s).unwrap(), expected, "{}", s);
+        }
+        assert!(normalize_timestamp_str("yesterday").is_err());
+    }
+}
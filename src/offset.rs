@@ -0,0 +1,179 @@
+//! UTC offsets.
+#[cfg(feature = "de")]
+use serde::de::{Deserialize, Deserializer, Visitor, Error, Unexpected};
+#[cfg(feature = "ser")]
+use serde::ser::{Serialize, Serializer};
+use std::error;
+use std::fmt;
+use std::str::FromStr;
+
+/// An offset from UTC, such as that of a time zone.
+///
+/// It is parsed from `Z` or a sign followed by hours and optional minutes,
+/// like `+05:30`, `-0800` or `+01`, and formatted like `+05:30`. It is
+/// serialized and deserialized as such strings.
+///
+/// # Examples
+///
+/// ```
+/// use serde_humantime::offset::UtcOffset;
+///
+/// let offset = "+05:30".parse::<UtcOffset>().unwrap();
+/// assert_eq!(offset.seconds(), 19800);
+/// assert_eq!("-0800".parse::<UtcOffset>().unwrap().to_string(), "-08:00");
+/// assert_eq!("Z".parse::<UtcOffset>().unwrap(), UtcOffset::UTC);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct UtcOffset {
+    seconds: i32,
+}
+
+impl UtcOffset {
+    /// The offset of UTC itself.
+    pub const UTC: UtcOffset = UtcOffset { seconds: 0 };
+
+    /// Creates a `UtcOffset` from a number of seconds east of UTC.
+    ///
+    /// Returns `None` unless the offset is a whole number of minutes less
+    /// than a day in magnitude.
+    pub fn from_seconds(seconds: i32) -> Option<UtcOffset> {
+        if seconds % 60 != 0 || seconds.unsigned_abs() >= 86400 {
+            return None;
+        }
+        Some(UtcOffset { seconds })
+    }
+
+    /// Returns the number of seconds east of UTC.
+    pub fn seconds(&self) -> i32 {
+        self.seconds
+    }
+}
+
+impl fmt::Display for UtcOffset {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        let sign = if self.seconds < 0 { '-' } else { '+' };
+        let seconds = self.seconds.unsigned_abs();
+        write!(fmt, "{}{:02}:{:02}", sign, seconds / 3600, seconds % 3600 / 60)
+    }
+}
+
+/// An error parsing a `UtcOffset`.
+#[derive(Debug)]
+pub struct ParseUtcOffsetError(());
+
+impl fmt::Display for ParseUtcOffsetError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str("invalid UTC offset")
+    }
+}
+
+impl error::Error for ParseUtcOffsetError {}
+
+impl FromStr for UtcOffset {
+    type Err = ParseUtcOffsetError;
+
+    fn from_str(s: &str) -> Result<UtcOffset, ParseUtcOffsetError> {
+        parse(s).ok_or(ParseUtcOffsetError(()))
+    }
+}
+
+fn parse(s: &str) -> Option<UtcOffset> {
+    if s == "Z" || s == "z" {
+        return Some(UtcOffset::UTC);
+    }
+
+    let b = s.as_bytes();
+    let sign = match b.first()? {
+        b'+' => 1,
+        b'-' => -1,
+        _ => return None,
+    };
+    let (hours, minutes) = match b.len() {
+        3 => (&b[1..3], &b"00"[..]),
+        5 => (&b[1..3], &b[3..5]),
+        6 if b[3] == b':' => (&b[1..3], &b[4..6]),
+        _ => return None,
+    };
+    let hours = digits(hours)?;
+    let minutes = digits(minutes)?;
+    if hours > 23 || minutes > 59 {
+        return None;
+    }
+    UtcOffset::from_seconds(sign * (hours * 3600 + minutes * 60))
+}
+
+fn digits(b: &[u8]) -> Option<i32> {
+    b.iter().try_fold(0, |n, &c| {
+        if c.is_ascii_digit() {
+            Some(n * 10 + (c - b'0') as i32)
+        } else {
+            None
+        }
+    })
+}
+
+#[cfg(feature = "ser")]
+impl Serialize for UtcOffset {
+    fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        s.collect_str(self)
+    }
+}
+
+#[cfg(feature = "de")]
+impl<'de> Deserialize<'de> for UtcOffset {
+    fn deserialize<D>(d: D) -> Result<UtcOffset, D::Error>
+        where D: Deserializer<'de>
+    {
+        struct V;
+
+        impl<'de2> Visitor<'de2> for V {
+            type Value = UtcOffset;
+
+            fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+                fmt.write_str("a UTC offset")
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<UtcOffset, E>
+                where E: Error
+            {
+                v.parse().map_err(|_| E::invalid_value(Unexpected::Str(v), &self))
+            }
+        }
+
+        d.deserialize_str(V)
+    }
+}
+
+#[cfg(all(test, feature = "ser", feature = "de"))]
+mod test {
+    use serde_json;
+
+    use super::*;
+
+    #[test]
+    fn parse() {
+        for &(s, seconds) in &[("Z", 0),
+                               ("+00:00", 0),
+                               ("+05:30", 19800),
+                               ("-0800", -28800),
+                               ("+01", 3600),
+                               ("-23:59", -86340)] {
+            assert_eq!(s.parse::<UtcOffset>().unwrap().seconds(), seconds, "{}", s);
+        }
+        for s in &["", "+", "05:30", "+5:30", "+24:00", "+05:60", "+05-30", "+0530:", "UTC"] {
+            assert!(s.parse::<UtcOffset>().is_err(), "{}", s);
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        for &(s, expected) in &[("Z", "+00:00"), ("-0800", "-08:00"), ("+05:30", "+05:30")] {
+            let offset = serde_json::from_str::<UtcOffset>(&format!("\"{}\"", s)).unwrap();
+            assert_eq!(serde_json::to_string(&offset).unwrap(), format!("\"{}\"", expected));
+        }
+        assert!(serde_json::from_str::<UtcOffset>("\"+25:00\"").is_err());
+        assert_eq!(UtcOffset::from_seconds(30), None);
+    }
+}
@@ -0,0 +1,102 @@
+//! Parallel parsing of large batches of durations and timestamps.
+//!
+//! Parsing is spread over `rayon`'s global thread pool, and otherwise
+//! behaves like the `batch` module.
+//!
+//! # Examples
+//!
+//! ```
+//! use serde_humantime::par::parse_durations_par;
+//! use std::time::Duration;
+//!
+//! let durations = parse_durations_par(&["1h", "30m"]).unwrap();
+//! assert_eq!(durations, [Duration::from_secs(3600), Duration::from_secs(1800)]);
+//!
+//! let err = parse_durations_par(&["1h", "bogus", "30m", "1 fortnight"]).unwrap_err();
+//! let indices = err.errors().iter().map(|&(i, _)| i).collect::<Vec<_>>();
+//! assert_eq!(indices, [1, 3]);
+//! ```
+use rayon::prelude::*;
+use std::time::Duration;
+#[cfg(feature = "systemtime")]
+use std::time::SystemTime;
+
+pub use batch::BatchError;
+use parse;
+use Error;
+
+/// Parses a batch of durations in parallel.
+///
+/// Returns the durations in the order of their inputs, or the errors of
+/// every input which failed to parse.
+pub fn parse_durations_par<S>(s: &[S]) -> Result<Vec<Duration>, BatchError>
+    where S: AsRef<str> + Sync
+{
+    parse_par(s, parse::parse_duration)
+}
+
+/// Parses a batch of RFC 3339-like timestamps in parallel.
+///
+/// Returns the timestamps in the order of their inputs, or the errors of
+/// every input which failed to parse.
+#[cfg(feature = "systemtime")]
+pub fn parse_timestamps_par<S>(s: &[S]) -> Result<Vec<SystemTime>, BatchError>
+    where S: AsRef<str> + Sync
+{
+    parse_par(s, parse::parse_rfc3339_weak)
+}
+
+fn parse_par<S, T, P>(s: &[S], parse: P) -> Result<Vec<T>, BatchError>
+    where S: AsRef<str> + Sync,
+          T: Send,
+          P: Fn(&str) -> Result<T, Error> + Sync
+{
+    let results = s.par_iter().map(|s| parse(s.as_ref())).collect::<Vec<_>>();
+
+    let errors = results.iter()
+        .enumerate()
+        .filter_map(|(i, r)| r.as_ref().err().map(|&e| (i, e)))
+        .collect::<Vec<_>>();
+    if !errors.is_empty() {
+        return Err(BatchError::new(errors));
+    }
+
+    Ok(results.into_iter().filter_map(Result::ok).collect())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn durations() {
+        let inputs = (0..10000).map(|i| format!("{}s", i)).collect::<Vec<_>>();
+        let durations = parse_durations_par(&inputs).unwrap();
+        assert_eq!(durations.len(), 10000);
+        assert!(durations.iter().enumerate().all(|(i, d)| *d == Duration::from_secs(i as u64)));
+
+        let mut inputs = inputs;
+        inputs[17] = "bogus".to_string();
+        inputs[9000] = String::new();
+        let err = parse_durations_par(&inputs).unwrap_err();
+        assert_eq!(err.errors().len(), 2);
+        assert_eq!(err.errors()[0].0, 17);
+        assert_eq!(err.errors()[1].0, 9000);
+        assert!(err.to_string().starts_with("2 values failed to parse, the first at index 17: "));
+
+        assert!(parse_durations_par::<&str>(&[]).unwrap().is_empty());
+    }
+
+    #[cfg(feature = "systemtime")]
+    #[test]
+    fn timestamps() {
+        use std::time::UNIX_EPOCH;
+
+        let stamps = parse_timestamps_par(&["1970-01-01T00:00:01Z", "2018-02-14 00:28:07"]).unwrap();
+        assert_eq!(stamps,
+                   [UNIX_EPOCH + Duration::from_secs(1), UNIX_EPOCH + Duration::from_secs(1518568087)]);
+
+        let err = parse_timestamps_par(&["1970-01-01T00:00:01Z", "yesterday"]).unwrap_err();
+        assert_eq!(err.errors()[0].0, 1);
+    }
+}
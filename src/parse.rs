@@ -0,0 +1,1150 @@
+//! Parsing of `Duration`s and `SystemTime`s.
+//!
+//! With the default `humantime` feature, parsing is delegated to the
+//! `humantime` crate. Without it, an internal parser accepting the same
+//! grammar is used instead.
+//!
+//! Durations are additionally parsed in every style produced by
+//! `format::FormatOptions` with whitespace unit separators, falling back to
+//! the internal parser for those `humantime` rejects, so that any formatted
+//! duration parses back to exactly the same value.
+use std::time::Duration;
+#[cfg(feature = "systemtime")]
+use std::time::SystemTime;
+
+#[cfg(feature = "humantime")]
+use humantime;
+
+#[cfg(feature = "systemtime")]
+use civil;
+#[cfg(feature = "systemtime")]
+use date;
+#[cfg(feature = "systemtime")]
+use clock::{Clock, SystemClock};
+use error::{Error, ErrorKind};
+#[cfg(feature = "systemtime")]
+use rfc3339;
+
+/// Parses a duration such as `1h 30m`.
+///
+/// # Examples
+///
+/// ```
+/// use serde_humantime::{parse_duration, ErrorKind};
+/// use std::time::Duration;
+///
+/// assert_eq!(parse_duration("1h 30m").unwrap(), Duration::from_secs(5400));
+/// assert_eq!(parse_duration("1 fortnight").unwrap_err().kind(), ErrorKind::UnknownUnit);
+/// ```
+#[cfg(feature = "humantime")]
+pub fn parse_duration(s: &str) -> Result<Duration, Error> {
+    humantime::parse_duration(s)
+        .or_else(|e| internal::parse_duration(s).map_err(|_| Error::from_duration(e)))
+}
+
+/// Parses a duration such as `1h 30m`.
+///
+/// # Examples
+///
+/// ```
+/// use serde_humantime::{parse_duration, ErrorKind};
+/// use std::time::Duration;
+///
+/// assert_eq!(parse_duration("1h 30m").unwrap(), Duration::from_secs(5400));
+/// assert_eq!(parse_duration("1 fortnight").unwrap_err().kind(), ErrorKind::UnknownUnit);
+/// ```
+#[cfg(not(feature = "humantime"))]
+pub fn parse_duration(s: &str) -> Result<Duration, Error> {
+    internal::parse_duration(s).map_err(Error::new)
+}
+
+/// Parses an RFC 3339-like timestamp such as `2018-02-14 00:28:07`.
+#[cfg(all(feature = "humantime", feature = "systemtime"))]
+pub fn parse_rfc3339_weak(s: &str) -> Result<SystemTime, Error> {
+    humantime::parse_rfc3339_weak(s).map_err(Error::from_timestamp)
+}
+
+/// Parses an RFC 3339-like timestamp such as `2018-02-14 00:28:07`.
+#[cfg(all(not(feature = "humantime"), feature = "systemtime"))]
+pub fn parse_rfc3339_weak(s: &str) -> Result<SystemTime, Error> {
+    internal::parse_rfc3339_weak(s).map_err(Error::new)
+}
+
+/// Options controlling how durations and timestamps are parsed.
+///
+/// Limits on the length and number of components of the input bound the
+/// work done parsing untrusted input. Inputs exceeding them are rejected
+/// before being parsed, with errors of kind `ErrorKind::TooLong` and
+/// `ErrorKind::TooManyComponents` respectively.
+///
+/// The `fortnight` and `quarter` units can be enabled, for configuration
+/// written in business terms. They're fixed lengths of time, like the
+/// `month` and `year` units: a fortnight is 14 days, and a quarter is three
+/// months of 30.44 days.
+///
+/// Numbers in decimal and scientific notation, like `1.5h` and `2.5e-3 s`,
+/// can also be enabled, for configuration generated by scientific tooling.
+/// They must be a whole number of nanoseconds.
+///
+/// A default unit can be set for bare numbers like `30`, for legacy
+/// configuration which omitted units. Inputs with units are unaffected.
+///
+/// Timestamps can be restricted to a window, which catches mistakes like
+/// milliseconds passed as seconds at the serialization boundary. Timestamps
+/// outside of it are rejected with errors of kind `ErrorKind::OutOfBounds`.
+///
+/// Options can't be passed through serde's `with` annotations, but a
+/// `DurationFormat` or `TimestampFormat` marker type can apply them with
+/// `De`.
+///
+/// # Examples
+///
+/// ```
+/// extern crate serde_humantime;
+/// extern crate serde_json;
+///
+/// use serde_humantime::{De, Error, ErrorKind, ParseOptions};
+/// use serde_humantime::marker::DurationFormat;
+/// use std::fmt;
+/// use std::time::{Duration, UNIX_EPOCH};
+///
+/// struct Limited;
+///
+/// impl DurationFormat for Limited {
+///     fn parse(s: &str) -> Result<Duration, Error> {
+///         ParseOptions::new().max_len(64).max_components(4).parse_duration(s)
+///     }
+///
+///     fn format(d: &Duration, fmt: &mut fmt::Formatter) -> fmt::Result {
+///         serde_humantime::marker::Human::format(d, fmt)
+///     }
+/// }
+///
+/// # fn main() {
+/// let options = ParseOptions::new().max_components(2);
+/// assert_eq!(options.parse_duration("1h 30m").unwrap(), Duration::from_secs(5400));
+/// assert_eq!(options.parse_duration("1h 30m 15s").unwrap_err().kind(),
+///            ErrorKind::TooManyComponents);
+///
+/// assert!(serde_json::from_str::<De<Duration, Limited>>(r#""1h 30m""#).is_ok());
+///
+/// let options = ParseOptions::new().default_unit(Duration::from_millis(1));
+/// assert_eq!(options.parse_duration("1500").unwrap(), Duration::from_millis(1500));
+/// assert_eq!(options.parse_duration("2s").unwrap(), Duration::from_secs(2));
+///
+/// // 2000-01-01 through ten years from now
+/// let options = ParseOptions::new()
+///     .not_before(UNIX_EPOCH + Duration::from_secs(946684800))
+///     .max_ahead(Duration::from_secs(10 * 31557600));
+/// assert!(options.parse_timestamp("2018-02-13T23:08:32Z").is_ok());
+/// assert_eq!(options.parse_timestamp("1970-01-18T13:49:23Z").unwrap_err().kind(),
+///            ErrorKind::OutOfBounds);
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ParseOptions {
+    max_len: Option<usize>,
+    max_components: Option<usize>,
+    fortnights: bool,
+    quarters: bool,
+    scientific: bool,
+    default_unit: Option<Duration>,
+    #[cfg(feature = "systemtime")]
+    not_before: Option<SystemTime>,
+    #[cfg(feature = "systemtime")]
+    not_after: Option<SystemTime>,
+    #[cfg(feature = "systemtime")]
+    max_ahead: Option<Duration>,
+    #[cfg(feature = "systemtime")]
+    max_behind: Option<Duration>,
+    #[cfg(feature = "systemtime")]
+    weekdays: bool,
+    #[cfg(feature = "systemtime")]
+    day_parts: bool,
+}
+
+impl ParseOptions {
+    /// Returns options with no limits.
+    pub fn new() -> ParseOptions {
+        ParseOptions {
+            max_len: None,
+            max_components: None,
+            fortnights: false,
+            quarters: false,
+            scientific: false,
+            default_unit: None,
+            #[cfg(feature = "systemtime")]
+            not_before: None,
+            #[cfg(feature = "systemtime")]
+            not_after: None,
+            #[cfg(feature = "systemtime")]
+            max_ahead: None,
+            #[cfg(feature = "systemtime")]
+            max_behind: None,
+            #[cfg(feature = "systemtime")]
+            weekdays: false,
+            #[cfg(feature = "systemtime")]
+            day_parts: false,
+        }
+    }
+
+    /// Sets the maximum length of the input in bytes.
+    pub fn max_len(mut self, max_len: usize) -> ParseOptions {
+        self.max_len = Some(max_len);
+        self
+    }
+
+    /// Sets the maximum number of components, like `30m`, in the input.
+    pub fn max_components(mut self, max_components: usize) -> ParseOptions {
+        self.max_components = Some(max_components);
+        self
+    }
+
+    /// Determines if the `fortnight` unit is accepted.
+    ///
+    /// Defaults to `false`.
+    pub fn fortnights(mut self, fortnights: bool) -> ParseOptions {
+        self.fortnights = fortnights;
+        self
+    }
+
+    /// Determines if the `quarter` unit is accepted.
+    ///
+    /// Defaults to `false`.
+    pub fn quarters(mut self, quarters: bool) -> ParseOptions {
+        self.quarters = quarters;
+        self
+    }
+
+    /// Determines if numbers in decimal and scientific notation, like `1.5h`
+    /// and `1e3 ms`, are accepted.
+    ///
+    /// Defaults to `false`.
+    pub fn scientific(mut self, scientific: bool) -> ParseOptions {
+        self.scientific = scientific;
+        self
+    }
+
+    /// Sets the unit of numbers without one, like `30`.
+    ///
+    /// By default such numbers are rejected.
+    pub fn default_unit(mut self, unit: Duration) -> ParseOptions {
+        self.default_unit = Some(unit);
+        self
+    }
+
+    /// Sets the earliest permitted timestamp.
+    #[cfg(feature = "systemtime")]
+    pub fn not_before(mut self, not_before: SystemTime) -> ParseOptions {
+        self.not_before = Some(not_before);
+        self
+    }
+
+    /// Sets the latest permitted timestamp.
+    #[cfg(feature = "systemtime")]
+    pub fn not_after(mut self, not_after: SystemTime) -> ParseOptions {
+        self.not_after = Some(not_after);
+        self
+    }
+
+    /// Sets how far past the current time timestamps are permitted to be.
+    #[cfg(feature = "systemtime")]
+    pub fn max_ahead(mut self, max_ahead: Duration) -> ParseOptions {
+        self.max_ahead = Some(max_ahead);
+        self
+    }
+
+    /// Sets how far before the current time timestamps are permitted to be.
+    #[cfg(feature = "systemtime")]
+    pub fn max_behind(mut self, max_behind: Duration) -> ParseOptions {
+        self.max_behind = Some(max_behind);
+        self
+    }
+
+    /// Determines if timestamps relative to a day of the week, like
+    /// `next sunday 02:00`, are accepted.
+    ///
+    /// Such timestamps have the form `[next] <weekday> [HH:MM[:SS]]`, where
+    /// the weekday is a full or three letter English name in any case and
+    /// the time defaults to midnight. They resolve to the first such time in
+    /// UTC after the current time, so `monday 09:00` on a Monday morning is
+    /// later that day, while `monday 00:00` is a week later. `next` is
+    /// optional and doesn't change the result.
+    ///
+    /// Defaults to `false`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_humantime::ParseOptions;
+    /// use serde_humantime::clock::FixedClock;
+    /// use std::time::{Duration, UNIX_EPOCH};
+    ///
+    /// // Tuesday 2018-02-13T23:08:32Z
+    /// let clock = FixedClock::new(UNIX_EPOCH + Duration::from_secs(1518563312));
+    /// let options = ParseOptions::new().weekdays(true);
+    /// let t = options.parse_timestamp_with_clock("next sunday 02:00", &clock).unwrap();
+    /// // Sunday 2018-02-18T02:00:00Z
+    /// assert_eq!(t, UNIX_EPOCH + Duration::from_secs(1518919200));
+    /// ```
+    #[cfg(feature = "systemtime")]
+    pub fn weekdays(mut self, weekdays: bool) -> ParseOptions {
+        self.weekdays = weekdays;
+        self
+    }
+
+    /// Determines if the day part keywords `midnight`, `noon` and
+    /// `end of day` are accepted as timestamps.
+    ///
+    /// They may be preceded by a date, as in `2024-06-01 end of day`, and
+    /// otherwise refer to the current day. Both are in UTC. `midnight` is the
+    /// start of the day, and `end of day` its last nanosecond.
+    ///
+    /// Defaults to `false`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_humantime::ParseOptions;
+    /// use std::time::{Duration, UNIX_EPOCH};
+    ///
+    /// let options = ParseOptions::new().day_parts(true);
+    /// let t = options.parse_timestamp("2024-06-01 end of day").unwrap();
+    /// assert_eq!(t, UNIX_EPOCH + Duration::new(1717286399, 999_999_999));
+    /// ```
+    #[cfg(feature = "systemtime")]
+    pub fn day_parts(mut self, day_parts: bool) -> ParseOptions {
+        self.day_parts = day_parts;
+        self
+    }
+
+    /// Parses a duration with these options.
+    pub fn parse_duration(&self, s: &str) -> Result<Duration, Error> {
+        self.check_len(s)?;
+        self.check_components(s)?;
+        if let Some(unit) = self.default_unit {
+            if let Some(d) = bare(s, unit, self).map_err(Error::new)? {
+                return Ok(d);
+            }
+        }
+        if self.fortnights || self.quarters || self.scientific {
+            internal::parse_duration_with(s, self).map_err(Error::new)
+        } else {
+            parse_duration(s)
+        }
+    }
+
+    /// Parses an RFC 3339 timestamp with these options, relative to the
+    /// system clock.
+    #[cfg(feature = "systemtime")]
+    pub fn parse_timestamp(&self, s: &str) -> Result<SystemTime, Error> {
+        self.parse_timestamp_with_clock(s, &SystemClock)
+    }
+
+    /// Parses an RFC 3339 timestamp with these options, relative to the
+    /// current time of a clock.
+    #[cfg(feature = "systemtime")]
+    pub fn parse_timestamp_with_clock<C>(&self, s: &str, clock: &C) -> Result<SystemTime, Error>
+        where C: Clock + ?Sized
+    {
+        self.check_len(s)?;
+        let mut now = None;
+        let t = match rfc3339::parse(s) {
+            Ok(t) => t,
+            Err(e) => {
+                if !self.weekdays && !self.day_parts {
+                    return Err(e);
+                }
+                let clock_now = clock.now();
+                now = Some(clock_now);
+                self.parse_relative(s, clock_now).ok_or(e)?
+            }
+        };
+        let mut not_before = self.not_before;
+        let mut not_after = self.not_after;
+        if self.max_ahead.is_some() || self.max_behind.is_some() {
+            let now = now.unwrap_or_else(|| clock.now());
+            if let Some(max_behind) = self.max_behind {
+                let min = now.checked_sub(max_behind);
+                not_before = not_before.max(min);
+            }
+            if let Some(max_ahead) = self.max_ahead {
+                // a bound past the end of SystemTime's range is no bound
+                if let Some(max) = now.checked_add(max_ahead) {
+                    not_after = Some(not_after.map_or(max, |a| a.min(max)));
+                }
+            }
+        }
+        if not_before.is_some_and(|min| t < min) || not_after.is_some_and(|max| t > max) {
+            return Err(ErrorKind::OutOfBounds.into());
+        }
+        Ok(t)
+    }
+
+    #[cfg(feature = "systemtime")]
+    fn parse_relative(&self, s: &str, now: SystemTime) -> Option<SystemTime> {
+        if self.weekdays {
+            if let Some(t) = weekday(s, now) {
+                return Some(t);
+            }
+        }
+        if self.day_parts {
+            if let Some(t) = day_part(s, now) {
+                return Some(t);
+            }
+        }
+        None
+    }
+
+    fn check_len(&self, s: &str) -> Result<(), Error> {
+        if let Some(max_len) = self.max_len {
+            if s.len() > max_len {
+                return Err(ErrorKind::TooLong.into());
+            }
+        }
+        Ok(())
+    }
+
+    fn check_components(&self, s: &str) -> Result<(), Error> {
+        if let Some(max_components) = self.max_components {
+            // every component starts with a run of digits
+            let number = |b: u8| {
+                b.is_ascii_digit() || (self.scientific && b"+-.eE".contains(&b))
+            };
+            let components = s.as_bytes()
+                .windows(2)
+                .filter(|w| !number(w[0]) && w[1].is_ascii_digit())
+                .count() + s.starts_with(|c: char| c.is_ascii_digit()) as usize;
+            if components > max_components {
+                return Err(ErrorKind::TooManyComponents.into());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Resolves a weekday-relative timestamp like `next sunday 02:00` against
+/// the current time.
+#[cfg(feature = "systemtime")]
+fn weekday(s: &str, now: SystemTime) -> Option<SystemTime> {
+    const DAYS: [&str; 7] = ["monday", "tuesday", "wednesday", "thursday", "friday", "saturday", "sunday"];
+
+    let mut parts = s.split_whitespace().peekable();
+    if parts.peek().is_some_and(|p| p.eq_ignore_ascii_case("next")) {
+        parts.next();
+    }
+    let name = parts.next()?.to_ascii_lowercase();
+    let day = DAYS.iter().position(|d| *d == name || (name.len() == 3 && d.starts_with(&*name)))? as i64;
+
+    let time = match parts.next() {
+        Some(time) => {
+            let mut fields = time.split(':');
+            let mut field = |max: i64| {
+                fields.next()
+                    .filter(|f| f.len() == 2 && f.bytes().all(|b| b.is_ascii_digit()))
+                    .and_then(|f| f.parse::<i64>().ok())
+                    .filter(|&v| v <= max)
+            };
+            let hour = field(23)?;
+            let minute = field(59)?;
+            let second = if time.len() > 5 { field(59)? } else { 0 };
+            if fields.next().is_some() {
+                return None;
+            }
+            hour * 3600 + minute * 60 + second
+        }
+        None => 0,
+    };
+    if parts.next().is_some() {
+        return None;
+    }
+
+    let (secs, _) = civil::to_unix(now);
+    let today = secs.div_euclid(civil::SECS_PER_DAY);
+    // the epoch was a Thursday
+    let weekday = (today + 3).rem_euclid(7);
+    let mut t = (today + (day - weekday).rem_euclid(7)) * civil::SECS_PER_DAY + time;
+    if t <= secs {
+        t += 7 * civil::SECS_PER_DAY;
+    }
+    civil::from_unix(t, 0)
+}
+
+/// Resolves a day part keyword like `noon` or `2024-06-01 end of day`
+/// against the current time.
+#[cfg(feature = "systemtime")]
+fn day_part(s: &str, now: SystemTime) -> Option<SystemTime> {
+    const PARTS: [(&str, i64, u32); 3] = [("midnight", 0, 0),
+                                          ("noon", 43200, 0),
+                                          ("end of day", 86399, 999_999_999)];
+
+    let s = s.split_whitespace().collect::<Vec<_>>().join(" ").to_ascii_lowercase();
+    let (date, secs, nanos) = PARTS.iter()
+        .filter_map(|&(part, secs, nanos)| s.strip_suffix(part).map(|date| (date, secs, nanos)))
+        .next()?;
+
+    let day = match date {
+        "" => now,
+        date => date::parse(date.strip_suffix(' ')?)?,
+    };
+    let day = civil::to_unix(day).0.div_euclid(civil::SECS_PER_DAY);
+    civil::from_unix(day * civil::SECS_PER_DAY + secs, nanos)
+}
+
+/// The names of each unit, and its length in seconds and nanoseconds.
+const UNITS: [(&[&str], u64, u64); 10] = [
+    (&["nanoseconds", "nanosecond", "nanos", "nsec", "ns"], 0, 1),
+    (&["microseconds", "microsecond", "usec", "us"], 0, 1000),
+    (&["milliseconds", "millisecond", "millis", "msec", "ms"], 0, 1_000_000),
+    (&["seconds", "second", "secs", "sec", "s"], 1, 0),
+    (&["minutes", "minute", "min", "mins", "m"], 60, 0),
+    (&["hours", "hour", "hr", "hrs", "h"], 3600, 0),
+    (&["days", "day", "d"], 86400, 0),
+    (&["weeks", "week", "w"], 86400 * 7, 0),
+    (&["months", "month", "M"], 2630016, 0),
+    (&["years", "year", "y"], 31557600, 0),
+];
+
+pub(crate) fn unit(unit: &str, options: &ParseOptions) -> Option<(u64, u64)> {
+    if let Some(&(_, secs, nanos)) = UNITS.iter().find(|&&(names, _, _)| names.contains(&unit)) {
+        return Some((secs, nanos));
+    }
+    match unit {
+        "fortnights" | "fortnight" if options.fortnights => Some((86400 * 14, 0)),
+        "quarters" | "quarter" if options.quarters => Some((2630016 * 3, 0)),
+        _ => None,
+    }
+}
+
+/// Splits a leading component like `15 sec` off of a string, returning the
+/// number, the unit's length in seconds and nanoseconds, and the remainder.
+fn component<'a>(s: &'a str, options: &ParseOptions) -> Result<(u64, (u64, u64), &'a str), ErrorKind> {
+    let end = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    if end == 0 {
+        return match s.chars().next() {
+            Some(c) if c.is_alphabetic() => Err(ErrorKind::InvalidNumber),
+            _ => Err(ErrorKind::InvalidCharacter),
+        };
+    }
+    if options.scientific {
+        let (mantissa, exponent, end) = decimal(s)?;
+        let rest = s[end..].trim_start();
+
+        let end = rest.find(|c: char| !c.is_ascii_alphabetic()).unwrap_or(rest.len());
+        let (unit_secs, unit_nanos) = unit(&rest[..end], options).ok_or(ErrorKind::UnknownUnit)?;
+        let unit = unit_secs as u128 * 1_000_000_000 + unit_nanos as u128;
+        let nanos = scale(mantissa.checked_mul(unit).ok_or(ErrorKind::OutOfRange)?, exponent)?;
+        // whole seconds are counted in seconds to reach the full range
+        let (n, unit) = if nanos.is_multiple_of(1_000_000_000) {
+            (nanos / 1_000_000_000, (1, 0))
+        } else {
+            (nanos, (0, 1))
+        };
+        if n > u64::MAX as u128 {
+            return Err(ErrorKind::OutOfRange);
+        }
+        return Ok((n as u64, unit, &rest[end..]));
+    }
+
+    let n = s[..end].parse().map_err(|_| ErrorKind::OutOfRange)?;
+    let rest = s[end..].trim_start();
+
+    let end = rest.find(|c: char| !c.is_ascii_alphabetic()).unwrap_or(rest.len());
+    let unit = unit(&rest[..end], options).ok_or(ErrorKind::UnknownUnit)?;
+    Ok((n, unit, &rest[end..]))
+}
+
+/// Splits a leading number like `2.5e-3` off of a string, returning its
+/// digits, the power of ten they're scaled by, and the length of the number.
+fn decimal(s: &str) -> Result<(u128, i32, usize), ErrorKind> {
+    let b = s.as_bytes();
+    let mut mantissa = 0u128;
+    let mut exponent = 0i32;
+    let mut i = 0;
+    let mut fraction = false;
+    while i < b.len() {
+        if b[i].is_ascii_digit() {
+            mantissa = mantissa.checked_mul(10)
+                .and_then(|m| m.checked_add((b[i] - b'0') as u128))
+                .ok_or(ErrorKind::OutOfRange)?;
+            if fraction {
+                exponent -= 1;
+            }
+        } else if b[i] == b'.' && !fraction {
+            fraction = true;
+        } else {
+            break;
+        }
+        i += 1;
+    }
+
+    if i < b.len() && (b[i] == b'e' || b[i] == b'E') {
+        let mut end = i + 1;
+        if end < b.len() && (b[end] == b'+' || b[end] == b'-') {
+            end += 1;
+        }
+        let digits = b[end..].iter().take_while(|b| b.is_ascii_digit()).count();
+        // otherwise the `e` is left to be read as a unit
+        if digits > 0 {
+            end += digits;
+            let e = s[i + 1..end].parse::<i32>().map_err(|_| ErrorKind::OutOfRange)?;
+            exponent = exponent.checked_add(e).ok_or(ErrorKind::OutOfRange)?;
+            i = end;
+        }
+    }
+    Ok((mantissa, exponent, i))
+}
+
+/// Multiplies a number by a power of ten, failing if the result overflows or
+/// isn't an integer.
+fn scale(mut n: u128, exponent: i32) -> Result<u128, ErrorKind> {
+    if n == 0 {
+        return Ok(0);
+    }
+    for _ in 0..exponent.min(40) {
+        n = n.checked_mul(10).ok_or(ErrorKind::OutOfRange)?;
+    }
+    for _ in 0..(-exponent).min(40) {
+        if !n.is_multiple_of(10) {
+            return Err(ErrorKind::OutOfRange);
+        }
+        n /= 10;
+    }
+    Ok(n)
+}
+
+/// Parses a string consisting of just a number as a multiple of `unit`,
+/// returning `None` for anything else.
+fn bare(s: &str, unit: Duration, options: &ParseOptions) -> Result<Option<Duration>, ErrorKind> {
+    let s = s.trim();
+    if !s.starts_with(|c: char| c.is_ascii_digit()) {
+        return Ok(None);
+    }
+    let (mantissa, exponent, end) = if options.scientific {
+        decimal(s)?
+    } else {
+        let end = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+        (s[..end].parse().map_err(|_| ErrorKind::OutOfRange)?, 0, end)
+    };
+    if end != s.len() {
+        return Ok(None);
+    }
+
+    let nanos = scale(mantissa.checked_mul(unit.as_nanos()).ok_or(ErrorKind::OutOfRange)?, exponent)?;
+    let secs = nanos / 1_000_000_000;
+    if secs > u64::MAX as u128 {
+        return Err(ErrorKind::OutOfRange);
+    }
+    Ok(Some(Duration::new(secs as u64, (nanos % 1_000_000_000) as u32)))
+}
+
+/// Parses a duration such as `1h 30m` in a constant context.
+///
+/// This accepts the same grammar as `parse_duration`, and can initialize
+/// `const` and `static` items.
+///
+/// # Panics
+///
+/// Panics if the string isn't a valid duration. In a constant context, this
+/// is a compile time error.
+///
+/// # Examples
+///
+/// ```
+/// use serde_humantime::parse_duration_const;
+/// use std::time::Duration;
+///
+/// const TIMEOUT: Duration = parse_duration_const("1m 30s");
+///
+/// assert_eq!(TIMEOUT, Duration::from_secs(90));
+/// ```
+pub const fn parse_duration_const(s: &str) -> Duration {
+    let b = s.as_bytes();
+    let mut i = skip_whitespace(b, 0);
+    if i == b.len() {
+        panic!("empty duration");
+    }
+
+    let mut secs = 0u64;
+    let mut nanos = 0u64;
+    loop {
+        if !b[i].is_ascii_digit() {
+            panic!("expected a number in duration");
+        }
+        let mut n = 0u64;
+        while i < b.len() && b[i].is_ascii_digit() {
+            n = match n.checked_mul(10) {
+                Some(n) => n,
+                None => panic!("duration out of range"),
+            };
+            n = match n.checked_add((b[i] - b'0') as u64) {
+                Some(n) => n,
+                None => panic!("duration out of range"),
+            };
+            i += 1;
+        }
+
+        i = skip_whitespace(b, i);
+        let start = i;
+        while i < b.len() && b[i].is_ascii_alphabetic() {
+            i += 1;
+        }
+        let (unit_secs, unit_nanos) = const_unit(b, start, i);
+        secs = match n.checked_mul(unit_secs) {
+            Some(v) => match secs.checked_add(v) {
+                Some(secs) => secs,
+                None => panic!("duration out of range"),
+            },
+            None => panic!("duration out of range"),
+        };
+        nanos = match n.checked_mul(unit_nanos) {
+            Some(v) => match nanos.checked_add(v) {
+                Some(nanos) => nanos,
+                None => panic!("duration out of range"),
+            },
+            None => panic!("duration out of range"),
+        };
+
+        if skip_whitespace(b, i) == b.len() {
+            break;
+        }
+        i = const_separator(b, i);
+        if i == b.len() || !b[i].is_ascii_digit() {
+            panic!("unexpected trailing input in duration");
+        }
+    }
+
+    match secs.checked_add(nanos / 1_000_000_000) {
+        Some(secs) => Duration::new(secs, (nanos % 1_000_000_000) as u32),
+        None => panic!("duration out of range"),
+    }
+}
+
+const fn skip_whitespace(b: &[u8], mut i: usize) -> usize {
+    while i < b.len() && b[i].is_ascii_whitespace() {
+        i += 1;
+    }
+    i
+}
+
+/// The constant counterpart of `separator`.
+const fn const_separator(b: &[u8], i: usize) -> usize {
+    let mut i = skip_whitespace(b, i);
+    if i < b.len() && b[i] == b',' {
+        i = skip_whitespace(b, i + 1);
+    }
+    if i + 3 < b.len() && b[i] == b'a' && b[i + 1] == b'n' && b[i + 2] == b'd' && b[i + 3].is_ascii_whitespace() {
+        i = skip_whitespace(b, i + 3);
+    }
+    i
+}
+
+/// The constant counterpart of `unit`, for the unit at `b[start..end]`.
+const fn const_unit(b: &[u8], start: usize, end: usize) -> (u64, u64) {
+    let mut i = 0;
+    while i < UNITS.len() {
+        let (names, secs, nanos) = UNITS[i];
+        let mut j = 0;
+        while j < names.len() {
+            let name = names[j].as_bytes();
+            if name.len() == end - start {
+                let mut k = 0;
+                while k < name.len() && name[k] == b[start + k] {
+                    k += 1;
+                }
+                if k == name.len() {
+                    return (secs, nanos);
+                }
+            }
+            j += 1;
+        }
+        i += 1;
+    }
+    panic!("unknown unit in duration")
+}
+
+/// Parses a duration from the start of a string, returning it along with the
+/// unparsed remainder of the string.
+///
+/// As many components as possible are consumed, and parsing stops at the
+/// first thing which isn't a number followed by a unit. Returns `None` if no
+/// components could be parsed or the duration overflows.
+///
+/// # Examples
+///
+/// ```
+/// use serde_humantime::parse_duration_partial;
+/// use std::time::Duration;
+///
+/// let (warmup, rest) = parse_duration_partial("1m 30s of warmup then steady").unwrap();
+/// assert_eq!(warmup, Duration::from_secs(90));
+/// assert_eq!(rest, " of warmup then steady");
+/// ```
+pub fn parse_duration_partial(s: &str) -> Option<(Duration, &str)> {
+    partial(s, &ParseOptions::new()).ok()
+}
+
+/// Skips the whitespace, commas and `and` which can separate components.
+pub(crate) fn separator(s: &str) -> &str {
+    let mut s = s.trim_start();
+    if let Some(rest) = s.strip_prefix(',') {
+        s = rest.trim_start();
+    }
+    match s.strip_prefix("and") {
+        Some(rest) if rest.starts_with(char::is_whitespace) => rest.trim_start(),
+        _ => s,
+    }
+}
+
+fn partial<'a>(s: &'a str, options: &ParseOptions) -> Result<(Duration, &'a str), ErrorKind> {
+    let mut secs = 0u64;
+    let mut nanos = 0u64;
+    let mut rest = s.trim_start();
+    if rest.is_empty() {
+        return Err(ErrorKind::Empty);
+    }
+    // only a failure to parse the first component is an error
+    let mut component = component(rest, options)?;
+
+    loop {
+        let (n, (unit_secs, unit_nanos), after) = component;
+        secs = n.checked_mul(unit_secs).and_then(|n| secs.checked_add(n))
+            .ok_or(ErrorKind::OutOfRange)?;
+        nanos = n.checked_mul(unit_nanos).and_then(|n| nanos.checked_add(n))
+            .ok_or(ErrorKind::OutOfRange)?;
+        rest = separator(after);
+        match self::component(rest, options) {
+            Ok(next) => component = next,
+            Err(_) => {
+                secs = secs.checked_add(nanos / 1_000_000_000).ok_or(ErrorKind::OutOfRange)?;
+                return Ok((Duration::new(secs, (nanos % 1_000_000_000) as u32), after));
+            }
+        }
+    }
+}
+
+mod internal {
+    use std::time::Duration;
+    #[cfg(all(any(test, not(feature = "humantime")), feature = "systemtime"))]
+    use std::time::SystemTime;
+
+    #[cfg(all(any(test, not(feature = "humantime")), feature = "systemtime"))]
+    use civil;
+    use error::ErrorKind;
+    use super::ParseOptions;
+
+    pub fn parse_duration(s: &str) -> Result<Duration, ErrorKind> {
+        parse_duration_with(s, &ParseOptions::new())
+    }
+
+    pub fn parse_duration_with(s: &str, options: &ParseOptions) -> Result<Duration, ErrorKind> {
+        let (d, rest) = super::partial(s, options)?;
+        let next = super::separator(rest);
+        if rest.trim_start().is_empty() {
+            Ok(d)
+        } else if next.starts_with(|c: char| c.is_ascii_digit()) {
+            // the next component was malformed rather than extraneous
+            super::component(next, options).map(|_| d)
+        } else {
+            Err(ErrorKind::TrailingInput)
+        }
+    }
+
+    #[cfg(all(any(test, not(feature = "humantime")), feature = "systemtime"))]
+    fn digits(b: &[u8]) -> Result<u32, ErrorKind> {
+        let mut value = 0;
+        for &b in b {
+            if !b.is_ascii_digit() {
+                return Err(ErrorKind::InvalidNumber);
+            }
+            value = value * 10 + (b - b'0') as u32;
+        }
+        Ok(value)
+    }
+
+    #[cfg(all(any(test, not(feature = "humantime")), feature = "systemtime"))]
+    pub fn parse_rfc3339_weak(s: &str) -> Result<SystemTime, ErrorKind> {
+        let b = s.as_bytes();
+        if b.len() < 19 || b[4] != b'-' || b[7] != b'-' || (b[10] != b'T' && b[10] != b' ') ||
+           b[13] != b':' || b[16] != b':' {
+            return Err(ErrorKind::InvalidFormat);
+        }
+        let year = digits(&b[0..4])? as i64;
+        let month = digits(&b[5..7])?;
+        let day = digits(&b[8..10])?;
+        let hour = digits(&b[11..13])? as i64;
+        let minute = digits(&b[14..16])? as i64;
+        // leap seconds are folded into the preceding second
+        let second = digits(&b[17..19])?.min(59) as i64;
+        if !(1970..=9999).contains(&year) || month == 0 || month > 12 || day == 0 ||
+           day > civil::days_in_month(year, month) || hour > 23 || minute > 59 ||
+           digits(&b[17..19])? > 60 {
+            return Err(ErrorKind::OutOfRange);
+        }
+
+        let mut rest = &b[19..];
+        let mut nanos = 0;
+        if rest.first() == Some(&b'.') {
+            let end = rest[1..].iter().position(|b| !b.is_ascii_digit()).map_or(rest.len(), |i| i + 1);
+            let mut mult = 100_000_000;
+            for &b in &rest[1..end] {
+                nanos += mult * (b - b'0') as u32;
+                mult /= 10;
+            }
+            rest = &rest[end..];
+        }
+        if rest != b"" && rest != b"Z" {
+            return Err(ErrorKind::InvalidFormat);
+        }
+
+        let secs = civil::days_from_civil(year, month, day) * civil::SECS_PER_DAY + hour * 3600 +
+                   minute * 60 + second;
+        civil::from_unix(secs, nanos).ok_or(ErrorKind::OutOfRange)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use error::ErrorKind;
+    use super::{internal, parse_duration, parse_duration_const, parse_duration_partial, ParseOptions};
+
+    #[test]
+    fn duration() {
+        assert_eq!(internal::parse_duration("2h 37min"), Ok(Duration::from_secs(9420)));
+        assert_eq!(internal::parse_duration(" 1h30m 5 s "), Ok(Duration::from_secs(5405)));
+        assert_eq!(internal::parse_duration("1500ms 600000us"), Ok(Duration::from_millis(2100)));
+        assert_eq!(internal::parse_duration("1M"), Ok(Duration::from_secs(2630016)));
+        assert_eq!(internal::parse_duration("2 hours, 1 minute and 1 millisecond"),
+                   Ok(Duration::new(7260, 1_000_000)));
+        for &(s, kind) in &[("", ErrorKind::Empty),
+                            (" ", ErrorKind::Empty),
+                            ("h", ErrorKind::InvalidNumber),
+                            ("-1h", ErrorKind::InvalidCharacter),
+                            ("1", ErrorKind::UnknownUnit),
+                            ("1 fortnight", ErrorKind::UnknownUnit),
+                            ("1h 2x", ErrorKind::UnknownUnit),
+                            ("1h-1m", ErrorKind::TrailingInput),
+                            ("1h,", ErrorKind::TrailingInput),
+                            ("1h and", ErrorKind::TrailingInput),
+                            ("1h, 2 fortnights", ErrorKind::UnknownUnit),
+                            ("18446744073709551616s", ErrorKind::OutOfRange),
+                            ("18446744073709551615m", ErrorKind::OutOfRange)] {
+            assert_eq!(internal::parse_duration(s), Err(kind), "{}", s);
+        }
+    }
+
+    #[test]
+    fn limits() {
+        let options = ParseOptions::new().max_len(10).max_components(2);
+        assert_eq!(options.parse_duration("1h 30m").ok(), Some(Duration::from_secs(5400)));
+        for &(s, kind) in &[("1h 30m 15s", ErrorKind::TooManyComponents),
+                            ("1h        30m", ErrorKind::TooLong),
+                            ("1h 2m 3", ErrorKind::TooManyComponents),
+                            ("1x", ErrorKind::UnknownUnit)] {
+            assert_eq!(options.parse_duration(s).map_err(|e| e.kind()), Err(kind), "{}", s);
+        }
+    }
+
+    #[test]
+    fn business_units() {
+        let options = ParseOptions::new();
+        assert_eq!(options.parse_duration("1 fortnight").map_err(|e| e.kind()),
+                   Err(ErrorKind::UnknownUnit));
+        assert_eq!(options.parse_duration("1 quarter").map_err(|e| e.kind()),
+                   Err(ErrorKind::UnknownUnit));
+
+        let options = options.fortnights(true);
+        assert_eq!(options.parse_duration("2 fortnights 1d").ok(),
+                   Some(Duration::from_secs(29 * 86400)));
+        assert_eq!(options.parse_duration("1 quarter").map_err(|e| e.kind()),
+                   Err(ErrorKind::UnknownUnit));
+
+        let options = options.quarters(true);
+        assert_eq!(options.parse_duration("1 quarter, 1 fortnight").ok(),
+                   Some(Duration::from_secs(7890048 + 1209600)));
+    }
+
+    #[test]
+    fn scientific() {
+        let options = ParseOptions::new();
+        assert_eq!(options.parse_duration("1e3 ms").map_err(|e| e.kind()),
+                   Err(ErrorKind::UnknownUnit));
+
+        let options = options.scientific(true);
+        let cases = [("1e3 ms", Duration::from_secs(1)),
+                     ("2.5e-3 s", Duration::from_micros(2500)),
+                     ("1.5h 30m", Duration::from_secs(7200)),
+                     ("1E+2s", Duration::from_secs(100)),
+                     ("5e2ms 2.s", Duration::from_millis(2500)),
+                     ("0.000000001s", Duration::new(0, 1)),
+                     ("18446744073709551615s", Duration::from_secs(u64::MAX)),
+                     ("1.8446744073709551615e19s", Duration::from_secs(u64::MAX)),
+                     ("0e999999999s", Duration::from_secs(0))];
+        for &(s, d) in &cases {
+            assert_eq!(options.parse_duration(s).ok(), Some(d), "{}", s);
+        }
+        for &(s, kind) in &[("1e-10s", ErrorKind::OutOfRange),
+                            ("1e40s", ErrorKind::OutOfRange),
+                            ("1e99999999999s", ErrorKind::OutOfRange),
+                            ("1.2.3s", ErrorKind::UnknownUnit),
+                            ("1e", ErrorKind::UnknownUnit)] {
+            assert_eq!(options.parse_duration(s).map_err(|e| e.kind()), Err(kind), "{}", s);
+        }
+        assert_eq!(options.max_components(1).parse_duration("2.5e-3 s").ok(),
+                   Some(Duration::from_micros(2500)));
+    }
+
+    #[test]
+    fn parse_const() {
+        const TIMEOUT: Duration = parse_duration_const(" 1 day, 2h and 3 mins 4ns ");
+        assert_eq!(TIMEOUT, Duration::new(93780, 4));
+
+        let cases = ["15 seconds", "1h30m", "18446744073709551615s", "1000000000ns 1ms", "2years 1M"];
+        for s in &cases {
+            assert_eq!(parse_duration_const(s), parse_duration(s).unwrap(), "{}", s);
+        }
+        for s in &["", "h", "1", "1 fortnight", "1h foo", "1h,", "18446744073709551616s", "18446744073709551615s 1s"] {
+            let result = ::std::panic::catch_unwind(|| parse_duration_const(s));
+            assert!(result.is_err(), "{}", s);
+            assert!(parse_duration(s).is_err(), "{}", s);
+        }
+    }
+
+    #[test]
+    fn default_unit() {
+        assert_eq!(ParseOptions::new().parse_duration("30").map_err(|e| e.kind()),
+                   Err(ErrorKind::UnknownUnit));
+
+        let options = ParseOptions::new().default_unit(Duration::from_secs(1));
+        let cases = [("30", Duration::from_secs(30)),
+                     (" 30 ", Duration::from_secs(30)),
+                     ("30ms", Duration::from_millis(30)),
+                     ("18446744073709551615", Duration::from_secs(u64::MAX))];
+        for &(s, d) in &cases {
+            assert_eq!(options.parse_duration(s).ok(), Some(d), "{}", s);
+        }
+        assert_eq!(options.parse_duration("18446744073709551616").map_err(|e| e.kind()),
+                   Err(ErrorKind::OutOfRange));
+        assert!(options.parse_duration("1.5").is_err());
+        assert!(options.parse_duration("1h 30").is_err());
+
+        let options = options.default_unit(Duration::from_millis(1)).scientific(true);
+        assert_eq!(options.parse_duration("1.5").ok(), Some(Duration::from_micros(1500)));
+        assert_eq!(options.parse_duration("2e3").ok(), Some(Duration::from_secs(2)));
+    }
+
+    #[test]
+    #[cfg(feature = "systemtime")]
+    fn bounds() {
+        use std::time::UNIX_EPOCH;
+
+        use clock::FixedClock;
+
+        let clock = FixedClock::new(UNIX_EPOCH + Duration::from_secs(1518563312));
+        let options = ParseOptions::new()
+            .not_before(UNIX_EPOCH + Duration::from_secs(946684800))
+            .max_ahead(Duration::from_secs(86400));
+        for &(s, ok) in &[("2018-02-13T23:08:32Z", true),
+                          ("2000-01-01T00:00:00Z", true),
+                          ("1999-12-31T23:59:59Z", false),
+                          ("2018-02-14T23:08:32Z", true),
+                          ("2018-02-14T23:08:33Z", false)] {
+            let r = options.parse_timestamp_with_clock(s, &clock);
+            assert_eq!(r.map_err(|e| e.kind()).is_ok(), ok, "{}", s);
+        }
+
+        let options = options.not_after(UNIX_EPOCH + Duration::from_secs(1500000000));
+        let r = options.parse_timestamp_with_clock("2018-02-13T23:08:32Z", &clock);
+        assert_eq!(r.unwrap_err().kind(), ErrorKind::OutOfBounds);
+    }
+
+    #[test]
+    #[cfg(feature = "systemtime")]
+    fn weekdays() {
+        use std::time::UNIX_EPOCH;
+
+        use clock::FixedClock;
+
+        // Tuesday 2018-02-13T23:08:32Z
+        let clock = FixedClock::new(UNIX_EPOCH + Duration::from_secs(1518563312));
+        let midnight = 1518480000;
+        let options = ParseOptions::new().weekdays(true);
+        for &(s, secs) in &[("next sunday 02:00", midnight + 5 * 86400 + 7200),
+                            ("Wed", midnight + 86400),
+                            ("tuesday 23:08:33", midnight + 83313),
+                            ("tuesday 23:08:32", midnight + 7 * 86400 + 83312),
+                            ("NEXT Tue", midnight + 7 * 86400),
+                            ("2018-02-13T23:08:32Z", 1518563312)] {
+            let t = options.parse_timestamp_with_clock(s, &clock).unwrap();
+            assert_eq!(t, UNIX_EPOCH + Duration::from_secs(secs), "{}", s);
+        }
+        for s in &["next", "next next monday", "mon 9:00", "mon 24:00", "mon 09:00 UTC", "mo", "monday 09:00:"] {
+            assert!(options.parse_timestamp_with_clock(s, &clock).is_err(), "{}", s);
+        }
+        assert!(ParseOptions::new().parse_timestamp_with_clock("monday", &clock).is_err());
+
+        let options = options.max_ahead(Duration::from_secs(86400));
+        assert_eq!(options.parse_timestamp_with_clock("friday", &clock).unwrap_err().kind(),
+                   ErrorKind::OutOfBounds);
+    }
+
+    #[test]
+    #[cfg(feature = "systemtime")]
+    fn day_parts() {
+        use std::time::UNIX_EPOCH;
+
+        use clock::FixedClock;
+
+        let clock = FixedClock::new(UNIX_EPOCH + Duration::from_secs(1518563312));
+        let midnight = 1518480000;
+        let options = ParseOptions::new().day_parts(true);
+        for &(s, secs, nanos) in &[("midnight", midnight, 0),
+                                   ("Noon", midnight + 43200, 0),
+                                   ("end  of day", midnight + 86399, 999_999_999),
+                                   ("2024-06-01 midnight", 1717200000, 0),
+                                   ("2024-06-01 end of day", 1717286399, 999_999_999)] {
+            let t = options.parse_timestamp_with_clock(s, &clock).unwrap();
+            assert_eq!(t, UNIX_EPOCH + Duration::new(secs, nanos), "{}", s);
+        }
+        for s in &["2024-06-01noon", "2024-06-31 noon", "tomorrow noon", "end of", "noon 2024-06-01"] {
+            assert!(options.parse_timestamp_with_clock(s, &clock).is_err(), "{}", s);
+        }
+        assert!(ParseOptions::new().parse_timestamp_with_clock("noon", &clock).is_err());
+        assert!(options.parse_timestamp_with_clock("monday", &clock).is_err());
+    }
+
+    #[test]
+    fn partial() {
+        assert_eq!(parse_duration_partial("30s of warmup"),
+                   Some((Duration::from_secs(30), " of warmup")));
+        assert_eq!(parse_duration_partial(" 1h 2 m3s, then 5s"),
+                   Some((Duration::from_secs(3723), ", then 5s")));
+        assert_eq!(parse_duration_partial("1h 2"), Some((Duration::from_secs(3600), " 2")));
+        assert_eq!(parse_duration_partial("1h"), Some((Duration::from_secs(3600), "")));
+        assert_eq!(parse_duration_partial("1 fortnight"), None);
+        assert_eq!(parse_duration_partial(""), None);
+        assert_eq!(parse_duration_partial("18446744073709551615m"), None);
+    }
+
+    #[test]
+    #[cfg(feature = "humantime")]
+    fn matches_humantime() {
+        use humantime;
+
+        for s in &["2h 37min", "1h30m", "17nsec 3usec", "1y 2M 3w 4d", "15 seconds", "1h 1h",
+                   "", "1", "1x", "1.5s", "99999999999999999999s"] {
+            assert_eq!(internal::parse_duration(s).ok(), humantime::parse_duration(s).ok(), "{}", s);
+        }
+        #[cfg(feature = "systemtime")]
+        for s in &["2018-02-13T23:08:32Z", "2018-02-13 23:08:32", "2018-02-13T23:08:32.123Z",
+                   "2016-12-31T23:59:60Z", "2016-02-29T00:00:00Z", "2017-02-29T00:00:00Z",
+                   "1969-12-31T23:59:59Z", "9999-12-31T23:59:59Z", "2018-02-13T24:00:00Z",
+                   "2018-02-13T23:08:32.Z", "2018-02-13T23:08:32ZZ", "2018-02-13t23:08:32Z",
+                   "2018-02-13T23:08:32.1234567891Z"] {
+            assert_eq!(internal::parse_rfc3339_weak(s).ok(), humantime::parse_rfc3339_weak(s).ok(),
+                       "{}", s);
+        }
+    }
+}
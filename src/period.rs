@@ -0,0 +1,238 @@
+//! Coarse scheduling periods.
+#[cfg(feature = "de")]
+use serde::de::{Deserialize, Deserializer, Visitor, Error, Unexpected};
+#[cfg(feature = "ser")]
+use serde::ser::{Serialize, Serializer};
+#[cfg(feature = "de")]
+use std::error;
+#[cfg(any(feature = "ser", feature = "de"))]
+use std::fmt;
+#[cfg(feature = "de")]
+use std::str::FromStr;
+use std::time::Duration;
+#[cfg(feature = "systemtime")]
+use std::time::SystemTime;
+
+use calendar::CalendarDuration;
+#[cfg(feature = "systemtime")]
+use civil;
+
+/// A schedule period, such as how often a report is sent.
+///
+/// It is written as one of the keywords `daily`, `weekly` or `monthly`, and
+/// is serialized and deserialized as such a string.
+///
+/// Monthly schedules recur on the same day of the month as their anchor,
+/// clamped to the length of shorter months, so a schedule anchored on
+/// January 31st next runs on the last day of February and then on March 31st.
+///
+/// # Examples
+///
+/// ```
+/// use serde_humantime::period::Period;
+/// use std::time::{Duration, UNIX_EPOCH};
+///
+/// let period = "weekly".parse::<Period>().unwrap();
+/// assert_eq!(period.approximate(), Duration::from_secs(7 * 86400));
+///
+/// let anchor = UNIX_EPOCH;
+/// let now = UNIX_EPOCH + Duration::from_secs(10 * 86400);
+/// assert_eq!(period.next_after(anchor, now), Some(UNIX_EPOCH + Duration::from_secs(14 * 86400)));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Period {
+    /// Every day.
+    Daily,
+    /// Every week.
+    Weekly,
+    /// Every calendar month.
+    Monthly,
+}
+
+impl Period {
+    /// Returns the approximate length of the period.
+    ///
+    /// A month is taken to be 30.44 days, as in `humantime`.
+    pub fn approximate(&self) -> Duration {
+        match *self {
+            Period::Daily => Duration::from_secs(86400),
+            Period::Weekly => Duration::from_secs(7 * 86400),
+            Period::Monthly => Duration::from_secs(2630016),
+        }
+    }
+
+    /// Returns the first occurrence strictly after `t` of a schedule starting
+    /// at `anchor`.
+    ///
+    /// Returns `anchor` itself if it is after `t`, and `None` if the result
+    /// can't be represented.
+    #[cfg(feature = "systemtime")]
+    pub fn next_after(&self, anchor: SystemTime, t: SystemTime) -> Option<SystemTime> {
+        let elapsed = match t.duration_since(anchor) {
+            Ok(elapsed) => elapsed,
+            Err(_) => return Some(anchor),
+        };
+
+        match *self {
+            Period::Daily | Period::Weekly => {
+                let period = self.approximate().as_secs();
+                let periods = elapsed.as_secs() / period + 1;
+                anchor.checked_add(Duration::from_secs(periods.checked_mul(period)?))
+            }
+            Period::Monthly => {
+                // start from the number of month boundaries crossed, which is
+                // at most one short of the answer
+                let (anchor_year, anchor_month) = year_month(anchor);
+                let (year, month) = year_month(t);
+                let months = (year - anchor_year) * 12 + month as i64 - anchor_month as i64;
+                if months > u32::MAX as i64 {
+                    return None;
+                }
+                let mut months = months as u32;
+                loop {
+                    let next = CalendarDuration::new(months, Duration::from_secs(0)).add_to(anchor)?;
+                    if next > t {
+                        return Some(next);
+                    }
+                    months = months.checked_add(1)?;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(feature = "systemtime")]
+fn year_month(t: SystemTime) -> (i64, u32) {
+    let (secs, _) = civil::to_unix(t);
+    let (year, month, _) = civil::civil_from_days(secs.div_euclid(civil::SECS_PER_DAY));
+    (year, month)
+}
+
+impl From<Period> for CalendarDuration {
+    fn from(period: Period) -> CalendarDuration {
+        match period {
+            Period::Daily => CalendarDuration::new(0, Duration::from_secs(86400)),
+            Period::Weekly => CalendarDuration::new(0, Duration::from_secs(7 * 86400)),
+            Period::Monthly => CalendarDuration::new(1, Duration::from_secs(0)),
+        }
+    }
+}
+
+#[cfg(feature = "ser")]
+impl fmt::Display for Period {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str(match *self {
+            Period::Daily => "daily",
+            Period::Weekly => "weekly",
+            Period::Monthly => "monthly",
+        })
+    }
+}
+
+/// An error parsing a `Period`.
+#[derive(Debug)]
+#[cfg(feature = "de")]
+pub struct ParsePeriodError(());
+
+#[cfg(feature = "de")]
+impl fmt::Display for ParsePeriodError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str("invalid period")
+    }
+}
+
+#[cfg(feature = "de")]
+impl error::Error for ParsePeriodError {}
+
+#[cfg(feature = "de")]
+impl FromStr for Period {
+    type Err = ParsePeriodError;
+
+    fn from_str(s: &str) -> Result<Period, ParsePeriodError> {
+        match s {
+            "daily" => Ok(Period::Daily),
+            "weekly" => Ok(Period::Weekly),
+            "monthly" => Ok(Period::Monthly),
+            _ => Err(ParsePeriodError(())),
+        }
+    }
+}
+
+#[cfg(feature = "ser")]
+impl Serialize for Period {
+    fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        s.collect_str(self)
+    }
+}
+
+#[cfg(feature = "de")]
+impl<'de> Deserialize<'de> for Period {
+    fn deserialize<D>(d: D) -> Result<Period, D::Error>
+        where D: Deserializer<'de>
+    {
+        struct V;
+
+        impl<'de2> Visitor<'de2> for V {
+            type Value = Period;
+
+            fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+                fmt.write_str("`daily`, `weekly` or `monthly`")
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Period, E>
+                where E: Error
+            {
+                v.parse().map_err(|_| E::invalid_value(Unexpected::Str(v), &self))
+            }
+        }
+
+        d.deserialize_str(V)
+    }
+}
+
+#[cfg(all(test, feature = "ser", feature = "de"))]
+mod test {
+    use serde_json;
+    #[cfg(feature = "systemtime")]
+    use std::time::SystemTime;
+
+    #[cfg(feature = "systemtime")]
+    use civil;
+    use super::*;
+
+    #[cfg(feature = "systemtime")]
+    fn date(year: i64, month: u32, day: u32) -> SystemTime {
+        civil::from_unix(civil::days_from_civil(year, month, day) * 86400, 0).unwrap()
+    }
+
+    #[test]
+    fn round_trip() {
+        for &(s, period) in &[("daily", Period::Daily), ("weekly", Period::Weekly), ("monthly", Period::Monthly)] {
+            let json = format!("\"{}\"", s);
+            assert_eq!(serde_json::from_str::<Period>(&json).unwrap(), period);
+            assert_eq!(serde_json::to_string(&period).unwrap(), json);
+        }
+        for s in &[r#""""#, r#""Daily""#, r#""yearly""#, "1"] {
+            assert!(serde_json::from_str::<Period>(s).is_err(), "{}", s);
+        }
+    }
+
+    #[cfg(feature = "systemtime")]
+    #[test]
+    fn next_after() {
+        let anchor = date(2024, 1, 31) + Duration::from_secs(9 * 3600);
+        let cases = [(Period::Daily, date(2024, 1, 1), anchor),
+                     (Period::Daily, anchor, anchor + Duration::from_secs(86400)),
+                     (Period::Daily, date(2024, 2, 1), date(2024, 2, 1) + Duration::from_secs(9 * 3600)),
+                     (Period::Weekly, date(2024, 2, 7) + Duration::from_secs(10 * 3600), anchor + Duration::from_secs(14 * 86400)),
+                     (Period::Monthly, date(2024, 2, 10), date(2024, 2, 29) + Duration::from_secs(9 * 3600)),
+                     (Period::Monthly, date(2024, 3, 1), date(2024, 3, 31) + Duration::from_secs(9 * 3600)),
+                     (Period::Monthly, date(2024, 3, 31) + Duration::from_secs(9 * 3600), date(2024, 4, 30) + Duration::from_secs(9 * 3600)),
+                     (Period::Monthly, date(2025, 1, 31), date(2025, 1, 31) + Duration::from_secs(9 * 3600))];
+        for &(period, t, next) in &cases {
+            assert_eq!(period.next_after(anchor, t), Some(next), "{:?} {:?}", period, t);
+        }
+    }
+}
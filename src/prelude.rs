@@ -0,0 +1,53 @@
+//! A prelude of commonly used items.
+//!
+//! Glob importing this module brings the wrapper, the newtypes and the
+//! extension traits into scope along with the `with` modules, which can then
+//! be referred to by their short names.
+//!
+//! # Examples
+//!
+//! ```
+//! extern crate serde_humantime;
+//! extern crate serde;
+//! #[macro_use]
+//! extern crate serde_derive;
+//!
+//! use serde_humantime::prelude::*;
+//! use std::time::{Duration, SystemTime};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Foo {
+//!     #[serde(with = "rfc3339")]
+//!     created: SystemTime,
+//!     #[serde(with = "iso8601")]
+//!     ttl: Duration,
+//!     retention: CalendarDuration,
+//! }
+//!
+//! # fn main() {}
+//! ```
+pub use buckets::Buckets;
+pub use calendar::CalendarDuration;
+#[cfg(all(any(feature = "systemtime", feature = "chrono", feature = "time"), any(feature = "ser", feature = "de")))]
+pub use date;
+#[cfg(any(feature = "ser", feature = "de"))]
+pub use duration_nanos_u128;
+pub use duration_or_range::DurationOrRange;
+#[cfg(feature = "ser")]
+pub use format::Humanize;
+pub use frequency::Frequency;
+#[cfg(feature = "systemtime")]
+pub use interval::Interval;
+#[cfg(any(feature = "ser", feature = "de"))]
+pub use iso8601;
+pub use non_zero::NonZeroDuration;
+pub use offset::UtcOffset;
+pub use period::Period;
+#[cfg(all(feature = "ser", feature = "systemtime"))]
+pub use relative::Relative;
+#[cfg(feature = "systemtime")]
+pub use rfc3339;
+pub use signed::SignedDuration;
+#[cfg(any(feature = "ser", feature = "de"))]
+pub use structured;
+pub use De;
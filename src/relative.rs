@@ -0,0 +1,197 @@
+//! Formatting of `SystemTime`s relative to another time.
+//!
+//! Timestamps are written as the largest whole unit of their distance from a
+//! reference time, as in `3 hours ago` or `in 2 days`. Timestamps less than a
+//! second from the reference are written as `now`.
+//!
+//! The output is lossy and intended for people, so this module can only
+//! serialize. Its `serialize` function writes timestamps relative to the
+//! system clock, and can be used with `serialize_with`. Other clocks can be
+//! used through `serialize_with_clock`.
+//!
+//! The `Relative` extension trait writes `Duration`s as times relative to
+//! now, with its `ago` and `from_now` adapters.
+//!
+//! # Examples
+//!
+//! ```
+//! extern crate serde_humantime;
+//! extern crate serde;
+//! #[macro_use]
+//! extern crate serde_derive;
+//!
+//! use std::time::SystemTime;
+//!
+//! #[derive(Serialize)]
+//! struct Status {
+//!     #[serde(serialize_with = "serde_humantime::relative::serialize")]
+//!     last_seen: SystemTime,
+//! }
+//!
+//! # fn main() {}
+//! ```
+use serde::ser::Serializer;
+use std::fmt;
+use std::time::{Duration, SystemTime};
+
+use clock::{Clock, SystemClock};
+use convert;
+use format::Units;
+
+/// A wrapper type which formats a `SystemTime` relative to a reference time
+/// via its `Display` implementation.
+#[derive(Debug, Clone, Copy)]
+pub struct FormattedRelative {
+    distance: Duration,
+    past: bool,
+}
+
+/// Returns a value which formats a `SystemTime` relative to a reference time
+/// via its `Display` implementation.
+///
+/// # Examples
+///
+/// ```
+/// use serde_humantime::relative::format_relative;
+/// use std::time::{Duration, UNIX_EPOCH};
+///
+/// let now = UNIX_EPOCH + Duration::from_secs(1518563312);
+/// let earlier = now - Duration::from_secs(3 * 3600 + 25 * 60);
+/// assert_eq!(format_relative(earlier, now).to_string(), "3 hours ago");
+/// let later = now + Duration::from_secs(2 * 86400);
+/// assert_eq!(format_relative(later, now).to_string(), "in 2 days");
+/// ```
+pub fn format_relative(time: SystemTime, reference: SystemTime) -> FormattedRelative {
+    match time.duration_since(reference) {
+        Ok(distance) => FormattedRelative { distance, past: false },
+        Err(e) => FormattedRelative { distance: e.duration(), past: true },
+    }
+}
+
+/// Returns a value which formats a `SystemTime` relative to the current time
+/// of a clock via its `Display` implementation.
+///
+/// # Examples
+///
+/// ```
+/// use serde_humantime::clock::FixedClock;
+/// use serde_humantime::relative::format_relative_to_clock;
+/// use std::time::{Duration, UNIX_EPOCH};
+///
+/// let clock = FixedClock::new(UNIX_EPOCH + Duration::from_secs(1518563312));
+/// let t = UNIX_EPOCH + Duration::from_secs(1518563312 - 90);
+/// assert_eq!(format_relative_to_clock(t, &clock).to_string(), "1 minute ago");
+/// ```
+pub fn format_relative_to_clock<C>(time: SystemTime, clock: &C) -> FormattedRelative
+    where C: Clock + ?Sized
+{
+    format_relative(time, clock.now())
+}
+
+impl fmt::Display for FormattedRelative {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        if self.distance < Duration::from_secs(1) {
+            return fmt.write_str("now");
+        }
+
+        // the distance is at least a second, so has a nonzero component
+        let (value, unit) = convert::decompose(self.distance).next().unwrap();
+        let (singular, plural) = Units::Long.names()[unit as usize];
+        let unit = if value == 1 { singular } else { plural };
+        if self.past {
+            write!(fmt, "{} {} ago", value, unit)
+        } else {
+            write!(fmt, "in {} {}", value, unit)
+        }
+    }
+}
+
+/// An extension trait adding relative `Display` adapters to `Duration`.
+///
+/// The adapters write a duration as the time that far from now, in the same
+/// form as `format_relative`.
+///
+/// # Examples
+///
+/// ```
+/// use serde_humantime::relative::Relative;
+/// use std::time::Duration;
+///
+/// let d = Duration::from_secs(3 * 3600 + 25 * 60);
+/// assert_eq!(format!("last seen {}", d.ago()), "last seen 3 hours ago");
+/// assert_eq!(format!("expires {}", Duration::from_secs(2 * 86400).from_now()),
+///            "expires in 2 days");
+/// ```
+pub trait Relative {
+    /// Returns an adapter writing the value as a time in the past, such as
+    /// `3 hours ago`.
+    fn ago(&self) -> FormattedRelative;
+
+    /// Returns an adapter writing the value as a time in the future, such as
+    /// `in 2 days`.
+    // named for the phrase, like `ago`, rather than as a constructor
+    #[allow(clippy::wrong_self_convention)]
+    fn from_now(&self) -> FormattedRelative;
+}
+
+impl Relative for Duration {
+    fn ago(&self) -> FormattedRelative {
+        FormattedRelative { distance: *self, past: true }
+    }
+
+    fn from_now(&self) -> FormattedRelative {
+        FormattedRelative { distance: *self, past: false }
+    }
+}
+
+/// Serializes a `SystemTime` relative to the system clock.
+pub fn serialize<S>(t: &SystemTime, s: S) -> Result<S::Ok, S::Error>
+    where S: Serializer
+{
+    serialize_with_clock(t, &SystemClock, s)
+}
+
+/// Serializes a `SystemTime` relative to the current time of a clock.
+pub fn serialize_with_clock<C, S>(t: &SystemTime, clock: &C, s: S) -> Result<S::Ok, S::Error>
+    where C: Clock + ?Sized,
+          S: Serializer
+{
+    s.collect_str(&format_relative_to_clock(*t, clock))
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use super::{format_relative, Relative};
+
+    #[test]
+    fn relative() {
+        let now = UNIX_EPOCH + Duration::from_secs(1518563312);
+        let cases = [(now, "now"),
+                     (now - Duration::from_millis(999), "now"),
+                     (now + Duration::from_millis(999), "now"),
+                     (now - Duration::from_secs(1), "1 second ago"),
+                     (now - Duration::from_secs(119), "1 minute ago"),
+                     (now + Duration::from_secs(86400), "in 1 day"),
+                     (now + Duration::from_secs(40 * 86400), "in 1 month"),
+                     (now - Duration::from_secs(3 * 31557600), "3 years ago")];
+        for &(t, expected) in &cases {
+            assert_eq!(format_relative(t, now).to_string(), expected);
+        }
+    }
+
+    #[test]
+    fn adapters() {
+        let cases = [(Duration::from_millis(999), "now", "now"),
+                     (Duration::from_secs(1), "1 second ago", "in 1 second"),
+                     (Duration::from_secs(40 * 86400), "1 month ago", "in 1 month"),
+                     (Duration::new(u64::MAX, 999_999_999),
+                      "584542046090 years ago",
+                      "in 584542046090 years")];
+        for &(d, ago, from_now) in &cases {
+            assert_eq!(d.ago().to_string(), ago);
+            assert_eq!(d.from_now().to_string(), from_now);
+        }
+    }
+}
@@ -0,0 +1,285 @@
+//! ISO 8601 repeating intervals.
+#[cfg(feature = "de")]
+use serde::de::{Deserialize, Deserializer, Visitor, Error, Unexpected};
+#[cfg(feature = "ser")]
+use serde::ser::{Serialize, Serializer};
+#[cfg(feature = "de")]
+use std::error;
+#[cfg(any(feature = "ser", feature = "de"))]
+use std::fmt;
+#[cfg(feature = "de")]
+use std::marker::PhantomData;
+#[cfg(all(feature = "ser", feature = "systemtime"))]
+use std::ops::Bound;
+#[cfg(feature = "de")]
+use std::str::FromStr;
+#[cfg(any(feature = "ser", feature = "de"))]
+use std::time::Duration;
+
+#[cfg(feature = "ser")]
+use format;
+#[cfg(all(feature = "de", feature = "systemtime"))]
+use interval;
+#[cfg(all(feature = "systemtime", any(feature = "ser", feature = "de")))]
+use interval::Interval;
+#[cfg(feature = "ser")]
+use iso8601::Iso8601;
+#[cfg(feature = "de")]
+use iso8601;
+
+/// An ISO 8601 repeating interval, such as a schedule.
+///
+/// It is written as `R`, an optional number of repetitions, and either a
+/// duration, as in `R5/PT1H`, or an interval, as in
+/// `R5/2024-01-01T00:00:00Z/PT1H`. A missing number of repetitions, as in
+/// `R/P1D`, repeats without bound.
+///
+/// A `Repeating<Duration>` holds just a duration. A `Repeating<Interval>`
+/// holds the first of the repeated intervals, whose sides may be written as
+/// in `Interval` or as an ISO 8601 duration from the other side, as in
+/// `2024-01-01T00:00:00Z/PT1H` or `PT1H/2024-01-01T01:00:00Z`. Bounded
+/// intervals are formatted as a start and a duration.
+///
+/// Repeating intervals are serialized and deserialized as such strings.
+///
+/// # Examples
+///
+/// ```
+/// use serde_humantime::interval::Interval;
+/// use serde_humantime::repeating::Repeating;
+/// use std::ops::Bound;
+/// use std::time::{Duration, UNIX_EPOCH};
+///
+/// let schedule = "R5/PT1H".parse::<Repeating<Duration>>().unwrap();
+/// assert_eq!(schedule.repetitions(), Some(5));
+/// assert_eq!(*schedule.interval(), Duration::from_secs(3600));
+///
+/// let schedule = "R/1970-01-02T00:00:00Z/P1D".parse::<Repeating<Interval>>().unwrap();
+/// assert_eq!(schedule.repetitions(), None);
+/// assert_eq!(schedule.interval().end(), Bound::Excluded(UNIX_EPOCH + Duration::from_secs(2 * 86400)));
+/// assert_eq!(schedule.to_string(), "R/1970-01-02T00:00:00Z/P1D");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Repeating<T> {
+    repetitions: Option<u32>,
+    interval: T,
+}
+
+impl<T> Repeating<T> {
+    /// Creates a `Repeating` from its number of repetitions, where `None`
+    /// repeats without bound, and the repeated duration or interval.
+    pub fn new(repetitions: Option<u32>, interval: T) -> Repeating<T> {
+        Repeating { repetitions, interval }
+    }
+
+    /// Returns the number of repetitions, or `None` if unbounded.
+    pub fn repetitions(&self) -> Option<u32> {
+        self.repetitions
+    }
+
+    /// Returns the repeated duration or interval.
+    pub fn interval(&self) -> &T {
+        &self.interval
+    }
+
+    /// Consumes the `Repeating`, returning the repeated duration or interval.
+    pub fn into_interval(self) -> T {
+        self.interval
+    }
+}
+
+#[cfg(feature = "ser")]
+impl<T> Repeating<T> {
+    fn fmt_repetitions(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self.repetitions {
+            Some(repetitions) => write!(fmt, "R{}/", repetitions),
+            None => fmt.write_str("R/"),
+        }
+    }
+}
+
+#[cfg(feature = "ser")]
+impl fmt::Display for Repeating<Duration> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        self.fmt_repetitions(fmt)?;
+        write!(fmt, "{}", Iso8601(self.interval, false))
+    }
+}
+
+#[cfg(all(feature = "ser", feature = "systemtime"))]
+impl fmt::Display for Repeating<Interval> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        self.fmt_repetitions(fmt)?;
+        match (self.interval.start(), self.interval.end()) {
+            (Bound::Included(start), Bound::Excluded(end)) => {
+                let d = end.duration_since(start).map_err(|_| fmt::Error)?;
+                write!(fmt, "{}/{}", format::format_rfc3339(start), Iso8601(d, false))
+            }
+            _ => write!(fmt, "{}", self.interval),
+        }
+    }
+}
+
+/// An error parsing a `Repeating`.
+#[derive(Debug)]
+#[cfg(feature = "de")]
+pub struct ParseRepeatingError(());
+
+#[cfg(feature = "de")]
+impl fmt::Display for ParseRepeatingError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str("invalid repeating interval")
+    }
+}
+
+#[cfg(feature = "de")]
+impl error::Error for ParseRepeatingError {}
+
+#[cfg(feature = "de")]
+impl FromStr for Repeating<Duration> {
+    type Err = ParseRepeatingError;
+
+    fn from_str(s: &str) -> Result<Repeating<Duration>, ParseRepeatingError> {
+        parse_duration(s).ok_or(ParseRepeatingError(()))
+    }
+}
+
+#[cfg(all(feature = "de", feature = "systemtime"))]
+impl FromStr for Repeating<Interval> {
+    type Err = ParseRepeatingError;
+
+    fn from_str(s: &str) -> Result<Repeating<Interval>, ParseRepeatingError> {
+        parse_interval(s).ok_or(ParseRepeatingError(()))
+    }
+}
+
+#[cfg(feature = "de")]
+fn parse_repetitions(s: &str) -> Option<(Option<u32>, &str)> {
+    let s = s.strip_prefix('R')?;
+    let end = s.find('/')?;
+    let repetitions = match &s[..end] {
+        "" => None,
+        n if n.chars().all(|c| c.is_ascii_digit()) => Some(n.parse().ok()?),
+        _ => return None,
+    };
+    Some((repetitions, &s[end + 1..]))
+}
+
+#[cfg(feature = "de")]
+fn parse_duration(s: &str) -> Option<Repeating<Duration>> {
+    let (repetitions, rest) = parse_repetitions(s)?;
+    Some(Repeating::new(repetitions, iso8601::parse(rest)?))
+}
+
+#[cfg(all(feature = "de", feature = "systemtime"))]
+fn parse_interval(s: &str) -> Option<Repeating<Interval>> {
+    let (repetitions, rest) = parse_repetitions(s)?;
+    let mut it = rest.split('/');
+    let start = it.next()?;
+    let end = it.next()?;
+    if it.next().is_some() {
+        return None;
+    }
+
+    let interval = if start.starts_with('P') {
+        let end = interval::parse_end(end)??;
+        Interval::new(Some(end.checked_sub(iso8601::parse(start)?)?), Some(end))?
+    } else if end.starts_with('P') {
+        let start = interval::parse_end(start)??;
+        Interval::new(Some(start), Some(start.checked_add(iso8601::parse(end)?)?))?
+    } else {
+        Interval::new(interval::parse_end(start)?, interval::parse_end(end)?)?
+    };
+    Some(Repeating::new(repetitions, interval))
+}
+
+#[cfg(feature = "ser")]
+impl<T> Serialize for Repeating<T>
+    where Repeating<T>: fmt::Display
+{
+    fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        format::serialize_display(self, s, "repeating interval can't be represented")
+    }
+}
+
+#[cfg(feature = "de")]
+impl<'de, T> Deserialize<'de> for Repeating<T>
+    where Repeating<T>: FromStr
+{
+    fn deserialize<D>(d: D) -> Result<Repeating<T>, D::Error>
+        where D: Deserializer<'de>
+    {
+        struct V<T>(PhantomData<T>);
+
+        impl<'de2, T> Visitor<'de2> for V<T>
+            where Repeating<T>: FromStr
+        {
+            type Value = Repeating<T>;
+
+            fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+                fmt.write_str("an ISO 8601 repeating interval")
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Repeating<T>, E>
+                where E: Error
+            {
+                v.parse().map_err(|_| E::invalid_value(Unexpected::Str(v), &self))
+            }
+        }
+
+        d.deserialize_str(V(PhantomData))
+    }
+}
+
+#[cfg(all(test, feature = "ser", feature = "de"))]
+mod test {
+    use serde_json;
+    #[cfg(feature = "systemtime")]
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use super::*;
+
+    #[test]
+    fn duration() {
+        let cases = [("R5/PT1H", Some(5), Duration::from_secs(3600)),
+                     ("R0/P1D", Some(0), Duration::from_secs(86400)),
+                     ("R/PT0.5S", None, Duration::from_millis(500))];
+        for &(s, repetitions, d) in &cases {
+            let json = format!("\"{}\"", s);
+            let r = serde_json::from_str::<Repeating<Duration>>(&json).unwrap();
+            assert_eq!(r, Repeating::new(repetitions, d), "{}", s);
+            assert_eq!(serde_json::to_string(&r).unwrap(), json);
+        }
+        for s in &["", "R5", "5/PT1H", "R-1/PT1H", "R+5/PT1H", "R5/1h", "R5/PT1H/PT1H", "R99999999999/PT1H"] {
+            assert!(s.parse::<Repeating<Duration>>().is_err(), "{}", s);
+        }
+    }
+
+    #[cfg(feature = "systemtime")]
+    #[test]
+    fn interval() {
+        fn at(secs: u64) -> Option<SystemTime> {
+            Some(UNIX_EPOCH + Duration::from_secs(secs))
+        }
+
+        let hour = Interval::new(at(1704067200), at(1704070800)).unwrap();
+        for s in &["R3/2024-01-01T00:00:00Z/PT1H",
+                   "R3/2024-01-01/2024-01-01T01:00:00Z",
+                   "R3/PT1H/2024-01-01T01:00:00Z"] {
+            assert_eq!(s.parse::<Repeating<Interval>>().unwrap(), Repeating::new(Some(3), hour), "{}", s);
+        }
+        let json = serde_json::to_string(&Repeating::new(Some(3), hour)).unwrap();
+        assert_eq!(json, r#""R3/2024-01-01T00:00:00Z/PT1H""#);
+
+        let open = Repeating::new(None, Interval::new(at(1704067200), None).unwrap());
+        assert_eq!(open.to_string(), "R/2024-01-01T00:00:00Z/..");
+        assert_eq!(open.to_string().parse::<Repeating<Interval>>().unwrap(), open);
+
+        for s in &["R3/PT1H", "R3/PT1H/PT1H", "R3/../PT1H", "R3/2024-01-01T01:00:00Z/2024-01-01",
+                   "R/PT1H/1970-01-01T00:00:00Z"] {
+            assert!(s.parse::<Repeating<Interval>>().is_err(), "{}", s);
+        }
+    }
+}
@@ -0,0 +1,564 @@
+//! Serialization of `SystemTime`s as RFC 3339 timestamps.
+//!
+//! Timestamps are always serialized in UTC. By default the UTC designator `Z`
+//! is used, while the `offset` submodule emits the numeric `+00:00` form
+//! instead, and the `sortable` submodule always writes nine fractional digits
+//! so that serialized timestamps sort as strings. All forms are accepted when
+//! deserializing, along with the looser syntax supported by
+//! `humantime::parse_rfc3339_weak` (`2018-02-14 00:28:07`) and the ISO 8601
+//! basic format found in file names (`20180214T002807Z`).
+//!
+//! RFC 3339 only permits four digit years, so timestamps after the end of
+//! year 9999 fail to serialize rather than producing output other systems
+//! can't parse. Timestamps before 1970 fail to serialize as well.
+//!
+//! Fractional seconds of more than nine digits, beyond the precision of a
+//! `SystemTime`, are only accepted in some of these forms. The `lenient`
+//! submodule accepts them in all forms by truncating the fraction to
+//! nanoseconds, for feeds with picosecond or finer precision.
+//!
+//! # Examples
+//!
+//! ```
+//! extern crate serde_humantime;
+//! extern crate serde;
+//! #[macro_use]
+//! extern crate serde_derive;
+//!
+//! use std::time::SystemTime;
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Foo {
+//!     #[serde(with = "serde_humantime::rfc3339")]
+//!     created: SystemTime,
+//!     #[serde(with = "serde_humantime::rfc3339::offset")]
+//!     updated: SystemTime,
+//! }
+//!
+//! # fn main() {}
+//! ```
+#[cfg(feature = "de")]
+use serde::de::{Deserializer, Visitor, Error, Unexpected};
+#[cfg(feature = "ser")]
+use serde::ser::{self, Serializer};
+#[cfg(feature = "de")]
+use std::borrow::Cow;
+#[cfg(feature = "de")]
+use std::fmt;
+use std::time::SystemTime;
+use std::time::{Duration, UNIX_EPOCH};
+
+#[cfg(feature = "de")]
+use civil;
+#[cfg(feature = "ser")]
+use format;
+#[cfg(feature = "de")]
+use parse;
+
+// 10000-01-01T00:00:00Z
+const MAX_SECS: u64 = 253402300800;
+
+/// Serializes a `SystemTime` as an RFC 3339 timestamp with a `Z` suffix.
+#[cfg(feature = "ser")]
+pub fn serialize<S>(t: &SystemTime, s: S) -> Result<S::Ok, S::Error>
+    where S: Serializer
+{
+    check_range(t)?;
+    s.collect_str(&format::format_rfc3339(*t))
+}
+
+#[cfg(feature = "ser")]
+fn check_range<E>(t: &SystemTime) -> Result<(), E>
+    where E: ser::Error
+{
+    match range_error(t) {
+        Some(e) => Err(E::custom(e)),
+        None => Ok(()),
+    }
+}
+
+#[cfg(feature = "ser")]
+fn range_error(t: &SystemTime) -> Option<&'static str> {
+    if *t < UNIX_EPOCH {
+        Some("timestamp is before 1970")
+    } else if *t >= UNIX_EPOCH + Duration::from_secs(MAX_SECS) {
+        Some("timestamp is after year 9999")
+    } else {
+        None
+    }
+}
+
+/// Formats a timestamp as an RFC 3339 timestamp, or returns why it can't be
+/// if it is before 1970 or after year 9999.
+#[cfg(any(feature = "async-graphql", feature = "juniper"))]
+pub(crate) fn format_checked(t: SystemTime) -> Result<String, &'static str> {
+    match range_error(&t) {
+        Some(e) => Err(e),
+        None => Ok(format::format_rfc3339(t).to_string()),
+    }
+}
+
+/// Determines if a timestamp is in the range RFC 3339 timestamps can be
+/// formatted in, from 1970 to the end of year 9999.
+pub(crate) fn in_range(t: &SystemTime) -> bool {
+    *t >= UNIX_EPOCH && *t < UNIX_EPOCH + Duration::from_secs(MAX_SECS)
+}
+
+/// Deserializes a `SystemTime` from an RFC 3339 timestamp.
+#[cfg(feature = "de")]
+pub fn deserialize<'de, D>(d: D) -> Result<SystemTime, D::Error>
+    where D: Deserializer<'de>
+{
+    d.deserialize_str(V)
+}
+
+/// Serialization of `SystemTime`s as RFC 3339 timestamps with exactly nine
+/// fractional digits.
+///
+/// Serialized timestamps all have the same length and are in UTC, so they
+/// sort as strings in the same order as the timestamps themselves. This
+/// makes them suitable for keys in object stores and sorted key-value
+/// stores.
+///
+/// # Examples
+///
+/// ```
+/// extern crate serde_humantime;
+/// extern crate serde;
+/// #[macro_use]
+/// extern crate serde_derive;
+///
+/// use std::time::SystemTime;
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Key {
+///     #[serde(with = "serde_humantime::rfc3339::sortable")]
+///     time: SystemTime,
+/// }
+///
+/// # fn main() {}
+/// ```
+#[cfg(any(feature = "ser", feature = "de"))]
+pub mod sortable {
+    #[cfg(feature = "de")]
+    use serde::de::Deserializer;
+    #[cfg(feature = "ser")]
+    use serde::ser::Serializer;
+    use std::time::SystemTime;
+
+    #[cfg(feature = "ser")]
+    use format;
+    #[cfg(feature = "ser")]
+    use super::check_range;
+    #[cfg(feature = "de")]
+    use super::V;
+
+    /// Serializes a `SystemTime` as an RFC 3339 timestamp with nine
+    /// fractional digits.
+    #[cfg(feature = "ser")]
+    pub fn serialize<S>(t: &SystemTime, s: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        check_range(t)?;
+        s.collect_str(&format::format_rfc3339_nanos(*t))
+    }
+
+    /// Deserializes a `SystemTime` from an RFC 3339 timestamp.
+    #[cfg(feature = "de")]
+    pub fn deserialize<'de, D>(d: D) -> Result<SystemTime, D::Error>
+        where D: Deserializer<'de>
+    {
+        d.deserialize_str(V)
+    }
+}
+
+/// Serialization of `SystemTime`s as RFC 3339 timestamps with a `+00:00`
+/// suffix.
+#[cfg(any(feature = "ser", feature = "de"))]
+pub mod offset {
+    #[cfg(feature = "de")]
+    use serde::de::Deserializer;
+    #[cfg(feature = "ser")]
+    use serde::ser::Serializer;
+    #[cfg(feature = "ser")]
+    use std::fmt;
+    use std::time::SystemTime;
+
+    #[cfg(feature = "ser")]
+    use format;
+    #[cfg(feature = "ser")]
+    use super::check_range;
+    #[cfg(feature = "de")]
+    use super::V;
+
+    #[cfg(feature = "ser")]
+    struct Offset(SystemTime);
+
+    #[cfg(feature = "ser")]
+    impl fmt::Display for Offset {
+        fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+            let s = format::format_rfc3339(self.0).to_string();
+            fmt.write_str(&s[..s.len() - 1])?;
+            fmt.write_str("+00:00")
+        }
+    }
+
+    /// Serializes a `SystemTime` as an RFC 3339 timestamp with a `+00:00`
+    /// suffix.
+    #[cfg(feature = "ser")]
+    pub fn serialize<S>(t: &SystemTime, s: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        check_range(t)?;
+        s.collect_str(&Offset(*t))
+    }
+
+    /// Deserializes a `SystemTime` from an RFC 3339 timestamp.
+    #[cfg(feature = "de")]
+    pub fn deserialize<'de, D>(d: D) -> Result<SystemTime, D::Error>
+        where D: Deserializer<'de>
+    {
+        d.deserialize_str(V)
+    }
+}
+
+/// Serialization of `SystemTime`s as RFC 3339 timestamps, accepting more
+/// than nine fractional digits.
+///
+/// Timestamps are serialized as in the parent module. When deserializing,
+/// fractional seconds are truncated to nine digits before parsing, so
+/// `20180213T230832.123456789123Z` is read as `20180213T230832.123456789Z`.
+/// The extra digits are discarded, not rounded.
+///
+/// # Examples
+///
+/// ```
+/// extern crate serde_humantime;
+/// extern crate serde;
+/// #[macro_use]
+/// extern crate serde_derive;
+///
+/// use std::time::SystemTime;
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Sample {
+///     #[serde(with = "serde_humantime::rfc3339::lenient")]
+///     time: SystemTime,
+/// }
+///
+/// # fn main() {}
+/// ```
+#[cfg(any(feature = "ser", feature = "de"))]
+pub mod lenient {
+    #[cfg(feature = "de")]
+    use serde::de::{Deserializer, Visitor, Error, Unexpected};
+    #[cfg(feature = "ser")]
+    use serde::ser::Serializer;
+    #[cfg(feature = "de")]
+    use std::fmt;
+    use std::time::SystemTime;
+
+    #[cfg(feature = "de")]
+    use super::{parse, truncate_fraction};
+
+    /// Serializes a `SystemTime` as an RFC 3339 timestamp with a `Z` suffix.
+    #[cfg(feature = "ser")]
+    pub fn serialize<S>(t: &SystemTime, s: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        super::serialize(t, s)
+    }
+
+    /// Deserializes a `SystemTime` from an RFC 3339 timestamp, truncating
+    /// fractional seconds to nanoseconds.
+    #[cfg(feature = "de")]
+    pub fn deserialize<'de, D>(d: D) -> Result<SystemTime, D::Error>
+        where D: Deserializer<'de>
+    {
+        d.deserialize_str(V)
+    }
+
+    #[cfg(feature = "de")]
+    struct V;
+
+    #[cfg(feature = "de")]
+    impl<'de> Visitor<'de> for V {
+        type Value = SystemTime;
+
+        fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+            fmt.write_str("an RFC 3339 timestamp")
+        }
+
+        fn visit_str<E>(self, v: &str) -> Result<SystemTime, E>
+            where E: Error
+        {
+            parse(&truncate_fraction(v)).map_err(|_| E::invalid_value(Unexpected::Str(v), &self))
+        }
+    }
+}
+
+#[cfg(feature = "de")]
+struct V;
+
+#[cfg(feature = "de")]
+impl<'de> Visitor<'de> for V {
+    type Value = SystemTime;
+
+    fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str("an RFC 3339 timestamp")
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<SystemTime, E>
+        where E: Error
+    {
+        parse(v).map_err(|_| E::invalid_value(Unexpected::Str(v), &self))
+    }
+}
+
+#[cfg(feature = "de")]
+pub(crate) fn parse(s: &str) -> Result<SystemTime, ::Error> {
+    let s = if s.ends_with("+00:00") || s.ends_with("-00:00") {
+        &s[..s.len() - 6]
+    } else {
+        s
+    };
+    match parse_fast(s.as_bytes()) {
+        Some(t) => Ok(t),
+        None => parse::parse_rfc3339_weak(s).or_else(|e| parse_basic(s.as_bytes()).ok_or(e)),
+    }
+}
+
+/// Removes fractional second digits beyond the ninth.
+#[cfg(feature = "de")]
+fn truncate_fraction(s: &str) -> Cow<'_, str> {
+    let start = match s.find('.') {
+        Some(idx) => idx + 1,
+        None => return Cow::Borrowed(s),
+    };
+    let len = s[start..].find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len() - start);
+    if len <= 9 {
+        return Cow::Borrowed(s);
+    }
+    Cow::Owned(format!("{}{}", &s[..start + 9], &s[start + len..]))
+}
+
+/// Parses an RFC 3339 timestamp with any UTC offset.
+#[cfg(feature = "de")]
+pub(crate) fn parse_with_offset(s: &str) -> Result<SystemTime, ::Error> {
+    match civil::split_offset(s) {
+        Some((rest, offset)) if offset != 0 => {
+            let dt = civil::parse_datetime(rest).ok_or(::ErrorKind::InvalidFormat)?;
+            let secs = civil::days_from_civil(dt.year, dt.month, dt.day) * civil::SECS_PER_DAY +
+                       (dt.hour * 3600 + dt.minute * 60 + dt.second) as i64 - offset as i64;
+            civil::from_unix(secs, dt.nanos).ok_or_else(|| ::ErrorKind::OutOfRange.into())
+        }
+        _ => parse(s),
+    }
+}
+
+/// Parses ISO 8601 basic format timestamps, `YYYYMMDDTHHMMSS[.f{1,9}]Z`, by
+/// adding the separators of the extended format and deferring to
+/// `parse_fast`.
+#[cfg(feature = "de")]
+fn parse_basic(b: &[u8]) -> Option<SystemTime> {
+    if b.len() < 16 || b.len() > 26 || b[8] != b'T' {
+        return None;
+    }
+
+    let mut buf = [0; 30];
+    buf[..4].copy_from_slice(&b[..4]);
+    buf[4] = b'-';
+    buf[5..7].copy_from_slice(&b[4..6]);
+    buf[7] = b'-';
+    buf[8..10].copy_from_slice(&b[6..8]);
+    buf[10] = b'T';
+    buf[11..13].copy_from_slice(&b[9..11]);
+    buf[13] = b':';
+    buf[14..16].copy_from_slice(&b[11..13]);
+    buf[16] = b':';
+    buf[17..b.len() + 4].copy_from_slice(&b[13..]);
+    parse_fast(&buf[..b.len() + 4])
+}
+
+/// Parses timestamps of the exact form `YYYY-MM-DDTHH:MM:SS[.f{1,9}]Z`.
+///
+/// This is the layout produced by nearly every RFC 3339 serializer, so it's
+/// checked without the generality of `parse_rfc3339_weak`. Anything else,
+/// including invalid dates in this layout, returns `None` and is left to the
+/// general parser.
+#[cfg(feature = "de")]
+fn parse_fast(b: &[u8]) -> Option<SystemTime> {
+    if b.len() < 20 || b.len() == 21 || b.len() > 30 || b[4] != b'-' || b[7] != b'-' ||
+       b[10] != b'T' || b[13] != b':' || b[16] != b':' || b[b.len() - 1] != b'Z' {
+        return None;
+    }
+
+    let year = digits(&b[0..4])? as i64;
+    let month = digits(&b[5..7])?;
+    let day = digits(&b[8..10])?;
+    let hour = digits(&b[11..13])?;
+    let minute = digits(&b[14..16])?;
+    let second = digits(&b[17..19])?;
+    if year < 1970 || month == 0 || month > 12 || day == 0 ||
+       day > civil::days_in_month(year, month) || hour > 23 || minute > 59 || second > 60 {
+        return None;
+    }
+
+    let mut nanos = 0;
+    if b.len() > 20 {
+        if b[19] != b'.' {
+            return None;
+        }
+        let fraction = &b[20..b.len() - 1];
+        nanos = digits(fraction)? * 10u32.pow(9 - fraction.len() as u32);
+    }
+
+    // leap seconds are folded into the preceding second
+    let secs = civil::days_from_civil(year, month, day) * civil::SECS_PER_DAY +
+               (hour * 3600 + minute * 60 + second.min(59)) as i64;
+    civil::from_unix(secs, nanos)
+}
+
+#[cfg(feature = "de")]
+fn digits(b: &[u8]) -> Option<u32> {
+    let mut value = 0;
+    for &b in b {
+        if !b.is_ascii_digit() {
+            return None;
+        }
+        value = value * 10 + (b - b'0') as u32;
+    }
+    Some(value)
+}
+
+#[cfg(all(test, feature = "ser", feature = "de"))]
+mod test {
+    use serde_json;
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    #[derive(Serialize, Deserialize)]
+    struct Foo {
+        #[serde(with = "super")]
+        z: SystemTime,
+        #[serde(with = "super::offset")]
+        offset: SystemTime,
+    }
+
+    #[test]
+    fn round_trip() {
+        let time = UNIX_EPOCH + Duration::new(1518563312, 123_000_000);
+        let foo = Foo { z: time, offset: time };
+        let json = serde_json::to_string(&foo).unwrap();
+        assert_eq!(json,
+                   r#"{"z":"2018-02-13T23:08:32.123000000Z","offset":"2018-02-13T23:08:32.123000000+00:00"}"#);
+        let foo = serde_json::from_str::<Foo>(&json).unwrap();
+        assert_eq!(foo.z, time);
+        assert_eq!(foo.offset, time);
+    }
+
+    #[test]
+    fn designators() {
+        let time = UNIX_EPOCH + Duration::from_secs(1518563312);
+        let json = r#"{"z":"2018-02-13T23:08:32+00:00","offset":"2018-02-13 23:08:32Z"}"#;
+        let foo = serde_json::from_str::<Foo>(json).unwrap();
+        assert_eq!(foo.z, time);
+        assert_eq!(foo.offset, time);
+
+        let json = r#"{"z":"2018-02-13T23:08:32+01:00","offset":"2018-02-13T23:08:32Z"}"#;
+        assert!(serde_json::from_str::<Foo>(json).is_err());
+    }
+
+    #[test]
+    fn sortable() {
+        #[derive(Serialize, Deserialize)]
+        struct Key(#[serde(with = "super::sortable")] SystemTime);
+
+        let times = [UNIX_EPOCH,
+                     UNIX_EPOCH + Duration::new(1518563312, 0),
+                     UNIX_EPOCH + Duration::new(1518563312, 1),
+                     UNIX_EPOCH + Duration::new(1518563312, 500_000_000),
+                     UNIX_EPOCH + Duration::new(1518563313, 0)];
+        let keys = times.iter().map(|&t| serde_json::to_string(&Key(t)).unwrap()).collect::<Vec<_>>();
+        assert_eq!(keys[1], r#""2018-02-13T23:08:32.000000000Z""#);
+        let mut sorted = keys.clone();
+        sorted.sort();
+        assert_eq!(sorted, keys);
+
+        for (key, &time) in keys.iter().zip(&times) {
+            assert_eq!(serde_json::from_str::<Key>(key).unwrap().0, time);
+        }
+    }
+
+    #[test]
+    fn fast_path() {
+        for s in &["2018-02-13T23:08:32Z", "2018-02-13T23:08:32.1Z", "2018-02-13T23:08:32.123456789Z",
+                   "2016-12-31T23:59:60Z", "2016-02-29T00:00:00Z", "2017-02-29T00:00:00Z",
+                   "1969-12-31T23:59:59Z", "9999-12-31T23:59:59Z", "2018-02-13T24:00:00Z",
+                   "2018-02-13T23:08:32.Z", "2018-02-13T23:08:32+0Z", "2018-13-13T23:08:32Z",
+                   "2018-02-13T23:08:3aZ", "2018-02-13T23:08:32.1234567891Z"] {
+            assert_eq!(super::parse(s).ok(), ::parse::parse_rfc3339_weak(s).ok(), "{}", s);
+        }
+        assert_eq!(super::parse_fast(b"2018-02-13T23:08:32.5Z"),
+                   Some(UNIX_EPOCH + Duration::new(1518563312, 500_000_000)));
+        assert_eq!(super::parse_fast(b"2018-02-13 23:08:32Z"), None);
+    }
+
+    #[test]
+    fn basic() {
+        assert_eq!(super::parse("20180213T230832Z").ok(),
+                   Some(UNIX_EPOCH + Duration::from_secs(1518563312)));
+        assert_eq!(super::parse("20180213T230832.5Z").ok(),
+                   Some(UNIX_EPOCH + Duration::new(1518563312, 500_000_000)));
+        for s in &["20180213T230832", "20180213 230832Z", "20181313T230832Z", "2018213T230832Z",
+                   "20180213T23083Z", "20180213T230832.1234567891Z", "2018-0213T230832Z"] {
+            assert!(super::parse(s).is_err(), "{}", s);
+        }
+    }
+
+    #[test]
+    fn lenient() {
+        #[derive(Serialize, Deserialize)]
+        struct Sample(#[serde(with = "super::lenient")] SystemTime);
+
+        let time = UNIX_EPOCH + Duration::new(1518563312, 123_456_789);
+        for s in &["2018-02-13T23:08:32.123456789Z", "2018-02-13T23:08:32.123456789999Z",
+                   "2018-02-13T23:08:32.123456789123456789+00:00", "2018-02-13 23:08:32.1234567899Z",
+                   "20180213T230832.1234567891Z"] {
+            assert_eq!(serde_json::from_str::<Sample>(&format!("\"{}\"", s)).unwrap().0, time, "{}", s);
+        }
+        assert_eq!(serde_json::to_string(&Sample(time)).unwrap(), r#""2018-02-13T23:08:32.123456789Z""#);
+        assert!(serde_json::from_str::<Sample>(r#""2018-02-13T23:08:32.1234567891Zx""#).is_err());
+    }
+
+    #[test]
+    fn far_future() {
+        let max = UNIX_EPOCH + Duration::new(253402300799, 999_999_999);
+        let foo = Foo { z: max, offset: max };
+        let json = serde_json::to_string(&foo).unwrap();
+        assert_eq!(json,
+                   r#"{"z":"9999-12-31T23:59:59.999999999Z","offset":"9999-12-31T23:59:59.999999999+00:00"}"#);
+        let foo = serde_json::from_str::<Foo>(&json).unwrap();
+        assert_eq!(foo.z, max);
+        assert_eq!(foo.offset, max);
+
+        let time = max + Duration::new(0, 1);
+        let err = serde_json::to_string(&Foo { z: time, offset: max }).unwrap_err();
+        assert_eq!(err.to_string(), "timestamp is after year 9999");
+        assert!(serde_json::to_string(&Foo { z: max, offset: time }).is_err());
+
+        let json = r#"{"z":"10000-01-01T00:00:00Z","offset":"9999-12-31T23:59:59Z"}"#;
+        assert!(serde_json::from_str::<Foo>(json).is_err());
+    }
+
+    #[test]
+    fn before_epoch() {
+        let time = UNIX_EPOCH - Duration::new(0, 1);
+        let err = serde_json::to_string(&Foo { z: time, offset: UNIX_EPOCH }).unwrap_err();
+        assert_eq!(err.to_string(), "timestamp is before 1970");
+        assert!(serde_json::to_string(&Foo { z: UNIX_EPOCH, offset: time }).is_err());
+
+        // `Value`'s serializer formats with `to_string`, which panics on errors
+        assert!(super::sortable::serialize(&time, serde_json::value::Serializer).is_err());
+        assert!(super::offset::serialize(&time, serde_json::value::Serializer).is_err());
+    }
+}
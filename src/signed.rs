@@ -0,0 +1,221 @@
+//! Durations which may be negative.
+#[cfg(feature = "de")]
+use serde::de::{Deserialize, Deserializer, Visitor, Error, Unexpected};
+#[cfg(feature = "ser")]
+use serde::ser::{Serialize, Serializer};
+use std::cmp::Ordering;
+#[cfg(feature = "de")]
+use std::error;
+#[cfg(any(feature = "ser", feature = "de"))]
+use std::fmt;
+use std::ops::Neg;
+#[cfg(feature = "de")]
+use std::str::FromStr;
+use std::time::Duration;
+#[cfg(feature = "systemtime")]
+use std::time::SystemTime;
+
+#[cfg(feature = "ser")]
+use format;
+#[cfg(feature = "de")]
+use parse;
+
+/// A duration which may be negative.
+///
+/// `Duration`s can't represent offsets like clock skew or how long before an
+/// event something is scheduled, which may go either way. A `SignedDuration`
+/// pairs a `Duration` with a sign, and is written as a duration string with
+/// an optional leading `-`, like `-30s`. It is serialized and deserialized as
+/// such a string.
+///
+/// # Examples
+///
+/// ```
+/// use serde_humantime::signed::SignedDuration;
+/// use std::time::{Duration, UNIX_EPOCH};
+///
+/// let lead = "-1h 30m".parse::<SignedDuration>().unwrap();
+/// assert!(lead.is_negative());
+/// assert_eq!(lead.abs(), Duration::from_secs(5400));
+///
+/// let start = UNIX_EPOCH + Duration::from_secs(86400);
+/// assert_eq!(lead.add_to(start), Some(UNIX_EPOCH + Duration::from_secs(81000)));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct SignedDuration {
+    negative: bool,
+    abs: Duration,
+}
+
+impl SignedDuration {
+    /// Creates a new `SignedDuration` from a sign and a magnitude.
+    ///
+    /// A zero duration is never negative.
+    pub fn new(negative: bool, abs: Duration) -> SignedDuration {
+        SignedDuration {
+            negative: negative && abs != Duration::from_secs(0),
+            abs,
+        }
+    }
+
+    /// Returns `true` if the duration is less than zero.
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    /// Returns the magnitude of the duration.
+    pub fn abs(&self) -> Duration {
+        self.abs
+    }
+
+    /// Returns the time this duration after `t`, or before it if the
+    /// duration is negative.
+    ///
+    /// Returns `None` if the result can't be represented.
+    #[cfg(feature = "systemtime")]
+    pub fn add_to(&self, t: SystemTime) -> Option<SystemTime> {
+        if self.negative {
+            t.checked_sub(self.abs)
+        } else {
+            t.checked_add(self.abs)
+        }
+    }
+}
+
+impl From<Duration> for SignedDuration {
+    fn from(d: Duration) -> SignedDuration {
+        SignedDuration::new(false, d)
+    }
+}
+
+impl Neg for SignedDuration {
+    type Output = SignedDuration;
+
+    fn neg(self) -> SignedDuration {
+        SignedDuration::new(!self.negative, self.abs)
+    }
+}
+
+impl PartialOrd for SignedDuration {
+    fn partial_cmp(&self, other: &SignedDuration) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for SignedDuration {
+    fn cmp(&self, other: &SignedDuration) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, false) => self.abs.cmp(&other.abs),
+            (true, true) => other.abs.cmp(&self.abs),
+            (negative, _) => other.negative.cmp(&negative),
+        }
+    }
+}
+
+#[cfg(feature = "ser")]
+impl fmt::Display for SignedDuration {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        if self.negative {
+            fmt.write_str("-")?;
+        }
+        fmt::Display::fmt(&format::format_duration(self.abs), fmt)
+    }
+}
+
+/// An error parsing a `SignedDuration`.
+#[derive(Debug)]
+#[cfg(feature = "de")]
+pub struct ParseSignedDurationError(());
+
+#[cfg(feature = "de")]
+impl fmt::Display for ParseSignedDurationError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str("invalid signed duration")
+    }
+}
+
+#[cfg(feature = "de")]
+impl error::Error for ParseSignedDurationError {}
+
+#[cfg(feature = "de")]
+impl FromStr for SignedDuration {
+    type Err = ParseSignedDurationError;
+
+    fn from_str(s: &str) -> Result<SignedDuration, ParseSignedDurationError> {
+        let (negative, s) = match s.strip_prefix('-') {
+            Some(s) => (true, s),
+            None => (false, s),
+        };
+        parse::parse_duration(s)
+            .map(|d| SignedDuration::new(negative, d))
+            .map_err(|_| ParseSignedDurationError(()))
+    }
+}
+
+#[cfg(feature = "ser")]
+impl Serialize for SignedDuration {
+    fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        s.collect_str(self)
+    }
+}
+
+#[cfg(feature = "de")]
+impl<'de> Deserialize<'de> for SignedDuration {
+    fn deserialize<D>(d: D) -> Result<SignedDuration, D::Error>
+        where D: Deserializer<'de>
+    {
+        struct V;
+
+        impl<'de2> Visitor<'de2> for V {
+            type Value = SignedDuration;
+
+            fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+                fmt.write_str("a signed duration")
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<SignedDuration, E>
+                where E: Error
+            {
+                v.parse().map_err(|_| E::invalid_value(Unexpected::Str(v), &self))
+            }
+        }
+
+        d.deserialize_str(V)
+    }
+}
+
+#[cfg(all(test, feature = "ser", feature = "de"))]
+mod test {
+    use serde_json;
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        for &(json, negative, secs) in &[(r#""-30s""#, true, 30), (r#""1h 30m""#, false, 5400), (r#""0s""#, false, 0)] {
+            let d = serde_json::from_str::<SignedDuration>(json).unwrap();
+            assert_eq!(d, SignedDuration::new(negative, Duration::from_secs(secs)), "{}", json);
+            assert_eq!(serde_json::to_string(&d).unwrap(), json);
+        }
+        assert_eq!(serde_json::from_str::<SignedDuration>(r#""-0s""#).unwrap(), SignedDuration::default());
+
+        for json in &[r#""""#, r#""-""#, r#""--1s""#, r#""+1s""#, "-30"] {
+            assert!(serde_json::from_str::<SignedDuration>(json).is_err(), "{}", json);
+        }
+    }
+
+    #[test]
+    fn ordering() {
+        let mut ds = ["1s", "-2s", "0s", "-1s", "2s"]
+            .iter()
+            .map(|s| s.parse::<SignedDuration>().unwrap())
+            .collect::<Vec<_>>();
+        ds.sort();
+        let ds = ds.iter().map(ToString::to_string).collect::<Vec<_>>();
+        assert_eq!(ds, ["-2s", "-1s", "0s", "1s", "2s"]);
+        assert_eq!(-SignedDuration::from(Duration::from_secs(1)), "-1s".parse().unwrap());
+    }
+}
@@ -0,0 +1,281 @@
+//! Serialization of `Duration`s as `{ "value": 5, "unit": "s" }` objects.
+//!
+//! On output, the largest unit which represents the duration exactly is
+//! chosen. On input, the value may be fractional. The supported units are
+//! `ns`, `us`, `ms`, `s`, `m`, `h`, and `d`.
+//!
+//! # Examples
+//!
+//! ```
+//! extern crate serde_humantime;
+//! extern crate serde;
+//! #[macro_use]
+//! extern crate serde_derive;
+//!
+//! use std::time::Duration;
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Foo {
+//!     #[serde(with = "serde_humantime::structured")]
+//!     timeout: Duration,
+//! }
+//!
+//! # fn main() {}
+//! ```
+#[cfg(feature = "de")]
+use serde::de::{self, Deserialize, Deserializer, MapAccess, Visitor, Unexpected};
+#[cfg(feature = "ser")]
+use serde::ser::{self, Serializer, SerializeStruct};
+#[cfg(feature = "de")]
+use std::fmt;
+use std::time::Duration;
+
+#[cfg(feature = "de")]
+const FIELDS: &[&str] = &["value", "unit"];
+
+// ordered from largest to smallest
+const UNITS: &[(&str, u128)] = &[("d", 86_400_000_000_000),
+                                 ("h", 3_600_000_000_000),
+                                 ("m", 60_000_000_000),
+                                 ("s", 1_000_000_000),
+                                 ("ms", 1_000_000),
+                                 ("us", 1_000),
+                                 ("ns", 1)];
+
+/// Serializes a `Duration` as a value and unit pair.
+#[cfg(feature = "ser")]
+pub fn serialize<S>(d: &Duration, s: S) -> Result<S::Ok, S::Error>
+    where S: Serializer
+{
+    let nanos = d.as_nanos();
+    let &(unit, per) = UNITS
+        .iter()
+        .find(|&&(_, per)| nanos != 0 && nanos.is_multiple_of(per))
+        .unwrap_or(&("s", 1_000_000_000));
+    let value = nanos / per;
+    if value > u64::MAX as u128 {
+        return Err(ser::Error::custom("duration too large to represent"));
+    }
+
+    let mut s = s.serialize_struct("Duration", 2)?;
+    s.serialize_field("value", &(value as u64))?;
+    s.serialize_field("unit", unit)?;
+    s.end()
+}
+
+/// Deserializes a `Duration` from a value and unit pair.
+#[cfg(feature = "de")]
+pub fn deserialize<'de, D>(d: D) -> Result<Duration, D::Error>
+    where D: Deserializer<'de>
+{
+    d.deserialize_struct("Duration", FIELDS, V)
+}
+
+#[cfg(feature = "de")]
+struct V;
+
+#[cfg(feature = "de")]
+impl<'de> Visitor<'de> for V {
+    type Value = Duration;
+
+    fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str("a duration value and unit")
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Duration, A::Error>
+        where A: MapAccess<'de>
+    {
+        let mut value = None;
+        let mut unit = None;
+        while let Some(key) = map.next_key::<Field>()? {
+            match key {
+                Field::Value => {
+                    if value.is_some() {
+                        return Err(de::Error::duplicate_field("value"));
+                    }
+                    value = Some(map.next_value::<Number>()?);
+                }
+                Field::Unit => {
+                    if unit.is_some() {
+                        return Err(de::Error::duplicate_field("unit"));
+                    }
+                    unit = Some(map.next_value::<Unit>()?);
+                }
+            }
+        }
+        let value = value.ok_or_else(|| de::Error::missing_field("value"))?;
+        let Unit(per) = unit.ok_or_else(|| de::Error::missing_field("unit"))?;
+
+        match value {
+            Number::Int(v) => {
+                let nanos = v as u128 * per;
+                let secs = nanos / 1_000_000_000;
+                if secs > u64::MAX as u128 {
+                    return Err(de::Error::invalid_value(Unexpected::Unsigned(v), &self));
+                }
+                Ok(Duration::new(secs as u64, (nanos % 1_000_000_000) as u32))
+            }
+            Number::Float(v) => {
+                Duration::try_from_secs_f64(v * per as f64 / 1e9)
+                    .map_err(|_| de::Error::invalid_value(Unexpected::Float(v), &self))
+            }
+        }
+    }
+}
+
+#[cfg(feature = "de")]
+enum Field {
+    Value,
+    Unit,
+}
+
+#[cfg(feature = "de")]
+impl<'de> Deserialize<'de> for Field {
+    fn deserialize<D>(d: D) -> Result<Field, D::Error>
+        where D: Deserializer<'de>
+    {
+        struct FieldV;
+
+        impl<'de2> Visitor<'de2> for FieldV {
+            type Value = Field;
+
+            fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+                fmt.write_str("`value` or `unit`")
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Field, E>
+                where E: de::Error
+            {
+                match v {
+                    "value" => Ok(Field::Value),
+                    "unit" => Ok(Field::Unit),
+                    _ => Err(E::unknown_field(v, FIELDS)),
+                }
+            }
+        }
+
+        d.deserialize_identifier(FieldV)
+    }
+}
+
+#[cfg(feature = "de")]
+enum Number {
+    Int(u64),
+    Float(f64),
+}
+
+#[cfg(feature = "de")]
+impl<'de> Deserialize<'de> for Number {
+    fn deserialize<D>(d: D) -> Result<Number, D::Error>
+        where D: Deserializer<'de>
+    {
+        struct NumberV;
+
+        impl<'de2> Visitor<'de2> for NumberV {
+            type Value = Number;
+
+            fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+                fmt.write_str("a non-negative number")
+            }
+
+            fn visit_u64<E>(self, v: u64) -> Result<Number, E>
+                where E: de::Error
+            {
+                Ok(Number::Int(v))
+            }
+
+            fn visit_i64<E>(self, v: i64) -> Result<Number, E>
+                where E: de::Error
+            {
+                if v < 0 {
+                    return Err(E::invalid_value(Unexpected::Signed(v), &self));
+                }
+                Ok(Number::Int(v as u64))
+            }
+
+            fn visit_f64<E>(self, v: f64) -> Result<Number, E>
+                where E: de::Error
+            {
+                Ok(Number::Float(v))
+            }
+        }
+
+        d.deserialize_any(NumberV)
+    }
+}
+
+#[cfg(feature = "de")]
+struct Unit(u128);
+
+#[cfg(feature = "de")]
+impl<'de> Deserialize<'de> for Unit {
+    fn deserialize<D>(d: D) -> Result<Unit, D::Error>
+        where D: Deserializer<'de>
+    {
+        struct UnitV;
+
+        impl<'de2> Visitor<'de2> for UnitV {
+            type Value = Unit;
+
+            fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+                fmt.write_str("a time unit")
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Unit, E>
+                where E: de::Error
+            {
+                UNITS
+                    .iter()
+                    .find(|&&(name, _)| name == v)
+                    .map(|&(_, per)| Unit(per))
+                    .ok_or_else(|| E::invalid_value(Unexpected::Str(v), &self))
+            }
+        }
+
+        d.deserialize_str(UnitV)
+    }
+}
+
+#[cfg(all(test, feature = "ser", feature = "de"))]
+mod test {
+    use serde_json;
+    use std::time::Duration;
+
+    #[derive(Serialize, Deserialize)]
+    struct Foo {
+        #[serde(with = "super")]
+        time: Duration,
+    }
+
+    #[test]
+    fn round_trip() {
+        let cases = [(Duration::from_secs(0), r#"{"value":0,"unit":"s"}"#),
+                     (Duration::from_secs(5), r#"{"value":5,"unit":"s"}"#),
+                     (Duration::from_secs(7200), r#"{"value":2,"unit":"h"}"#),
+                     (Duration::from_secs(90), r#"{"value":90,"unit":"s"}"#),
+                     (Duration::new(1, 500_000_000), r#"{"value":1500,"unit":"ms"}"#),
+                     (Duration::new(0, 7), r#"{"value":7,"unit":"ns"}"#)];
+        for &(time, expected) in &cases {
+            let json = serde_json::to_string(&Foo { time }).unwrap();
+            assert_eq!(json, format!(r#"{{"time":{}}}"#, expected));
+            let foo = serde_json::from_str::<Foo>(&json).unwrap();
+            assert_eq!(foo.time, time);
+        }
+    }
+
+    #[test]
+    fn fractional() {
+        let json = r#"{"time":{"unit":"h","value":1.5}}"#;
+        let foo = serde_json::from_str::<Foo>(json).unwrap();
+        assert_eq!(foo.time, Duration::from_secs(5400));
+    }
+
+    #[test]
+    fn invalid() {
+        for s in &[r#"{"value":5}"#, r#"{"value":5,"unit":"fortnight"}"#,
+                   r#"{"value":-5,"unit":"s"}"#, r#"{"value":5,"unit":"s","extra":1}"#] {
+            let json = format!(r#"{{"time":{}}}"#, s);
+            assert!(serde_json::from_str::<Foo>(&json).is_err(), "{}", s);
+        }
+    }
+}
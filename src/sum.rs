@@ -0,0 +1,113 @@
+//! Serialization of `Duration`s as a list of components to be summed.
+//!
+//! Durations are deserialized from a sequence of duration strings like
+//! `["1h", "30m", "15s"]`, which are added together. They are serialized as a
+//! sequence of the components of their formatted representation, so the
+//! duration above becomes `["1h", "30m", "15s"]` again.
+//!
+//! # Examples
+//!
+//! ```
+//! extern crate serde_humantime;
+//! extern crate serde;
+//! #[macro_use]
+//! extern crate serde_derive;
+//!
+//! use std::time::Duration;
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Config {
+//!     #[serde(with = "serde_humantime::sum")]
+//!     timeout: Duration,
+//! }
+//!
+//! # fn main() {}
+//! ```
+#[cfg(feature = "de")]
+use serde::de::{Deserializer, Visitor, SeqAccess, Error};
+#[cfg(feature = "ser")]
+use serde::ser::{Serializer, SerializeSeq};
+#[cfg(feature = "de")]
+use std::fmt;
+use std::time::Duration;
+
+#[cfg(feature = "ser")]
+use format;
+#[cfg(feature = "de")]
+use De;
+
+/// Serializes a `Duration` as a sequence of its components.
+#[cfg(feature = "ser")]
+pub fn serialize<S>(d: &Duration, s: S) -> Result<S::Ok, S::Error>
+    where S: Serializer
+{
+    let formatted = format::format_duration(*d).to_string();
+    let mut seq = s.serialize_seq(Some(formatted.split(' ').count()))?;
+    for component in formatted.split(' ') {
+        seq.serialize_element(component)?;
+    }
+    seq.end()
+}
+
+/// Deserializes a `Duration` as the sum of a sequence of durations.
+#[cfg(feature = "de")]
+pub fn deserialize<'de, D>(d: D) -> Result<Duration, D::Error>
+    where D: Deserializer<'de>
+{
+    d.deserialize_seq(V)
+}
+
+#[cfg(feature = "de")]
+struct V;
+
+#[cfg(feature = "de")]
+impl<'de> Visitor<'de> for V {
+    type Value = Duration;
+
+    fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str("a sequence of durations")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Duration, A::Error>
+        where A: SeqAccess<'de>
+    {
+        let mut sum = Duration::from_secs(0);
+        while let Some(d) = seq.next_element::<De<Duration>>()? {
+            sum = sum.checked_add(d.into_inner())
+                .ok_or_else(|| A::Error::custom("sum of durations overflowed"))?;
+        }
+        Ok(sum)
+    }
+}
+
+#[cfg(all(test, feature = "ser", feature = "de"))]
+mod test {
+    use serde_json;
+    use std::time::Duration;
+
+    #[derive(Serialize, Deserialize)]
+    struct Foo {
+        #[serde(with = "super")]
+        time: Duration,
+    }
+
+    #[test]
+    fn round_trip() {
+        let foo = serde_json::from_str::<Foo>(r#"{"time": ["1h", "30m", "15s", "1h"]}"#).unwrap();
+        assert_eq!(foo.time, Duration::from_secs(9015));
+        assert_eq!(serde_json::to_string(&foo).unwrap(), r#"{"time":["2h","30m","15s"]}"#);
+
+        let foo = serde_json::from_str::<Foo>(r#"{"time": []}"#).unwrap();
+        assert_eq!(foo.time, Duration::from_secs(0));
+        assert_eq!(serde_json::to_string(&foo).unwrap(), r#"{"time":["0s"]}"#);
+    }
+
+    #[test]
+    fn invalid() {
+        for json in &[r#"{"time": "1h"}"#,
+                      r#"{"time": ["1h", "bogus"]}"#,
+                      r#"{"time": ["18446744073709551615s", "1s"]}"#] {
+            assert!(serde_json::from_str::<Foo>(json).is_err(), "{}", json);
+        }
+    }
+}
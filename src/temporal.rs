@@ -0,0 +1,233 @@
+//! Validation of `SystemTime`s against the current time.
+//!
+//! The `must_be_future` and `must_be_past` modules deserialize RFC 3339
+//! timestamps like the crate root, but reject those on the wrong side of the
+//! current time, as for expiry dates and birth dates respectively. Their
+//! `serialize` and `deserialize` functions can be used with `serde_derive`'s
+//! `with` annotations.
+//!
+//! Timestamps are compared against the system clock with no tolerance. The
+//! `deserialize_with` functions take a tolerance and a `Clock` instead, and
+//! can be used with `deserialize_with` from a small wrapper function.
+//!
+//! # Examples
+//!
+//! ```
+//! extern crate serde_humantime;
+//! extern crate serde;
+//! #[macro_use]
+//! extern crate serde_derive;
+//!
+//! use serde::Deserializer;
+//! use serde_humantime::clock::SystemClock;
+//! use serde_humantime::temporal::must_be_future;
+//! use std::time::{Duration, SystemTime};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Token {
+//!     #[serde(with = "serde_humantime::temporal::must_be_future")]
+//!     expires: SystemTime,
+//!     #[serde(serialize_with = "serde_humantime::serialize",
+//!             deserialize_with = "past_with_skew")]
+//!     issued: SystemTime,
+//! }
+//!
+//! fn past_with_skew<'de, D>(d: D) -> Result<SystemTime, D::Error>
+//!     where D: Deserializer<'de>
+//! {
+//!     // allow for a minute of clock skew
+//!     serde_humantime::temporal::must_be_past::deserialize_with(
+//!         d, Duration::from_secs(60), &SystemClock)
+//! }
+//!
+//! # fn main() {}
+//! ```
+#[cfg(feature = "de")]
+use serde::de::{Deserializer, Visitor, Error};
+#[cfg(feature = "de")]
+use std::fmt;
+#[cfg(feature = "de")]
+use std::time::SystemTime;
+
+#[cfg(feature = "de")]
+use clock::Clock;
+#[cfg(feature = "de")]
+use marker::invalid_value;
+#[cfg(feature = "de")]
+use ParseOptions;
+
+/// Serialization of timestamps which must be in the future.
+pub mod must_be_future {
+    #[cfg(feature = "de")]
+    use serde::de::Deserializer;
+    #[cfg(feature = "ser")]
+    use serde::ser::Serializer;
+    use std::time::SystemTime;
+    #[cfg(feature = "de")]
+    use std::time::Duration;
+
+    #[cfg(feature = "de")]
+    use clock::{Clock, SystemClock};
+    #[cfg(feature = "de")]
+    use ParseOptions;
+
+    /// Serializes a `SystemTime` as an RFC 3339 timestamp.
+    #[cfg(feature = "ser")]
+    pub fn serialize<S>(t: &SystemTime, s: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        ::serialize(t, s)
+    }
+
+    /// Deserializes an RFC 3339 timestamp, rejecting those not after the
+    /// current time of the system clock.
+    #[cfg(feature = "de")]
+    pub fn deserialize<'de, D>(d: D) -> Result<SystemTime, D::Error>
+        where D: Deserializer<'de>
+    {
+        deserialize_with(d, Duration::from_secs(0), &SystemClock)
+    }
+
+    /// Deserializes an RFC 3339 timestamp, rejecting those more than
+    /// `tolerance` before the current time of a clock.
+    #[cfg(feature = "de")]
+    pub fn deserialize_with<'de, D, C>(d: D, tolerance: Duration, clock: &C) -> Result<SystemTime, D::Error>
+        where D: Deserializer<'de>,
+              C: Clock + ?Sized
+    {
+        let options = ParseOptions::new().max_behind(tolerance);
+        super::deserialize(d, options, clock, "a timestamp in the future")
+    }
+}
+
+/// Serialization of timestamps which must be in the past.
+pub mod must_be_past {
+    #[cfg(feature = "de")]
+    use serde::de::Deserializer;
+    #[cfg(feature = "ser")]
+    use serde::ser::Serializer;
+    use std::time::SystemTime;
+    #[cfg(feature = "de")]
+    use std::time::Duration;
+
+    #[cfg(feature = "de")]
+    use clock::{Clock, SystemClock};
+    #[cfg(feature = "de")]
+    use ParseOptions;
+
+    /// Serializes a `SystemTime` as an RFC 3339 timestamp.
+    #[cfg(feature = "ser")]
+    pub fn serialize<S>(t: &SystemTime, s: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        ::serialize(t, s)
+    }
+
+    /// Deserializes an RFC 3339 timestamp, rejecting those after the current
+    /// time of the system clock.
+    #[cfg(feature = "de")]
+    pub fn deserialize<'de, D>(d: D) -> Result<SystemTime, D::Error>
+        where D: Deserializer<'de>
+    {
+        deserialize_with(d, Duration::from_secs(0), &SystemClock)
+    }
+
+    /// Deserializes an RFC 3339 timestamp, rejecting those more than
+    /// `tolerance` after the current time of a clock.
+    #[cfg(feature = "de")]
+    pub fn deserialize_with<'de, D, C>(d: D, tolerance: Duration, clock: &C) -> Result<SystemTime, D::Error>
+        where D: Deserializer<'de>,
+              C: Clock + ?Sized
+    {
+        let options = ParseOptions::new().max_ahead(tolerance);
+        super::deserialize(d, options, clock, "a timestamp in the past")
+    }
+}
+
+#[cfg(feature = "de")]
+fn deserialize<'de, D, C>(d: D,
+                          options: ParseOptions,
+                          clock: &C,
+                          expecting: &'static str)
+                          -> Result<SystemTime, D::Error>
+    where D: Deserializer<'de>,
+          C: Clock + ?Sized
+{
+    d.deserialize_str(V { options, clock, expecting })
+}
+
+#[cfg(feature = "de")]
+struct V<'a, C: ?Sized + 'a> {
+    options: ParseOptions,
+    clock: &'a C,
+    expecting: &'static str,
+}
+
+#[cfg(feature = "de")]
+impl<'de, 'a, C> Visitor<'de> for V<'a, C>
+    where C: Clock + ?Sized
+{
+    type Value = SystemTime;
+
+    fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str(self.expecting)
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<SystemTime, E>
+        where E: Error
+    {
+        self.options
+            .parse_timestamp_with_clock(v, self.clock)
+            .map_err(|e| invalid_value(e, v, &self))
+    }
+}
+
+#[cfg(all(test, feature = "ser", feature = "de"))]
+mod test {
+    use serde::de::IntoDeserializer;
+    use serde::de::value::{Error, StrDeserializer};
+    use serde_json;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use clock::FixedClock;
+    use super::*;
+
+    fn de(s: &str) -> StrDeserializer<'_, Error> {
+        s.into_deserializer()
+    }
+
+    #[test]
+    fn future() {
+        let clock = FixedClock::new(UNIX_EPOCH + Duration::from_secs(1518563312));
+        let tolerance = Duration::from_secs(60);
+
+        let t = must_be_future::deserialize_with(de("2018-02-14T00:00:00Z"), tolerance, &clock);
+        assert_eq!(t.unwrap(), UNIX_EPOCH + Duration::from_secs(1518566400));
+        assert!(must_be_future::deserialize_with(de("2018-02-13T23:08:00Z"), tolerance, &clock)
+                    .is_ok());
+        let err = must_be_future::deserialize_with(de("2018-02-13T23:07:00Z"), tolerance, &clock)
+            .unwrap_err();
+        assert_eq!(err.to_string(),
+                   "invalid value: string \"2018-02-13T23:07:00Z\", expected a timestamp in the \
+                    future");
+
+        let mut de = serde_json::Deserializer::from_str("\"2000-01-01T00:00:00Z\"");
+        assert!(must_be_future::deserialize(&mut de).is_err());
+    }
+
+    #[test]
+    fn past() {
+        let clock = FixedClock::new(UNIX_EPOCH + Duration::from_secs(1518563312));
+        let tolerance = Duration::from_secs(60);
+
+        assert!(must_be_past::deserialize_with(de("1990-05-17T00:00:00Z"), tolerance, &clock)
+                    .is_ok());
+        assert!(must_be_past::deserialize_with(de("2018-02-13T23:09:00Z"), tolerance, &clock)
+                    .is_ok());
+        assert!(must_be_past::deserialize_with(de("2018-02-13T23:10:00Z"), tolerance, &clock)
+                    .is_err());
+
+        let mut de = serde_json::Deserializer::from_str("\"2000-01-01T00:00:00Z\"");
+        assert!(must_be_past::deserialize(&mut de).is_ok());
+    }
+}
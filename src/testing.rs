@@ -0,0 +1,101 @@
+//! Utilities for testing code which uses this crate.
+//!
+//! The `assert_roundtrip!` macro serializes a value through the crate root or
+//! one of its `with` modules, and asserts that it deserializes back to the
+//! same value. Values pass through `serde_json::Value`s, so the format is
+//! exercised without being tied to the syntax of a particular format.
+//!
+//! # Examples
+//!
+//! ```
+//! #[macro_use]
+//! extern crate serde_humantime;
+//!
+//! use std::time::{Duration, UNIX_EPOCH};
+//!
+//! # fn main() {
+//! assert_roundtrip!(Duration::new(5400, 5));
+//! assert_roundtrip!(Some(UNIX_EPOCH + Duration::from_secs(1518563312)));
+//! assert_roundtrip!(Duration::from_secs(90), serde_humantime::iso8601);
+//! # }
+//! ```
+use serde_json::value::{Serializer, Value};
+use serde_json::Error;
+use std::fmt;
+
+/// Asserts that a value round-trips through this crate's serialization.
+///
+/// With one argument, the value is serialized through the crate root, and
+/// must be a `Duration`, `SystemTime` or `Option` of either. A second
+/// argument names a module with `serialize` and `deserialize` functions to
+/// use instead, such as `serde_humantime::rfc3339`.
+///
+/// See the `testing` module for an example.
+#[macro_export]
+macro_rules! assert_roundtrip {
+    ($value:expr) => {
+        $crate::testing::assert_roundtrip(&$value, $crate::serialize, $crate::deserialize)
+    };
+    ($value:expr, $($module:ident)::+) => {
+        $crate::testing::assert_roundtrip(&$value,
+                                          $($module)::+::serialize,
+                                          $($module)::+::deserialize)
+    };
+}
+
+/// Asserts that a value serialized by one function deserializes back to the
+/// same value with another.
+///
+/// This is the function behind `assert_roundtrip!`, for use with serialize
+/// and deserialize functions which aren't in a module of their own.
+///
+/// # Panics
+///
+/// Panics if serialization or deserialization fails, or if the deserialized
+/// value doesn't equal the original.
+pub fn assert_roundtrip<T, S, D>(value: &T, serialize: S, deserialize: D)
+    where T: PartialEq + fmt::Debug,
+          S: FnOnce(&T, Serializer) -> Result<Value, Error>,
+          D: FnOnce(Value) -> Result<T, Error>
+{
+    let serialized = match serialize(value, Serializer) {
+        Ok(serialized) => serialized,
+        Err(e) => panic!("failed to serialize {:?}: {}", value, e),
+    };
+    match deserialize(serialized.clone()) {
+        Ok(ref deserialized) if deserialized == value => {}
+        Ok(deserialized) => {
+            panic!("{:?} serialized as {} but deserialized as {:?}", value, serialized, deserialized)
+        }
+        Err(e) => panic!("failed to deserialize {} serialized from {:?}: {}", serialized, value, e),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+    #[cfg(feature = "systemtime")]
+    use std::time::UNIX_EPOCH;
+
+    use iso8601;
+    #[cfg(feature = "systemtime")]
+    use rfc3339;
+
+    #[test]
+    fn roundtrip() {
+        assert_roundtrip!(Duration::new(90, 5));
+        assert_roundtrip!(None::<Duration>);
+        assert_roundtrip!(Duration::from_secs(90), iso8601);
+        assert_roundtrip!(Duration::from_secs(90), iso8601::weeks);
+        #[cfg(feature = "systemtime")]
+        assert_roundtrip!(UNIX_EPOCH + Duration::new(1518563312, 5), rfc3339);
+    }
+
+    #[test]
+    #[should_panic(expected = "serialized as \"1m 30s 1ns\" but deserialized as 90s")]
+    fn lossy() {
+        super::assert_roundtrip(&Duration::new(90, 1), ::serialize, |v| {
+            ::deserialize::<Duration, _>(v).map(|d| Duration::from_secs(d.as_secs()))
+        });
+    }
+}
@@ -0,0 +1,260 @@
+//! Serialization of `time` crate types in the format of the crate root.
+//!
+//! This module is only available with the `time03` feature. Its
+//! `offset_date_time` submodule serializes `OffsetDateTime`s like
+//! `SystemTime`s, as RFC 3339 timestamps in UTC, and its `duration` submodule
+//! serializes `time::Duration`s like `Duration`s, with a leading `-` for
+//! negative durations.
+//!
+//! Every `TimestampFormat` is also a `Format<OffsetDateTime>` and every
+//! `DurationFormat` a `Format<time::Duration>`, so `De<OffsetDateTime>` and
+//! `De<time::Duration, Iso8601>` work like their `std::time` counterparts.
+//! `time::Duration`s are always serialized as strings, even in formats which
+//! aren't human readable.
+//!
+//! # Examples
+//!
+//! ```
+//! extern crate serde_humantime;
+//! extern crate serde;
+//! #[macro_use]
+//! extern crate serde_derive;
+//! extern crate time;
+//!
+//! use serde_humantime::De;
+//! use time::OffsetDateTime;
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Job {
+//!     #[serde(with = "serde_humantime::time_compat::offset_date_time")]
+//!     started: OffsetDateTime,
+//!     #[serde(with = "serde_humantime::time_compat::duration")]
+//!     skew: time::Duration,
+//!     deadline: Option<De<OffsetDateTime>>,
+//! }
+//!
+//! # fn main() {}
+//! ```
+#[cfg(feature = "de")]
+use serde::de::{Deserializer, Visitor, Error, Unexpected};
+#[cfg(feature = "ser")]
+use serde::ser::Serializer;
+#[cfg(all(feature = "ser", feature = "systemtime"))]
+use serde::ser;
+#[cfg(feature = "de")]
+use std::convert::TryFrom;
+#[cfg(feature = "de")]
+use std::fmt;
+#[cfg(any(feature = "ser", feature = "de"))]
+use std::marker::PhantomData;
+#[cfg(all(feature = "systemtime", any(feature = "ser", feature = "de")))]
+use std::time::{SystemTime, UNIX_EPOCH};
+use time;
+#[cfg(feature = "systemtime")]
+use time::OffsetDateTime;
+
+use marker::{DurationFormat, Format};
+#[cfg(any(feature = "ser", feature = "de"))]
+use marker;
+#[cfg(feature = "systemtime")]
+use marker::TimestampFormat;
+
+/// Serialization of `OffsetDateTime`s as RFC 3339 timestamps in UTC.
+#[cfg(all(feature = "systemtime", any(feature = "ser", feature = "de")))]
+pub mod offset_date_time {
+    #[cfg(feature = "de")]
+    use serde::de::Deserializer;
+    #[cfg(feature = "ser")]
+    use serde::ser::Serializer;
+    use time::OffsetDateTime;
+
+    #[cfg(any(feature = "ser", feature = "de"))]
+    use marker::{Format, Human};
+
+    /// Serializes an `OffsetDateTime` as an RFC 3339 timestamp in UTC.
+    #[cfg(feature = "ser")]
+    pub fn serialize<S>(t: &OffsetDateTime, s: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        <Human as Format<OffsetDateTime>>::serialize(t, s)
+    }
+
+    /// Deserializes an `OffsetDateTime` in UTC from an RFC 3339 timestamp.
+    #[cfg(feature = "de")]
+    pub fn deserialize<'de, D>(d: D) -> Result<OffsetDateTime, D::Error>
+        where D: Deserializer<'de>
+    {
+        <Human as Format<OffsetDateTime>>::deserialize(d)
+    }
+}
+
+/// Serialization of `time::Duration`s as duration strings.
+#[cfg(any(feature = "ser", feature = "de"))]
+pub mod duration {
+    #[cfg(feature = "de")]
+    use serde::de::Deserializer;
+    #[cfg(feature = "ser")]
+    use serde::ser::Serializer;
+    use time;
+
+    #[cfg(any(feature = "ser", feature = "de"))]
+    use marker::{Format, Human};
+
+    /// Serializes a `time::Duration` as a duration string, prefixed with `-`
+    /// if it is negative.
+    #[cfg(feature = "ser")]
+    pub fn serialize<S>(d: &time::Duration, s: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        <Human as Format<time::Duration>>::serialize(d, s)
+    }
+
+    /// Deserializes a `time::Duration` from a duration string, optionally
+    /// prefixed with `-`.
+    #[cfg(feature = "de")]
+    pub fn deserialize<'de, D>(d: D) -> Result<time::Duration, D::Error>
+        where D: Deserializer<'de>
+    {
+        <Human as Format<time::Duration>>::deserialize(d)
+    }
+}
+
+#[cfg(feature = "systemtime")]
+impl<F> Format<OffsetDateTime> for F
+    where F: TimestampFormat
+{
+    #[cfg(feature = "ser")]
+    fn serialize<S>(value: &OffsetDateTime, s: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        let d = *value - OffsetDateTime::UNIX_EPOCH;
+        let t = if d.is_negative() {
+            UNIX_EPOCH.checked_sub(d.unsigned_abs())
+        } else {
+            UNIX_EPOCH.checked_add(d.unsigned_abs())
+        };
+        let t = t.ok_or_else(|| ser::Error::custom("timestamp is out of range"))?;
+        <F as Format<SystemTime>>::serialize(&t, s)
+    }
+
+    #[cfg(feature = "de")]
+    fn deserialize<'de, D>(d: D) -> Result<OffsetDateTime, D::Error>
+        where D: Deserializer<'de>
+    {
+        let t = <F as Format<SystemTime>>::deserialize(d)?;
+        let d = match t.duration_since(UNIX_EPOCH) {
+            Ok(d) => time::Duration::try_from(d).ok(),
+            Err(e) => time::Duration::try_from(e.duration()).ok().map(|d| -d),
+        };
+        d.and_then(|d| OffsetDateTime::UNIX_EPOCH.checked_add(d))
+            .ok_or_else(|| D::Error::custom("timestamp is out of range"))
+    }
+}
+
+impl<F> Format<time::Duration> for F
+    where F: DurationFormat
+{
+    #[cfg(feature = "ser")]
+    fn serialize<S>(value: &time::Duration, s: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        let sign = if value.is_negative() { "-" } else { "" };
+        let d = value.unsigned_abs();
+        s.collect_str(&format_args!("{}{}", sign, marker::FormattedDuration::<F>(&d, PhantomData)))
+    }
+
+    #[cfg(feature = "de")]
+    fn deserialize<'de, D>(d: D) -> Result<time::Duration, D::Error>
+        where D: Deserializer<'de>
+    {
+        d.deserialize_str(V::<F>(PhantomData))
+    }
+}
+
+#[cfg(feature = "de")]
+struct V<F>(PhantomData<F>);
+
+#[cfg(feature = "de")]
+impl<'de, F> Visitor<'de> for V<F>
+    where F: DurationFormat
+{
+    type Value = time::Duration;
+
+    fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        F::expecting(fmt)
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<time::Duration, E>
+        where E: Error
+    {
+        let (negative, s) = match v.strip_prefix('-') {
+            Some(s) => (true, s),
+            None => (false, v),
+        };
+        let d = F::parse(s).map_err(|e| marker::invalid_value(e, v, &self))?;
+        let d = time::Duration::try_from(d).map_err(|_| E::invalid_value(Unexpected::Str(v), &self))?;
+        Ok(if negative { -d } else { d })
+    }
+}
+
+#[cfg(all(test, feature = "ser", feature = "de"))]
+mod test {
+    use serde_json;
+    use time;
+    #[cfg(feature = "systemtime")]
+    use time::OffsetDateTime;
+
+    use De;
+    use marker::Iso8601;
+
+    #[cfg(feature = "systemtime")]
+    #[test]
+    fn offset_date_times() {
+        #[derive(Serialize, Deserialize)]
+        struct Foo {
+            #[serde(with = "super::offset_date_time")]
+            started: OffsetDateTime,
+            deadline: Option<De<OffsetDateTime>>,
+        }
+
+        // 2018-02-13T23:08:32.5Z
+        let t = OffsetDateTime::from_unix_timestamp_nanos(1_518_563_312_500_000_000).unwrap();
+        let json = r#"{"started":"2018-02-13T23:08:32.500000000Z","deadline":null}"#;
+        let foo = serde_json::from_str::<Foo>(json).unwrap();
+        assert_eq!(foo.started, t);
+        assert_eq!(serde_json::to_string(&foo).unwrap(), json);
+
+        let local = t.to_offset(time::UtcOffset::from_hms(9, 0, 0).unwrap());
+        let foo = Foo { started: local, deadline: Some(De::new(local)) };
+        let json = r#"{"started":"2018-02-13T23:08:32.500000000Z","deadline":"2018-02-13T23:08:32.500000000Z"}"#;
+        assert_eq!(serde_json::to_string(&foo).unwrap(), json);
+
+        let early = OffsetDateTime::from_unix_timestamp(-86400).unwrap();
+        assert!(serde_json::to_string(&Foo { started: early, deadline: None }).is_err());
+    }
+
+    #[test]
+    fn durations() {
+        #[derive(Serialize, Deserialize)]
+        struct Foo {
+            #[serde(with = "super::duration")]
+            d: time::Duration,
+        }
+
+        for &(json, secs) in &[(r#"{"d":"1h 30m"}"#, 5400), (r#"{"d":"-15s"}"#, -15), (r#"{"d":"0s"}"#, 0)] {
+            let foo = serde_json::from_str::<Foo>(json).unwrap();
+            assert_eq!(foo.d, time::Duration::seconds(secs), "{}", json);
+            assert_eq!(serde_json::to_string(&foo).unwrap(), json);
+        }
+        let foo = Foo { d: -time::Duration::MAX };
+        let json = serde_json::to_string(&foo).unwrap();
+        assert_eq!(serde_json::from_str::<Foo>(&json).unwrap().d, -time::Duration::MAX);
+
+        for json in &[r#"{"d":"--1s"}"#, r#"{"d":"1000000000000years"}"#, r#"{"d":15}"#] {
+            assert!(serde_json::from_str::<Foo>(json).is_err(), "{}", json);
+        }
+
+        let d = serde_json::from_str::<De<time::Duration, Iso8601>>(r#""-PT90S""#).unwrap();
+        assert_eq!(d.into_inner(), time::Duration::seconds(-90));
+    }
+}
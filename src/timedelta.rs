@@ -0,0 +1,182 @@
+//! Serialization of `Duration`s as Python `timedelta` strings.
+//!
+//! Durations are serialized in the form produced by Python's
+//! `str(timedelta)`, as in `1 day, 2:03:04.500000`, with the day count
+//! omitted when zero and the microseconds omitted when zero. Such strings
+//! are accepted when deserializing, along with fractions of one to nine
+//! digits. Negative `timedelta`s have no `Duration` equivalent and are
+//! rejected.
+//!
+//! `timedelta`s only have microsecond precision, so durations with a
+//! nonzero number of nanoseconds beyond that fail to serialize.
+//!
+//! # Examples
+//!
+//! ```
+//! extern crate serde_humantime;
+//! extern crate serde;
+//! #[macro_use]
+//! extern crate serde_derive;
+//!
+//! use std::time::Duration;
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Foo {
+//!     #[serde(with = "serde_humantime::timedelta")]
+//!     timeout: Duration,
+//! }
+//!
+//! # fn main() {}
+//! ```
+#[cfg(feature = "de")]
+use serde::de::{Deserializer, Visitor, Error, Unexpected};
+#[cfg(feature = "ser")]
+use serde::ser::{self, Serializer};
+use std::fmt;
+use std::time::Duration;
+
+const DAY: u64 = 86400;
+
+/// Serializes a `Duration` as a Python `timedelta` string.
+#[cfg(feature = "ser")]
+pub fn serialize<S>(d: &Duration, s: S) -> Result<S::Ok, S::Error>
+    where S: Serializer
+{
+    if !d.subsec_nanos().is_multiple_of(1000) {
+        return Err(ser::Error::custom("duration has sub-microsecond precision"));
+    }
+    s.collect_str(&Timedelta(*d))
+}
+
+/// Deserializes a `Duration` from a Python `timedelta` string.
+#[cfg(feature = "de")]
+pub fn deserialize<'de, D>(d: D) -> Result<Duration, D::Error>
+    where D: Deserializer<'de>
+{
+    d.deserialize_str(V)
+}
+
+#[cfg(feature = "ser")]
+struct Timedelta(Duration);
+
+#[cfg(feature = "ser")]
+impl fmt::Display for Timedelta {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        let secs = self.0.as_secs();
+        let days = secs / DAY;
+        match days {
+            0 => {}
+            1 => fmt.write_str("1 day, ")?,
+            _ => write!(fmt, "{} days, ", days)?,
+        }
+        write!(fmt, "{}:{:02}:{:02}", secs % DAY / 3600, secs % 3600 / 60, secs % 60)?;
+        let micros = self.0.subsec_micros();
+        if micros != 0 {
+            write!(fmt, ".{:06}", micros)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "de")]
+struct V;
+
+#[cfg(feature = "de")]
+impl<'de> Visitor<'de> for V {
+    type Value = Duration;
+
+    fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str("a Python timedelta string")
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Duration, E>
+        where E: Error
+    {
+        parse(v).ok_or_else(|| E::invalid_value(Unexpected::Str(v), &self))
+    }
+}
+
+#[cfg(feature = "de")]
+fn parse(s: &str) -> Option<Duration> {
+    let (days, rest) = match s.find(", ") {
+        Some(idx) => {
+            let days = s[..idx].strip_suffix(" days").or_else(|| s[..idx].strip_suffix(" day"))?;
+            (digits(days, 1, 20)?, &s[idx + 2..])
+        }
+        None => (0, s),
+    };
+
+    let mut parts = rest.splitn(3, ':');
+    let hours = digits(parts.next()?, 1, 2)?;
+    let minutes = digits(parts.next()?, 2, 2)?;
+    let seconds = parts.next()?;
+    let (seconds, fraction) = match seconds.find('.') {
+        Some(idx) => (&seconds[..idx], Some(&seconds[idx + 1..])),
+        None => (seconds, None),
+    };
+    let seconds = digits(seconds, 2, 2)?;
+    if hours >= 24 || minutes >= 60 || seconds >= 60 {
+        return None;
+    }
+
+    let nanos = match fraction {
+        Some(fraction) => {
+            digits(fraction, 1, 9)?;
+            format!("{:0<9}", fraction).parse().ok()?
+        }
+        None => 0,
+    };
+
+    let secs = days.checked_mul(DAY)?.checked_add(hours * 3600 + minutes * 60 + seconds)?;
+    Some(Duration::new(secs, nanos))
+}
+
+// parses a string of between `min` and `max` ASCII digits
+#[cfg(feature = "de")]
+fn digits(s: &str, min: usize, max: usize) -> Option<u64> {
+    if s.len() < min || s.len() > max || !s.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    s.parse().ok()
+}
+
+#[cfg(all(test, feature = "ser", feature = "de"))]
+mod test {
+    use serde_json;
+    use std::time::Duration;
+
+    use super::parse;
+
+    #[test]
+    fn parse_invalid() {
+        for s in &["", "1:02", "1:2:03", "24:00:00", "0:60:00", "0:00:60", "1 day 0:00:00",
+                   "-1 day, 23:59:59", "1 week, 0:00:00", "0:00:00.", "0:00:00.0000000001",
+                   "0:00:00.-5", "1 day, ", "100:00:00", "18446744073709551615 days, 0:00:00"] {
+            assert_eq!(parse(s), None, "{}", s);
+        }
+        assert_eq!(parse("0:00:00.5"), Some(Duration::from_millis(500)));
+        assert_eq!(parse("01:00:00"), Some(Duration::from_secs(3600)));
+    }
+
+    #[test]
+    fn round_trip() {
+        #[derive(Serialize, Deserialize)]
+        struct Foo {
+            #[serde(with = "super")]
+            time: Duration,
+        }
+
+        let cases = [(Duration::from_secs(0), "0:00:00"),
+                     (Duration::new(93784, 500_000_000), "1 day, 2:03:04.500000"),
+                     (Duration::new(2 * 86400 + 59, 1000), "2 days, 0:00:59.000001"),
+                     (Duration::from_secs(86400), "1 day, 0:00:00"),
+                     (Duration::from_secs(36000), "10:00:00")];
+        for &(dur, s) in &cases {
+            let json = serde_json::to_string(&Foo { time: dur }).unwrap();
+            assert_eq!(json, format!(r#"{{"time":"{}"}}"#, s));
+            assert_eq!(serde_json::from_str::<Foo>(&json).unwrap().time, dur);
+        }
+
+        assert!(serde_json::to_string(&Foo { time: Duration::new(0, 1) }).is_err());
+    }
+}
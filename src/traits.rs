@@ -0,0 +1,277 @@
+//! The types supported by the crate's generic functions.
+#[cfg(not(feature = "de"))]
+use serde::de;
+#[cfg(feature = "de")]
+use serde::de::{Deserialize, DeserializeOwned};
+use serde::de::Deserializer;
+#[cfg(not(feature = "ser"))]
+use serde::ser;
+#[cfg(feature = "ser")]
+use serde::ser::Serialize;
+use serde::ser::Serializer;
+use std::collections::{BTreeMap, HashMap};
+use std::hash::{BuildHasher, Hash};
+use std::time::Duration;
+#[cfg(feature = "systemtime")]
+use std::time::SystemTime;
+
+#[cfg(any(feature = "ser", feature = "de"))]
+use marker::{self, Human};
+
+/// A type which can be serialized and deserialized in a human readable form.
+///
+/// This trait is implemented for `Duration`, `SystemTime`, and `Option`s,
+/// `Vec`s and the values of `HashMap`s and `BTreeMap`s of those types, nested
+/// to any depth, like `Option<Vec<Duration>>` or
+/// `HashMap<String, Vec<SystemTime>>`.
+///
+/// Other crates can implement it for their own types, typically by
+/// delegating to one of those implementations, so that the types work with
+/// the crate root's `serialize` and `deserialize` functions, and within
+/// `Option`s and `Vec`s. Both methods exist whichever of the `ser` and `de`
+/// features are enabled, so implementations compile under any feature set.
+/// Without the `ser` or `de` feature, the crate's own implementations of the
+/// corresponding method return an error.
+///
+/// # Examples
+///
+/// ```
+/// extern crate serde_humantime;
+/// extern crate serde;
+/// #[macro_use]
+/// extern crate serde_derive;
+///
+/// use serde::{Deserializer, Serializer};
+/// use serde_humantime::HumanTime;
+/// use std::time::Duration;
+///
+/// struct Timeout(Duration);
+///
+/// impl HumanTime for Timeout {
+///     fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
+///         where S: Serializer
+///     {
+///         self.0.serialize(s)
+///     }
+///
+///     fn deserialize<'de, D>(d: D) -> Result<Timeout, D::Error>
+///         where D: Deserializer<'de>
+///     {
+///         <Duration as HumanTime>::deserialize(d).map(Timeout)
+///     }
+/// }
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Config {
+///     #[serde(with = "serde_humantime")]
+///     timeouts: Vec<Timeout>,
+/// }
+///
+/// # fn main() {}
+/// ```
+pub trait HumanTime: Sized {
+    /// Serializes the value.
+    #[cfg(feature = "ser")]
+    fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
+        where S: Serializer;
+
+    /// Serializes the value.
+    ///
+    /// Returns an error unless overridden, since the `ser` feature is
+    /// disabled.
+    #[cfg(not(feature = "ser"))]
+    fn serialize<S>(&self, _: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        Err(ser::Error::custom("serialization support is disabled"))
+    }
+
+    /// Deserializes a value.
+    #[cfg(feature = "de")]
+    fn deserialize<'de, D>(d: D) -> Result<Self, D::Error>
+        where D: Deserializer<'de>;
+
+    /// Deserializes a value.
+    ///
+    /// Returns an error unless overridden, since the `de` feature is
+    /// disabled.
+    #[cfg(not(feature = "de"))]
+    fn deserialize<'de, D>(_: D) -> Result<Self, D::Error>
+        where D: Deserializer<'de>
+    {
+        Err(de::Error::custom("deserialization support is disabled"))
+    }
+}
+
+impl HumanTime for Duration {
+    #[cfg(feature = "ser")]
+    fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        <Human as marker::Format<Duration>>::serialize(self, s)
+    }
+
+    #[cfg(feature = "de")]
+    fn deserialize<'de, D>(d: D) -> Result<Duration, D::Error>
+        where D: Deserializer<'de>
+    {
+        <Human as marker::Format<Duration>>::deserialize(d)
+    }
+}
+
+#[cfg(feature = "systemtime")]
+impl HumanTime for SystemTime {
+    #[cfg(feature = "ser")]
+    fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        <Human as marker::Format<SystemTime>>::serialize(self, s)
+    }
+
+    #[cfg(feature = "de")]
+    fn deserialize<'de, D>(d: D) -> Result<SystemTime, D::Error>
+        where D: Deserializer<'de>
+    {
+        <Human as marker::Format<SystemTime>>::deserialize(d)
+    }
+}
+
+impl<T> HumanTime for Option<T>
+    where T: HumanTime
+{
+    #[cfg(feature = "ser")]
+    fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        match *self {
+            Some(ref v) => s.serialize_some(&Ref(v)),
+            None => s.serialize_none(),
+        }
+    }
+
+    #[cfg(feature = "de")]
+    fn deserialize<'de, D>(d: D) -> Result<Option<T>, D::Error>
+        where D: Deserializer<'de>
+    {
+        Option::<Wrap<T>>::deserialize(d).map(|v| v.map(|v| v.0))
+    }
+}
+
+impl<T> HumanTime for Vec<T>
+    where T: HumanTime
+{
+    #[cfg(feature = "ser")]
+    fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        s.collect_seq(self.iter().map(Ref))
+    }
+
+    #[cfg(feature = "de")]
+    fn deserialize<'de, D>(d: D) -> Result<Vec<T>, D::Error>
+        where D: Deserializer<'de>
+    {
+        Vec::<Wrap<T>>::deserialize(d).map(|v| v.into_iter().map(|v| v.0).collect())
+    }
+}
+
+impl<K, V, H> HumanTime for HashMap<K, V, H>
+    where K: MapKey + Eq + Hash,
+          V: HumanTime,
+          H: BuildHasher + Default
+{
+    #[cfg(feature = "ser")]
+    fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        s.collect_map(self.iter().map(|(k, v)| (k, Ref(v))))
+    }
+
+    #[cfg(feature = "de")]
+    fn deserialize<'de, D>(d: D) -> Result<HashMap<K, V, H>, D::Error>
+        where D: Deserializer<'de>
+    {
+        HashMap::<K, Wrap<V>, H>::deserialize(d)
+            .map(|m| m.into_iter().map(|(k, v)| (k, v.0)).collect())
+    }
+}
+
+impl<K, V> HumanTime for BTreeMap<K, V>
+    where K: MapKey + Ord,
+          V: HumanTime
+{
+    #[cfg(feature = "ser")]
+    fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        s.collect_map(self.iter().map(|(k, v)| (k, Ref(v))))
+    }
+
+    #[cfg(feature = "de")]
+    fn deserialize<'de, D>(d: D) -> Result<BTreeMap<K, V>, D::Error>
+        where D: Deserializer<'de>
+    {
+        BTreeMap::<K, Wrap<V>>::deserialize(d)
+            .map(|m| m.into_iter().map(|(k, v)| (k, v.0)).collect())
+    }
+}
+
+/// The keys of maps supported by `HumanTime` and `marker::Format`.
+///
+/// Keys must be `Serialize` with the `ser` feature and `DeserializeOwned`
+/// with the `de` feature, so serialize-only builds accept keys like `&str`.
+/// This trait is implemented for every such type.
+#[cfg(all(feature = "ser", feature = "de"))]
+pub trait MapKey: Serialize + DeserializeOwned {}
+
+#[cfg(all(feature = "ser", feature = "de"))]
+impl<T> MapKey for T where T: Serialize + DeserializeOwned {}
+
+/// The keys of maps supported by `HumanTime` and `marker::Format`.
+#[cfg(all(feature = "ser", not(feature = "de")))]
+pub trait MapKey: Serialize {}
+
+#[cfg(all(feature = "ser", not(feature = "de")))]
+impl<T> MapKey for T where T: Serialize {}
+
+/// The keys of maps supported by `HumanTime` and `marker::Format`.
+#[cfg(all(not(feature = "ser"), feature = "de"))]
+pub trait MapKey: DeserializeOwned {}
+
+#[cfg(all(not(feature = "ser"), feature = "de"))]
+impl<T> MapKey for T where T: DeserializeOwned {}
+
+/// The keys of maps supported by `HumanTime` and `marker::Format`.
+#[cfg(not(any(feature = "ser", feature = "de")))]
+pub trait MapKey {}
+
+#[cfg(not(any(feature = "ser", feature = "de")))]
+impl<T> MapKey for T {}
+
+#[cfg(feature = "de")]
+pub(crate) struct Wrap<T>(pub(crate) T);
+
+#[cfg(feature = "de")]
+impl<'de, T> Deserialize<'de> for Wrap<T>
+    where T: HumanTime
+{
+    fn deserialize<D>(d: D) -> Result<Wrap<T>, D::Error>
+        where D: Deserializer<'de>
+    {
+        T::deserialize(d).map(Wrap)
+    }
+}
+
+#[cfg(feature = "ser")]
+pub(crate) struct Ref<'a, T: 'a>(pub(crate) &'a T);
+
+#[cfg(feature = "ser")]
+impl<'a, T> Serialize for Ref<'a, T>
+    where T: HumanTime
+{
+    fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        self.0.serialize(s)
+    }
+}
@@ -0,0 +1,159 @@
+//! Serialization of `SystemTime`s as millisecond Unix timestamps.
+//!
+//! Timestamps are serialized as an `i64` count of milliseconds since the Unix
+//! epoch, as returned by JavaScript's `Date.now()` and used by Kafka record
+//! timestamps. Fractions of a millisecond are discarded, rounding towards
+//! the past, so timestamps before the epoch are negative. In human readable
+//! formats like JSON, RFC 3339 timestamps as produced by the crate root are
+//! also accepted when deserializing.
+//!
+//! # Examples
+//!
+//! ```
+//! extern crate serde_humantime;
+//! extern crate serde;
+//! #[macro_use]
+//! extern crate serde_derive;
+//!
+//! use std::time::SystemTime;
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Record {
+//!     #[serde(with = "serde_humantime::unix_millis")]
+//!     timestamp: SystemTime,
+//! }
+//!
+//! # fn main() {}
+//! ```
+#[cfg(feature = "de")]
+use serde::de::{Deserializer, Visitor, Error, Unexpected};
+#[cfg(feature = "ser")]
+use serde::ser::{self, Serializer};
+#[cfg(feature = "de")]
+use std::fmt;
+#[cfg(feature = "de")]
+use std::time::Duration;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[cfg(feature = "de")]
+use rfc3339;
+
+/// Returns the number of milliseconds from the Unix epoch to a timestamp,
+/// rounded towards the past.
+#[cfg(feature = "ser")]
+pub(crate) fn to_millis(t: SystemTime) -> i128 {
+    match t.duration_since(UNIX_EPOCH) {
+        Ok(d) => d.as_millis() as i128,
+        Err(e) => {
+            let d = e.duration();
+            let millis = d.as_millis() as i128;
+            if d.subsec_nanos().is_multiple_of(1_000_000) {
+                -millis
+            } else {
+                -millis - 1
+            }
+        }
+    }
+}
+
+/// Returns the timestamp a number of milliseconds before or after the Unix
+/// epoch, or `None` if it can't be represented.
+#[cfg(feature = "de")]
+pub(crate) fn from_millis(negative: bool, millis: u64) -> Option<SystemTime> {
+    let d = Duration::from_millis(millis);
+    if negative {
+        UNIX_EPOCH.checked_sub(d)
+    } else {
+        UNIX_EPOCH.checked_add(d)
+    }
+}
+
+/// Serializes a `SystemTime` as an `i64` number of milliseconds since the
+/// Unix epoch.
+///
+/// Returns an error if the number of milliseconds doesn't fit in an `i64`.
+#[cfg(feature = "ser")]
+pub fn serialize<S>(t: &SystemTime, s: S) -> Result<S::Ok, S::Error>
+    where S: Serializer
+{
+    let millis = to_millis(*t);
+    if millis < i64::MIN as i128 || millis > i64::MAX as i128 {
+        return Err(ser::Error::custom("timestamp is out of range"));
+    }
+    s.serialize_i64(millis as i64)
+}
+
+/// Deserializes a `SystemTime` from a number of milliseconds since the Unix
+/// epoch or an RFC 3339 timestamp.
+#[cfg(feature = "de")]
+pub fn deserialize<'de, D>(d: D) -> Result<SystemTime, D::Error>
+    where D: Deserializer<'de>
+{
+    if d.is_human_readable() {
+        d.deserialize_any(V)
+    } else {
+        d.deserialize_i64(V)
+    }
+}
+
+#[cfg(feature = "de")]
+struct V;
+
+#[cfg(feature = "de")]
+impl<'de> Visitor<'de> for V {
+    type Value = SystemTime;
+
+    fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str("a number of milliseconds since the Unix epoch or an RFC 3339 timestamp")
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<SystemTime, E>
+        where E: Error
+    {
+        from_millis(false, v).ok_or_else(|| E::invalid_value(Unexpected::Unsigned(v), &self))
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<SystemTime, E>
+        where E: Error
+    {
+        from_millis(v < 0, v.unsigned_abs()).ok_or_else(|| E::invalid_value(Unexpected::Signed(v), &self))
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<SystemTime, E>
+        where E: Error
+    {
+        rfc3339::parse(v).map_err(|_| E::invalid_value(Unexpected::Str(v), &self))
+    }
+}
+
+#[cfg(all(test, feature = "ser", feature = "de"))]
+mod test {
+    use serde_json;
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    #[derive(Serialize, Deserialize)]
+    struct Foo {
+        #[serde(with = "super")]
+        time: SystemTime,
+    }
+
+    #[test]
+    fn round_trip() {
+        // 2018-02-13T23:08:32.500Z
+        let time = UNIX_EPOCH + Duration::from_millis(1518563312500);
+        for json in &[r#"{"time":1518563312500}"#, r#"{"time":"2018-02-13T23:08:32.500000000Z"}"#] {
+            assert_eq!(serde_json::from_str::<Foo>(json).unwrap().time, time, "{}", json);
+        }
+        let json = serde_json::to_string(&Foo { time: time + Duration::new(0, 999_999) }).unwrap();
+        assert_eq!(json, r#"{"time":1518563312500}"#);
+
+        let time = UNIX_EPOCH - Duration::new(1, 500_000);
+        let json = serde_json::to_string(&Foo { time }).unwrap();
+        assert_eq!(json, r#"{"time":-1001}"#);
+        assert_eq!(serde_json::from_str::<Foo>(&json).unwrap().time, time - Duration::new(0, 500_000));
+
+        for json in &[r#"{"time":1.5}"#, r#"{"time":"1518563312500"}"#, r#"{"time":null}"#] {
+            assert!(serde_json::from_str::<Foo>(json).is_err(), "{}", json);
+        }
+    }
+}
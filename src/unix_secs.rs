@@ -0,0 +1,125 @@
+//! Serialization of `SystemTime`s as Unix timestamps.
+//!
+//! Timestamps are serialized as a `u64` count of seconds since the Unix
+//! epoch, as in `epoch::unix`, the form many JSON APIs exchange. In human
+//! readable formats like JSON, RFC 3339 timestamps as produced by the crate
+//! root are also accepted when deserializing, so fields can move between the
+//! two forms.
+//!
+//! # Examples
+//!
+//! ```
+//! extern crate serde_humantime;
+//! extern crate serde;
+//! #[macro_use]
+//! extern crate serde_derive;
+//!
+//! use std::time::SystemTime;
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Token {
+//!     #[serde(with = "serde_humantime::unix_secs")]
+//!     expires_at: SystemTime,
+//! }
+//!
+//! # fn main() {}
+//! ```
+#[cfg(feature = "de")]
+use serde::de::{Deserializer, Visitor, Error, Unexpected};
+#[cfg(feature = "ser")]
+use serde::ser::Serializer;
+#[cfg(feature = "de")]
+use std::fmt;
+#[cfg(feature = "de")]
+use std::time::{Duration, UNIX_EPOCH};
+use std::time::SystemTime;
+
+use epoch;
+#[cfg(feature = "de")]
+use rfc3339;
+
+/// Serializes a `SystemTime` as a `u64` number of seconds since the Unix
+/// epoch, discarding any fraction of a second.
+///
+/// Returns an error if the timestamp is before the epoch.
+#[cfg(feature = "ser")]
+pub fn serialize<S>(t: &SystemTime, s: S) -> Result<S::Ok, S::Error>
+    where S: Serializer
+{
+    epoch::unix::serialize(t, s)
+}
+
+/// Deserializes a `SystemTime` from a number of seconds since the Unix epoch
+/// or an RFC 3339 timestamp.
+#[cfg(feature = "de")]
+pub fn deserialize<'de, D>(d: D) -> Result<SystemTime, D::Error>
+    where D: Deserializer<'de>
+{
+    if d.is_human_readable() {
+        d.deserialize_any(V)
+    } else {
+        epoch::unix::deserialize(d)
+    }
+}
+
+#[cfg(feature = "de")]
+struct V;
+
+#[cfg(feature = "de")]
+impl<'de> Visitor<'de> for V {
+    type Value = SystemTime;
+
+    fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str("a Unix timestamp or an RFC 3339 timestamp")
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<SystemTime, E>
+        where E: Error
+    {
+        UNIX_EPOCH
+            .checked_add(Duration::from_secs(v))
+            .ok_or_else(|| E::invalid_value(Unexpected::Unsigned(v), &self))
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<SystemTime, E>
+        where E: Error
+    {
+        if v < 0 {
+            return Err(E::invalid_value(Unexpected::Signed(v), &self));
+        }
+        self.visit_u64(v as u64)
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<SystemTime, E>
+        where E: Error
+    {
+        rfc3339::parse(v).map_err(|_| E::invalid_value(Unexpected::Str(v), &self))
+    }
+}
+
+#[cfg(all(test, feature = "ser", feature = "de"))]
+mod test {
+    use serde_json;
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    #[derive(Serialize, Deserialize)]
+    struct Foo {
+        #[serde(with = "super")]
+        time: SystemTime,
+    }
+
+    #[test]
+    fn round_trip() {
+        // 2018-02-13T23:08:32Z
+        let time = UNIX_EPOCH + Duration::from_secs(1518563312);
+        for json in &[r#"{"time":1518563312}"#, r#"{"time":"2018-02-13T23:08:32Z"}"#] {
+            assert_eq!(serde_json::from_str::<Foo>(json).unwrap().time, time, "{}", json);
+        }
+        let json = serde_json::to_string(&Foo { time: time + Duration::from_millis(500) }).unwrap();
+        assert_eq!(json, r#"{"time":1518563312}"#);
+
+        for json in &[r#"{"time":-1}"#, r#"{"time":1.5}"#, r#"{"time":"1518563312"}"#, r#"{"time":null}"#] {
+            assert!(serde_json::from_str::<Foo>(json).is_err(), "{}", json);
+        }
+    }
+}
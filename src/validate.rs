@@ -0,0 +1,181 @@
+//! Validation of duration and timestamp strings.
+//!
+//! Parsing stops at the first problem in its input, which makes for a poor
+//! experience in tools like configuration linters and editor plugins.
+//! `validate_str` instead reports every problem it finds, each with the byte
+//! range of the input it applies to.
+//!
+//! # Examples
+//!
+//! ```
+//! use serde_humantime::ErrorKind;
+//! use serde_humantime::validate::{validate_str, Target};
+//!
+//! let problems = validate_str("1 hour 30 mins 5 parsecs 99999999999999999999s", Target::Duration);
+//! assert_eq!(problems.len(), 2);
+//! assert_eq!(problems[0].kind(), ErrorKind::UnknownUnit);
+//! assert_eq!(problems[0].span(), 17..24);
+//! assert_eq!(problems[1].kind(), ErrorKind::OutOfRange);
+//! assert_eq!(problems[1].span(), 25..45);
+//!
+//! assert!(validate_str("1h 30m", Target::Duration).is_empty());
+//! ```
+use std::ops::Range;
+
+use parse::{self, ParseOptions};
+#[cfg(feature = "systemtime")]
+use rfc3339;
+use ErrorKind;
+
+/// The kind of value a string is validated as.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Target {
+    /// A duration, as parsed by `parse_duration`.
+    Duration,
+    /// An RFC 3339 timestamp, as parsed by the `rfc3339` module.
+    #[cfg(feature = "systemtime")]
+    Timestamp,
+}
+
+/// A problem found in a string.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Problem {
+    kind: ErrorKind,
+    span: Range<usize>,
+}
+
+impl Problem {
+    /// Returns the kind of the problem.
+    pub fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+
+    /// Returns the byte range of the input the problem applies to.
+    pub fn span(&self) -> Range<usize> {
+        self.span.clone()
+    }
+}
+
+/// Returns every problem found in a string, in order of position.
+///
+/// A string is valid if and only if no problems are returned. Timestamps are
+/// validated as a whole, so at most one problem is reported for them.
+pub fn validate_str(s: &str, target: Target) -> Vec<Problem> {
+    match target {
+        Target::Duration => duration(s),
+        #[cfg(feature = "systemtime")]
+        Target::Timestamp => match rfc3339::parse(s) {
+            Ok(_) => vec![],
+            Err(e) => vec![Problem { kind: e.kind(), span: 0..s.len() }],
+        },
+    }
+}
+
+fn duration(s: &str) -> Vec<Problem> {
+    let mut problems = vec![];
+    if s.trim().is_empty() {
+        problems.push(Problem { kind: ErrorKind::Empty, span: 0..s.len() });
+        return problems;
+    }
+
+    let options = ParseOptions::new();
+    let offset = |rest: &str| s.len() - rest.len();
+    // the running total in seconds and nanoseconds, until it overflows
+    let mut total = Some((0u64, 0u64));
+    let mut rest = s.trim_start();
+    while !rest.is_empty() {
+        let start = offset(rest);
+        let after;
+        let c = rest.chars().next().unwrap();
+        if c.is_ascii_digit() {
+            let end = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+            let n = rest[..end].parse::<u64>().ok();
+            if n.is_none() {
+                problems.push(Problem { kind: ErrorKind::OutOfRange, span: start..start + end });
+            }
+            rest = rest[end..].trim_start();
+
+            let unit_start = offset(rest);
+            let end = rest.find(|c: char| !c.is_ascii_alphabetic()).unwrap_or(rest.len());
+            match parse::unit(&rest[..end], &options) {
+                Some((unit_secs, unit_nanos)) => {
+                    // overflow is only reported once, and only for a number
+                    // which fits on its own
+                    if let (Some(n), Some((secs, nanos))) = (n, total) {
+                        let secs = n.checked_mul(unit_secs).and_then(|v| secs.checked_add(v));
+                        let nanos = n.checked_mul(unit_nanos).and_then(|v| nanos.checked_add(v));
+                        total = secs.and_then(|secs| nanos.map(|nanos| (secs, nanos)));
+                        if total.is_none() {
+                            problems.push(Problem {
+                                kind: ErrorKind::OutOfRange,
+                                span: start..unit_start + end,
+                            });
+                        }
+                    }
+                }
+                None => {
+                    let span = if end == 0 { start..unit_start } else { unit_start..unit_start + end };
+                    problems.push(Problem { kind: ErrorKind::UnknownUnit, span });
+                }
+            }
+            after = &rest[end..];
+        } else if c.is_alphabetic() {
+            let end = rest.find(|c: char| !c.is_alphabetic()).unwrap_or(rest.len());
+            problems.push(Problem { kind: ErrorKind::InvalidNumber, span: start..start + end });
+            after = &rest[end..];
+        } else {
+            problems.push(Problem { kind: ErrorKind::InvalidCharacter, span: start..start + c.len_utf8() });
+            after = &rest[c.len_utf8()..];
+        }
+
+        rest = parse::separator(after);
+        // a separator must be followed by another component
+        if rest.is_empty() && !after.trim().is_empty() {
+            let start = offset(after.trim_start());
+            problems.push(Problem { kind: ErrorKind::TrailingInput, span: start..s.len() });
+        }
+    }
+
+    if let Some((secs, nanos)) = total {
+        if secs.checked_add(nanos / 1_000_000_000).is_none() {
+            problems.push(Problem { kind: ErrorKind::OutOfRange, span: 0..s.len() });
+        }
+    }
+    problems
+}
+
+#[cfg(test)]
+mod test {
+    use parse::parse_duration;
+    use super::*;
+
+    fn kinds(s: &str) -> Vec<(ErrorKind, Range<usize>)> {
+        validate_str(s, Target::Duration).into_iter().map(|p| (p.kind(), p.span())).collect()
+    }
+
+    #[test]
+    fn duration() {
+        assert_eq!(kinds(""), [(ErrorKind::Empty, 0..0)]);
+        assert_eq!(kinds("5 fortnights, 3 eons"),
+                   [(ErrorKind::UnknownUnit, 2..12), (ErrorKind::UnknownUnit, 16..20)]);
+        assert_eq!(kinds("1h 30"), [(ErrorKind::UnknownUnit, 3..5)]);
+        assert_eq!(kinds("h 1m $ 2s"), [(ErrorKind::InvalidNumber, 0..1), (ErrorKind::InvalidCharacter, 5..6)]);
+        assert_eq!(kinds("18446744073709551615s 1s"), [(ErrorKind::OutOfRange, 22..24)]);
+        assert_eq!(kinds("1h, "), [(ErrorKind::TrailingInput, 2..4)]);
+        assert_eq!(kinds("99999999999999999999 days"), [(ErrorKind::OutOfRange, 0..20)]);
+
+        for s in &["1h 30m", "15 seconds", "1 day, 2 hours and 3 minutes", "1h30m", "0s", "1ns",
+                   "18446744073709551615s", "", "1h foo", "1h,", "1h and", "1h, and 2m", "-1s", "1e3s", "1h 1x"] {
+            assert_eq!(validate_str(s, Target::Duration).is_empty(), parse_duration(s).is_ok(), "{}", s);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "systemtime")]
+    fn timestamp() {
+        assert!(validate_str("2018-02-13T23:08:32Z", Target::Timestamp).is_empty());
+        let problems = validate_str("2018-02-30T23:08:32Z", Target::Timestamp);
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].span(), 0..20);
+    }
+}
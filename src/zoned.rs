@@ -0,0 +1,140 @@
+//! Deserialization of `SystemTime`s from local times in named timezones.
+//!
+//! This module is only available with the `chrono-tz` feature. It accepts
+//! timestamps of the form `2024-03-10 09:00 America/New_York`, where the
+//! date and time are the wall clock time in the given IANA timezone. Seconds
+//! and fractional seconds are optional. RFC 3339 timestamps are accepted as
+//! well.
+//!
+//! When a wall clock time occurs twice because of a daylight saving time
+//! transition, the earlier instant is used. Wall clock times skipped by a
+//! transition are rejected.
+//!
+//! Timestamps are serialized as RFC 3339 timestamps in UTC, since the
+//! timezone is not retained.
+//!
+//! # Examples
+//!
+//! ```
+//! extern crate serde_humantime;
+//! extern crate serde;
+//! #[macro_use]
+//! extern crate serde_derive;
+//!
+//! use std::time::SystemTime;
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Maintenance {
+//!     #[serde(with = "serde_humantime::zoned")]
+//!     start: SystemTime,
+//! }
+//!
+//! # fn main() {}
+//! ```
+#[cfg(feature = "de")]
+use chrono::{LocalResult, NaiveDate, TimeZone};
+#[cfg(feature = "de")]
+use chrono_tz::Tz;
+#[cfg(feature = "de")]
+use serde::de::{Deserializer, Visitor, Error, Unexpected};
+#[cfg(feature = "ser")]
+use serde::ser::Serializer;
+#[cfg(feature = "de")]
+use std::fmt;
+use std::time::SystemTime;
+
+#[cfg(feature = "de")]
+use civil;
+use rfc3339;
+
+/// Serializes a `SystemTime` as an RFC 3339 timestamp.
+#[cfg(feature = "ser")]
+pub fn serialize<S>(t: &SystemTime, s: S) -> Result<S::Ok, S::Error>
+    where S: Serializer
+{
+    rfc3339::serialize(t, s)
+}
+
+/// Deserializes a `SystemTime` from a local time in a named timezone.
+#[cfg(feature = "de")]
+pub fn deserialize<'de, D>(d: D) -> Result<SystemTime, D::Error>
+    where D: Deserializer<'de>
+{
+    d.deserialize_str(V)
+}
+
+#[cfg(feature = "de")]
+struct V;
+
+#[cfg(feature = "de")]
+impl<'de> Visitor<'de> for V {
+    type Value = SystemTime;
+
+    fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str("a timestamp with a timezone")
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<SystemTime, E>
+        where E: Error
+    {
+        parse(v).ok_or_else(|| E::invalid_value(Unexpected::Str(v), &self))
+    }
+}
+
+#[cfg(feature = "de")]
+fn parse(s: &str) -> Option<SystemTime> {
+    let idx = match s.rfind(' ') {
+        Some(idx) => idx,
+        None => return rfc3339::parse(s).ok(),
+    };
+    let tz = match s[idx + 1..].parse::<Tz>() {
+        Ok(tz) => tz,
+        Err(_) => return rfc3339::parse(s).ok(),
+    };
+
+    let dt = civil::parse_datetime(&s[..idx])?;
+    let naive = NaiveDate::from_ymd_opt(dt.year as i32, dt.month, dt.day)?
+        .and_hms_nano_opt(dt.hour, dt.minute, dt.second, dt.nanos)?;
+    match tz.from_local_datetime(&naive) {
+        LocalResult::Single(t) | LocalResult::Ambiguous(t, _) => Some(SystemTime::from(t)),
+        LocalResult::None => None,
+    }
+}
+
+#[cfg(all(test, feature = "de"))]
+mod test {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use super::parse;
+
+    #[test]
+    fn zones() {
+        // EST
+        assert_eq!(parse("2024-03-09 09:00 America/New_York"),
+                   Some(UNIX_EPOCH + Duration::from_secs(1709992800)));
+        // EDT
+        assert_eq!(parse("2024-03-10T09:00:00.5 America/New_York"),
+                   Some(UNIX_EPOCH + Duration::new(1710075600, 500_000_000)));
+        assert_eq!(parse("2024-03-10 09:00 UTC"),
+                   Some(UNIX_EPOCH + Duration::from_secs(1710061200)));
+        assert_eq!(parse("2024-03-10T09:00:00Z"),
+                   Some(UNIX_EPOCH + Duration::from_secs(1710061200)));
+    }
+
+    #[test]
+    fn transitions() {
+        // skipped by the spring forward transition
+        assert_eq!(parse("2024-03-10 02:30 America/New_York"), None);
+        // repeated by the fall back transition, so the EDT instant is used
+        assert_eq!(parse("2024-11-03 01:30 America/New_York"),
+                   Some(UNIX_EPOCH + Duration::from_secs(1730611800)));
+    }
+
+    #[test]
+    fn invalid() {
+        for s in &["2024-03-10 09:00 Mars/Olympus_Mons", "2024-03-10 America/New_York",
+                   "09:00 America/New_York", ""] {
+            assert_eq!(parse(s), None, "{}", s);
+        }
+    }
+}
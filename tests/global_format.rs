@@ -0,0 +1,35 @@
+// `set_global_format` changes the output of every test in the process, so
+// it's tested in a binary of its own.
+#![cfg(feature = "ser")]
+
+extern crate serde_humantime;
+extern crate serde;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde_json;
+
+use serde_humantime::format::{set_global_format, with_format, FormatOptions, Units};
+use std::time::Duration;
+
+#[derive(Serialize)]
+struct Foo {
+    #[serde(with = "serde_humantime")]
+    timeout: Duration,
+}
+
+#[test]
+fn global_format() {
+    let foo = Foo { timeout: Duration::from_secs(5400) };
+    assert_eq!(serde_json::to_string(&foo).unwrap(), r#"{"timeout":"1h 30m"}"#);
+
+    set_global_format(FormatOptions::new().units(Units::Long));
+    assert_eq!(serde_json::to_string(&foo).unwrap(), r#"{"timeout":"1 hour 30 minutes"}"#);
+    let short = with_format(FormatOptions::new().units(Units::Short),
+                            || serde_json::to_string(&foo).unwrap());
+    assert_eq!(short, r#"{"timeout":"1h 30m"}"#);
+    assert_eq!(serde_json::to_string(&foo).unwrap(), r#"{"timeout":"1 hour 30 minutes"}"#);
+
+    // restore the default
+    set_global_format(FormatOptions::new());
+    assert_eq!(serde_json::to_string(&foo).unwrap(), r#"{"timeout":"1h 30m"}"#);
+}
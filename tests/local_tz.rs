@@ -0,0 +1,43 @@
+// The `local` module depends on the `TZ` environment variable, which can't be
+// changed safely while other tests run, so it's tested in a binary of its own.
+#![cfg(all(feature = "tzdb", feature = "ser", feature = "de"))]
+
+extern crate serde_humantime;
+extern crate serde;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde_json;
+
+use std::env;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+#[derive(Serialize, Deserialize)]
+struct Foo {
+    #[serde(with = "serde_humantime::local")]
+    time: SystemTime,
+}
+
+fn parse(s: &str) -> Option<SystemTime> {
+    serde_json::from_str::<Foo>(&format!(r#"{{"time":"{}"}}"#, s)).ok().map(|f| f.time)
+}
+
+#[test]
+fn local() {
+    env::set_var("TZ", "EST5EDT,M3.2.0,M11.1.0");
+
+    let edt = UNIX_EPOCH + Duration::from_secs(1710075600);
+    let json = serde_json::to_string(&Foo { time: edt }).unwrap();
+    assert_eq!(json, r#"{"time":"2024-03-10T09:00:00-04:00"}"#);
+    assert_eq!(serde_json::from_str::<Foo>(&json).unwrap().time, edt);
+    assert_eq!(parse("2024-03-10 09:00"), Some(edt));
+
+    let est = UNIX_EPOCH + Duration::new(1709992800, 5);
+    let json = serde_json::to_string(&Foo { time: est }).unwrap();
+    assert_eq!(json, r#"{"time":"2024-03-09T09:00:00.000000005-05:00"}"#);
+    assert_eq!(serde_json::from_str::<Foo>(&json).unwrap().time, est);
+
+    assert_eq!(parse("2024-03-10 02:30"), None);
+    assert_eq!(parse("2024-11-03 01:30"), Some(UNIX_EPOCH + Duration::from_secs(1730611800)));
+    assert_eq!(parse("2024-03-10T13:00:00Z"), Some(edt));
+    assert_eq!(parse("2024-03-10T14:30:00+01:30"), Some(edt));
+}